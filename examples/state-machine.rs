@@ -38,9 +38,8 @@ use sdre_rust_adsb_parser::{
     decoders::{aircraftjson::AircraftJSON, helpers::cpr_calculators::Position, json::JSONMessage},
     error_handling::deserialization_error::DeserializationError,
     helpers::{
-        encode_adsb_beast_input::{ADSBBeastFrames, format_adsb_beast_frames_from_bytes},
-        encode_adsb_json_input::format_adsb_json_frames_from_string,
-        encode_adsb_raw_input::{ADSBRawFrames, format_adsb_raw_frames_from_bytes},
+        encode_adsb_beast_input::ADSBBeastFramer, encode_adsb_json_input::ADSBJSONFramer,
+        encode_adsb_raw_input::ADSBRawFramer,
     },
     state_machine::state::{
         Machine, MachineBuilder, ProcessMessageType, expire_planes, generate_aircraft_json,
@@ -369,7 +368,7 @@ async fn process_beast_frames(
 
     info!("Connected to {}", ip);
     let mut buffer: [u8; 4096] = [0u8; 4096];
-    let mut left_over: Vec<u8> = Vec::new();
+    let mut framer = ADSBBeastFramer::new();
 
     while let Ok(n) = stream.read(&mut buffer).await {
         if n == 0 {
@@ -378,33 +377,21 @@ async fn process_beast_frames(
         }
         trace!("Raw frame: {:02X?}", buffer[0..n].to_vec());
 
-        // append the left over bytes to the buffer
-        let processed_buffer: Vec<u8> = [&left_over[..], &buffer[0..n]].concat();
-        let frames: ADSBBeastFrames = format_adsb_beast_frames_from_bytes(&processed_buffer);
-
-        if !frames.errors.is_empty() {
-            for error in frames.errors {
-                error!("Error decoding: {}", error);
-            }
-
-            info!("Full buffer: {:02X?}", processed_buffer);
-            info!("Left over before: {:02X?}", left_over);
-            info!("Left over after: {:02X?}", frames.left_over);
-            info!("Frames: {:02X?}", frames.frames);
-        }
-
-        left_over = frames.left_over;
-
-        trace!("Pre-processed: {:02X?}", frames.frames);
-
-        for frame in frames.frames {
+        framer.push(&buffer[0..n]);
+        while let Some(frame) = framer.next_frame() {
             debug!("Decoding: {:02X?}", frame);
 
             sender_channel
-                .send(ProcessMessageType::AsVecU8(frame))
+                .send(ProcessMessageType::AsVecU8(frame.to_vec()))
                 .await
                 .unwrap();
         }
+
+        trace!(
+            "Dropped {} bytes in {} resyncs",
+            framer.bytes_dropped(),
+            framer.resyncs()
+        );
     }
     Ok(())
 }
@@ -433,7 +420,7 @@ async fn process_raw_frames(
 
     info!("Connected to {}", ip);
     let mut buffer: [u8; 4096] = [0u8; 4096];
-    let mut left_over: Vec<u8> = Vec::new();
+    let mut framer = ADSBRawFramer::new();
 
     while let Ok(n) = stream.read(&mut buffer).await {
         if n == 0 {
@@ -442,33 +429,21 @@ async fn process_raw_frames(
         }
         trace!("Raw frame: {:02X?}", buffer[0..n].to_vec());
 
-        // append the left over bytes to the buffer
-        let processed_buffer: Vec<u8> = [&left_over[..], &buffer[0..n]].concat();
-        let frames: ADSBRawFrames = format_adsb_raw_frames_from_bytes(&processed_buffer);
-
-        if !frames.errors.is_empty() {
-            for error in frames.errors {
-                error!("Error decoding: {}", error);
-            }
-
-            info!("Full buffer: {:02X?}", processed_buffer);
-            info!("Left over before: {:02X?}", left_over);
-            info!("Left over after: {:02X?}", frames.left_over);
-            info!("Frames: {:02X?}", frames.frames);
-        }
-
-        left_over = frames.left_over;
-
-        trace!("Pre-processed: {:02X?}", frames.frames);
-
-        for frame in frames.frames {
+        framer.push(&buffer[0..n]);
+        while let Some(frame) = framer.next_frame() {
             debug!("Decoding: {:02X?}", frame);
 
             sender_channel
-                .send(ProcessMessageType::AsVecU8(frame))
+                .send(ProcessMessageType::AsVecU8(frame.to_vec()))
                 .await
                 .unwrap();
         }
+
+        trace!(
+            "Dropped {} bytes in {} resyncs",
+            framer.bytes_dropped(),
+            framer.resyncs()
+        );
     }
     Ok(())
 }
@@ -579,7 +554,7 @@ async fn process_json_from_tcp(
     info!("Connected to {}", ip);
 
     let mut buffer: [u8; 8000] = [0u8; 8000];
-    let mut left_over = String::new();
+    let mut framer = ADSBJSONFramer::new();
 
     while let Ok(n) = stream.read(&mut buffer).await {
         if n == 0 {
@@ -587,32 +562,10 @@ async fn process_json_from_tcp(
             continue;
         }
         trace!("Raw frame: {:02X?}", buffer[0..n].to_vec());
-        // convert the bytes to a string
-        let mut json_string: String = String::from_utf8_lossy(&buffer[0..n]).to_string();
-        trace!("Pre-processed: {}", json_string);
-
-        // if we have a left over string, prepend it to the json_string
-        if !left_over.is_empty() {
-            json_string = format!("{}{}", left_over, json_string);
-        }
-
-        let frames = format_adsb_json_frames_from_string(&json_string);
-
-        trace!("Pre-processed with left overs: {:02X?}", frames.frames);
-
-        left_over = frames.left_over;
-
-        if !frames.errors.is_empty() {
-            for error in frames.errors {
-                error!("Error decoding: {}", error);
-            }
-
-            info!("Full buffer: {}", json_string);
-            info!("Left over: {}", left_over);
-            info!("Frames: {:?}", frames.frames);
-        }
 
-        for frame in frames.frames {
+        framer.push(&buffer[0..n]);
+        while let Some(frame) = framer.next_frame() {
+            let frame = String::from_utf8_lossy(&frame);
             debug!("Decoding: {}", frame);
 
             let message: Result<ADSBMessage, DeserializationError> = frame.decode_message();
@@ -5,8 +5,11 @@
 // https://opensource.org/licenses/MIT.
 
 use crate::MessageResult;
+use crate::error_handling::adsb_beast_error::ADSBBeastError;
+use crate::helpers::encode_adsb_beast_output::{encode_beast_frame, escape_beast_frame};
 //use deku::bitvec::{BitSlice, Msb0};
-use deku::no_std_io::Cursor;
+use deku::ctx::Limit;
+use deku::no_std_io::{Cursor, Read, Seek};
 use deku::prelude::*;
 use hex;
 use serde::{Deserialize, Serialize};
@@ -16,6 +19,7 @@ use super::{
     beast_types::messagetype::MessageType,
     helpers::prettyprint::{pretty_print_field, pretty_print_label},
     raw::AdsbRawMessage,
+    raw_types::df::DF,
 };
 
 // Beast format sources:
@@ -26,8 +30,27 @@ use super::{
 // <esc> "2" : 6 byte MLAT timestamp, 1 byte signal level, 7 byte Mode-S short frame
 // <esc> "3" : 6 byte MLAT timestamp, 1 byte signal level, 14 byte Mode-S long frame
 // <esc> "4" : 6 byte MLAT timestamp, 1 byte unused, DIP switch configuration settings, time stamp error ticks as int8_t (1 tick is 15ns) (message "4" not on Mode-S Beast classic)
+// <esc> 0xe3 : 8 byte receiver ID of the feeder that sent the frame after it (readsb, mlat-client)
 // <esc><esc>: true 0x1a
-// <esc> is 0x1a, and "1", "2" and "3" are 0x31, 0x32 and 0x33
+// <esc> is 0x1a, and "1", "2", "3" and "4" are 0x31, 0x32, 0x33 and 0x34
+
+const BEAST_MODEAC_FRAME_BYTES: usize = 2;
+const BEAST_SHORT_FRAME_BYTES: usize = 7;
+const BEAST_LONG_FRAME_BYTES: usize = 14;
+const BEAST_RECEIVER_ID_CHARACTER: u8 = 0xe3;
+const BEAST_RECEIVER_ID_LENGTH: usize = 9;
+/// Frame type, 6 bytes of MLAT timestamp and the signal level come before the frame
+const BEAST_HEADER_BYTES: usize = 8;
+
+/// Split the `0xe3` receiver ID off the front of a frame, if there is one.
+pub(crate) fn split_receiver_id(buf: &[u8]) -> (Option<u64>, &[u8]) {
+    match buf.split_first_chunk::<BEAST_RECEIVER_ID_LENGTH>() {
+        Some(([BEAST_RECEIVER_ID_CHARACTER, receiver_id @ ..], frame)) => {
+            (Some(u64::from_be_bytes(*receiver_id)), frame)
+        }
+        _ => (None, buf),
+    }
+}
 
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
@@ -129,9 +152,15 @@ impl fmt::Display for AdsbBeastMessage {
 /// Additionally, incoming message payloads will have a 0x1a 0x1a sequence to represent a single 0x1a byte.
 /// This is not handled by this library, and should be handled by the user by only emitting one 0x1a byte in the payload that is processed here.
 /// Both of those are handled by `helpers::encode_adsb_beast_input::format`_* methods.
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, PartialEq)]
+///
+/// A frame may start with the `0xe3` receiver ID that came in front of it, see `receiver_id`.
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct AdsbBeastMessage {
+    /// Receiver ID of the feeder, as sent by readsb and mlat-client to aggregators
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub receiver_id: Option<u64>,
     /// 1: Message Type
     message_type: MessageType,
     /// 2: MLAT Timestamp
@@ -141,20 +170,133 @@ pub struct AdsbBeastMessage {
     #[deku(bits = "8")]
     signal_level: u8,
     /// 4: Message
+    #[deku(reader = "Self::read_raw_message(deku::reader, message_type)")]
     pub raw_message: AdsbRawMessage,
+    /// 4: Message, as received, for writing the message back out. Empty for a message built with `new`.
+    #[deku(skip, default = "Vec::new()")]
+    #[serde(skip)]
+    frame: Vec<u8>,
+}
+
+/// The received frame is only kept for writing it back out, so it is left out of the comparison.
+impl PartialEq for AdsbBeastMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.receiver_id == other.receiver_id
+            && self.message_type == other.message_type
+            && self.mlat_timestamp == other.mlat_timestamp
+            && self.signal_level == other.signal_level
+            && self.raw_message == other.raw_message
+    }
 }
 
 impl AdsbBeastMessage {
+    /// The parity is checked over the whole frame, so the frame is cut out before it is decoded.
+    fn read_raw_message<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        message_type: &MessageType,
+    ) -> Result<AdsbRawMessage, DekuError> {
+        let frame_length = match message_type {
+            MessageType::ModeAC => BEAST_MODEAC_FRAME_BYTES,
+            MessageType::ShortFrame => BEAST_SHORT_FRAME_BYTES,
+            MessageType::LongFrame => BEAST_LONG_FRAME_BYTES,
+        };
+        let frame = Vec::<u8>::from_reader_with_ctx(reader, Limit::new_count(frame_length))?;
+
+        AdsbRawMessage::from_bytes(&frame)
+    }
+
+    /// A message for `raw_message`, received at the 12 MHz `mlat_timestamp` with `signal_level`, for sending it out as Beast.
+    #[must_use]
+    pub fn new(raw_message: AdsbRawMessage, mlat_timestamp: u64, signal_level: u8) -> Self {
+        // DF16 and up are long frames, the same as when the parity is read
+        let message_type = match raw_message.df.deku_id() {
+            Ok(id) if id & 0x10 == 0 => MessageType::ShortFrame,
+            _ => MessageType::LongFrame,
+        };
+
+        Self {
+            receiver_id: None,
+            message_type,
+            mlat_timestamp,
+            signal_level,
+            raw_message,
+            frame: Vec::new(),
+        }
+    }
+
     /// # Errors
     /// If the conversion to a `String` fails, the error is returned.
     pub fn from_bytes(buf: &[u8]) -> Result<Self, DekuError> {
+        let (receiver_id, buf) = split_receiver_id(buf);
         let mut cursor = Cursor::new(buf);
         match Self::from_reader((&mut cursor, 0)) {
-            Ok((_, message)) => Ok(message),
+            Ok((_, mut message)) => {
+                message.receiver_id = receiver_id;
+                message.frame = buf[BEAST_HEADER_BYTES..]
+                    .iter()
+                    .take(message.frame_length())
+                    .copied()
+                    .collect();
+                Ok(message)
+            }
             Err(e) => Err(e),
         }
     }
 
+    /// Encodes the message back to Beast wire bytes, escaped and with the receiver ID in front if it has one.
+    ///
+    /// The bytes are the ones that were received, parity included, so a decoded frame round trips exactly.
+    /// A message built with `new` has no received bytes, its frame is encoded with `AdsbRawMessage::to_frame_bytes`.
+    /// # Errors
+    /// If there are no received bytes and the message is not a DF17 or DF18, for example because it was
+    /// deserialized from JSON, or a field can't be sent, the error is returned.
+    pub fn to_beast_bytes(&self) -> MessageResult<Vec<u8>> {
+        let encoded_frame;
+        let frame = if self.frame.len() == self.frame_length() {
+            &self.frame
+        } else if matches!(self.raw_message.df, DF::ADSB(_) | DF::TisB { .. }) {
+            encoded_frame = self.raw_message.to_frame_bytes()?;
+            &encoded_frame
+        } else {
+            return Err(ADSBBeastError::MissingFrame.into());
+        };
+
+        let frame = encode_beast_frame(
+            &self.message_type,
+            self.mlat_timestamp,
+            self.signal_level,
+            frame,
+        )?;
+
+        match self.receiver_id {
+            Some(receiver_id) => {
+                let mut receiver_id_frame = vec![BEAST_RECEIVER_ID_CHARACTER];
+                receiver_id_frame.extend_from_slice(&receiver_id.to_be_bytes());
+                Ok([escape_beast_frame(&receiver_id_frame), frame].concat())
+            }
+            None => Ok(frame),
+        }
+    }
+
+    /// The 48 bit 12 MHz MLAT timestamp
+    #[must_use]
+    pub const fn get_mlat_timestamp(&self) -> u64 {
+        self.mlat_timestamp
+    }
+
+    #[must_use]
+    pub const fn get_signal_level(&self) -> u8 {
+        self.signal_level
+    }
+
+    const fn frame_length(&self) -> usize {
+        match self.message_type {
+            MessageType::ModeAC => BEAST_MODEAC_FRAME_BYTES,
+            MessageType::ShortFrame => BEAST_SHORT_FRAME_BYTES,
+            MessageType::LongFrame => BEAST_LONG_FRAME_BYTES,
+        }
+    }
+
     /// Converts `AdsbBeastMessage` to `String`.
     /// # Errors
     /// If the conversion to a `String` fails, the error is returned.
@@ -200,6 +342,9 @@ impl AdsbBeastMessage {
     pub fn pretty_print(&self) -> String {
         let mut output = String::new();
         pretty_print_label("ADS-B Beast Message", &mut output);
+        if let Some(receiver_id) = self.receiver_id {
+            pretty_print_field("Receiver ID", &format!("{receiver_id:016X}"), &mut output);
+        }
         pretty_print_field("Message Type", &self.message_type, &mut output);
         pretty_print_field("MLAT Timestamp", &self.mlat_timestamp, &mut output);
         pretty_print_field("Signal Level", &self.signal_level, &mut output);
@@ -0,0 +1,65 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// DIP switch settings of a Mode-S Beast or Radarcape, switch 8 is the most significant bit.
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DipSwitches {
+    /// 8 ('j'): Mode A/C replies are forwarded
+    #[deku(bits = "1")]
+    pub mode_ac: bool,
+    /// 7 ('i'): forward error correction is off
+    #[deku(bits = "1")]
+    pub fec_disabled: bool,
+    /// 6 ('h'): RTS/CTS handshake is on
+    #[deku(bits = "1")]
+    pub rts_handshake: bool,
+    /// 5 ('g'): timestamps come from the GPS instead of the 12 MHz counter
+    #[deku(bits = "1")]
+    pub gps_timestamp: bool,
+    /// 4 ('f'): frames that fail the CRC check are forwarded
+    #[deku(bits = "1")]
+    pub crc_disabled: bool,
+    /// 3 ('e'): frames carry the MLAT timestamp
+    #[deku(bits = "1")]
+    pub mlat: bool,
+    /// 2 ('d'): only DF11 and DF17 are forwarded
+    #[deku(bits = "1")]
+    pub df11_df17_only: bool,
+    /// 1 ('c'): binary output instead of AVR
+    #[deku(bits = "1")]
+    pub binary_format: bool,
+}
+
+impl fmt::Display for DipSwitches {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let switches = [
+            self.binary_format,
+            self.df11_df17_only,
+            self.mlat,
+            self.crc_disabled,
+            self.gps_timestamp,
+            self.rts_handshake,
+            self.fec_disabled,
+            self.mode_ac,
+        ];
+
+        // same notation as the Beast's serial settings, upper case is on
+        for (switch, setting) in switches.iter().zip('c'..='j') {
+            if *switch {
+                write!(f, "{}", setting.to_ascii_uppercase())?;
+            } else {
+                write!(f, "{setting}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
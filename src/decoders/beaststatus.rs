@@ -0,0 +1,93 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use crate::MessageResult;
+use deku::no_std_io::Cursor;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::{
+    beast::split_receiver_id,
+    beast_types::dipswitches::DipSwitches,
+    helpers::prettyprint::{pretty_print_field, pretty_print_label},
+};
+
+// <esc> "4" : 6 byte MLAT timestamp, 1 byte unused, 14 byte status. The status starts with the
+// DIP switch settings and the time stamp error ticks as int8_t (1 tick is 15ns), a Radarcape
+// puts its GPS status in the rest.
+const BEAST_STATUS_REMAINDER_BYTES: usize = 12;
+
+/// Receiver status frame (`0x34`), sent by the Mode-S Beast, the Radarcape and readsb.
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, PartialEq)]
+#[deku(magic = b"\x34")]
+#[serde(deny_unknown_fields)]
+pub struct BeastStatusMessage {
+    /// The `0xe3` receiver ID sent in front of the status frame, see `AdsbBeastMessage::receiver_id`
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub receiver_id: Option<u64>,
+    #[deku(endian = "big", bits = "48")]
+    pub mlat_timestamp: u64,
+    #[deku(bits = "8")]
+    unused: u8,
+    pub dip_switches: DipSwitches,
+    /// Time stamp error in ticks of 15ns
+    pub timestamp_error: i8,
+    /// GPS status on a Radarcape, unused by the Mode-S Beast
+    pub status: [u8; BEAST_STATUS_REMAINDER_BYTES],
+}
+
+impl fmt::Display for BeastStatusMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ADSB Beast Status: MLAT Timestamp: {:?}, DIP Switches: {}, Timestamp Error: {}",
+            self.mlat_timestamp, self.dip_switches, self.timestamp_error
+        )
+    }
+}
+
+impl BeastStatusMessage {
+    /// Decode a status frame starting at the frame type, optionally prefixed with the receiver ID.
+    /// # Errors
+    /// If the input is not a status frame, the error is returned.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, DekuError> {
+        let (receiver_id, buf) = split_receiver_id(buf);
+        let mut cursor = Cursor::new(buf);
+        let (_, mut message) = Self::from_reader((&mut cursor, 0))?;
+        message.receiver_id = receiver_id;
+        Ok(message)
+    }
+
+    /// Converts `BeastStatusMessage` to `String`.
+    /// # Errors
+    /// If the conversion to a `String` fails, the error is returned.
+    pub fn to_string(&self) -> MessageResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Converts `BeastStatusMessage` to `String` and appends a `\n` to the end.
+    /// # Errors
+    /// If the conversion to a `String` fails, the error is returned.
+    pub fn to_string_newline(&self) -> MessageResult<String> {
+        Ok(format!("{}\n", self.to_string()?))
+    }
+
+    #[must_use]
+    pub fn pretty_print(&self) -> String {
+        let mut output = String::new();
+        pretty_print_label("ADS-B Beast Status", &mut output);
+        if let Some(receiver_id) = self.receiver_id {
+            pretty_print_field("Receiver ID", &format!("{receiver_id:016X}"), &mut output);
+        }
+        pretty_print_field("MLAT Timestamp", &self.mlat_timestamp, &mut output);
+        pretty_print_field("DIP Switches", &self.dip_switches, &mut output);
+        pretty_print_field("Timestamp Error", &self.timestamp_error, &mut output);
+
+        output
+    }
+}
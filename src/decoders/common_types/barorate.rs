@@ -22,6 +22,14 @@ impl Serialize for BaroRate {
     }
 }
 
+impl BaroRate {
+    /// Feet per minute
+    #[must_use]
+    pub const fn get_baro_rate(&self) -> i32 {
+        self.baro_rate
+    }
+}
+
 impl From<i16> for BaroRate {
     fn from(baro_rate: i16) -> Self {
         Self {
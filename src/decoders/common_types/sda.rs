@@ -9,7 +9,9 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Formatter};
 
 /// [`ME::AircraftOperationStatus`]
-#[derive(Deserialize, DekuRead, Default, Debug, Clone, Copy, Eq, PartialEq, PartialOrd)]
+#[derive(
+    Deserialize, DekuRead, DekuWrite, Default, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
 #[serde(try_from = "u8")]
 #[deku(id_type = "u8", bits = "2")]
 pub enum SystemDesignAssurance {
@@ -10,7 +10,17 @@ use std::fmt::{self, Formatter};
 
 /// SPI Condition
 #[derive(
-    Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Default,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Default,
 )]
 #[serde(from = "u8")]
 #[deku(id_type = "u8", bits = "2")]
@@ -18,5 +18,6 @@ custom_error! {pub ConversionError
     UnknownCapabilityClass = "Unknown capability class",
     UnknownOperationalMode = "Unknown operational mode",
     LatitudeOrLongitudeIsZero{lat: f64, lon: f64} = "Latitude or longitude is 0.0. Latitude: {lat}, Longitude: {lon}. Unable to calculate position",
+    CorruptFrame{syndrome: String} = "The frame failed the CRC check, syndrome {syndrome}",
     UnableToCalculatePosition = "Unable to calculate position from Even/Odd CPR, supplied reference position, and/or previous aircraft position used as reference position",
 }
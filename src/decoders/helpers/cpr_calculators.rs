@@ -12,7 +12,7 @@ Compact Position Reporting for [`Position`] Reporting
 reference: ICAO 9871 (D.2.4.7)
 !*/
 
-// FIXME: surface position decoding needs verification, especially in southern hemisphere
+// FIXME: surface position decoding needs verification
 
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +26,8 @@ const D_LAT_ODD_SURFACE: f64 = 90.0 / (4.0 * NZ - 1.0);
 
 /// 2^17 (Max of 17 bits)
 const CPR_MAX: f64 = 131_072.0;
+/// 2^19, surface positions are encoded with 19 bits and only the lower 17 are sent
+const CPR_MAX_SURFACE: f64 = 524_288.0;
 
 /// Post-processing of CPR into Latitude/Longitude
 #[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
@@ -34,6 +36,23 @@ pub struct Position {
     pub longitude: f64,
 }
 
+/// The 17 bit CPR latitude and longitude as sent in an airborne or surface position message
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CPRPosition {
+    pub lat_cpr: u32,
+    pub lon_cpr: u32,
+}
+
+impl From<CPRPosition> for Position {
+    /// The CPR values in the form the decoding functions take them
+    fn from(cpr_position: CPRPosition) -> Self {
+        Position {
+            latitude: f64::from(cpr_position.lat_cpr),
+            longitude: f64::from(cpr_position.lon_cpr),
+        }
+    }
+}
+
 fn cpr_nl_less_than_twenty_nine(lat: f64) -> f64 {
     if lat < 10.470_471_30 {
         return 59.0;
@@ -436,8 +455,17 @@ pub fn get_position_from_even_odd_cpr_positions_surface(
 
     let j = libm::floor(59.0 * cpr_lat_even - 60.0 * cpr_lat_odd + 0.5);
 
-    let lat_even = D_LAT_EVEN_SURFACE * (calc_modulo(j, 60.0) + cpr_lat_even);
-    let lat_odd = D_LAT_ODD_SURFACE * (calc_modulo(j, 59.0) + cpr_lat_odd);
+    let mut lat_even = D_LAT_EVEN_SURFACE * (calc_modulo(j, 60.0) + cpr_lat_even);
+    let mut lat_odd = D_LAT_ODD_SURFACE * (calc_modulo(j, 59.0) + cpr_lat_odd);
+
+    // the latitudes are in the northern hemisphere, the reference position says if it is the southern one.
+    // This has to be settled first, NL is different for the two.
+    if (reference_position.latitude - (lat_even - 90.0)).abs()
+        < (reference_position.latitude - lat_even).abs()
+    {
+        lat_even -= 90.0;
+        lat_odd -= 90.0;
+    }
 
     // validate the NZ values are the same
 
@@ -451,14 +479,12 @@ pub fn get_position_from_even_odd_cpr_positions_surface(
         return None;
     }
 
-    let lat_northern = if latest_frame_flag == CPRFormat::Even {
+    let lat = if latest_frame_flag == CPRFormat::Even {
         lat_odd
     } else {
         lat_even // the fuck? This matches the solution in the mode-s.org work....
     };
 
-    let lat_southern = &lat_northern - 90.0;
-
     let m = libm::floor(cpr_lon_even * (nl_even - 1.0) - cpr_lon_odd * nl_even + 0.5);
 
     let n = if latest_frame_flag == CPRFormat::Even {
@@ -480,16 +506,6 @@ pub fn get_position_from_even_odd_cpr_positions_surface(
     let lon_three = &lon_one + 180.0;
     let lon_four = &lon_one + 270.0;
 
-    // find the closest latitude to the reference position
-
-    let lat = if (reference_position.latitude - lat_northern).abs()
-        < (reference_position.latitude - lat_southern).abs()
-    {
-        lat_northern
-    } else {
-        lat_southern
-    };
-
     // using haversign distance, now that we have a lat, find the closest lat/lon pair from lon_one, lon_two, lon_three, lon_four to the reference position
 
     let mut lon = lon_one;
@@ -540,12 +556,55 @@ pub fn get_position_from_even_odd_cpr_positions_surface(
         lon = lon_four;
     }
 
+    if lon >= 180.0 {
+        lon -= 360.0;
+    }
+
     Some(Position {
         latitude: lat,
         longitude: lon,
     })
 }
 
+/// Encode a position as the CPR latitude and longitude of an airborne position message
+///
+/// reference: ICAO 9871 (C.2.6.7)
+#[must_use]
+pub fn get_cpr_position_airborne(position: &Position, cpr_flag: CPRFormat) -> CPRPosition {
+    encode_cpr_position(position, cpr_flag, CPR_MAX)
+}
+
+/// Encode a position as the CPR latitude and longitude of a surface position message
+///
+/// reference: ICAO 9871 (C.2.6.7)
+#[must_use]
+pub fn get_cpr_position_surface(position: &Position, cpr_flag: CPRFormat) -> CPRPosition {
+    encode_cpr_position(position, cpr_flag, CPR_MAX_SURFACE)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn encode_cpr_position(position: &Position, cpr_flag: CPRFormat, cpr_scale: f64) -> CPRPosition {
+    // surface positions use the airborne zones with two more bits, the two bits dropped when sending
+    // make it the same as the decoder's quarter size zones
+    let (d_lat, i) = match cpr_flag {
+        CPRFormat::Even => (D_LAT_EVEN_AIRBORNE, 0.0),
+        CPRFormat::Odd => (D_LAT_ODD_AIRBORNE, 1.0),
+    };
+
+    let yz = libm::floor(cpr_scale * calc_modulo(position.latitude, d_lat) / d_lat + 0.5);
+    // the latitude the receiver will decode, which picks the longitude zone size
+    let r_lat = d_lat * (yz / cpr_scale + libm::floor(position.latitude / d_lat));
+
+    let d_lon = 360.0 / libm::fmax(cpr_nl(r_lat) - i, 1.0);
+    let xz = libm::floor(cpr_scale * calc_modulo(position.longitude, d_lon) / d_lon + 0.5);
+
+    // both are whole numbers in 0..2^17 after the modulo
+    CPRPosition {
+        lat_cpr: calc_modulo(yz, CPR_MAX) as u32,
+        lon_cpr: calc_modulo(xz, CPR_MAX) as u32,
+    }
+}
+
 #[must_use]
 pub fn get_bearing_from_positions(position: &Position, other: &Position) -> f64 {
     let lat1 = position.latitude.to_radians();
@@ -569,6 +628,35 @@ pub fn get_bearing_from_positions(position: &Position, other: &Position) -> f64
     }
 }
 
+/// The position reached after `distance` kilometers on the great circle leaving `position` at `bearing`
+#[must_use]
+pub fn get_position_from_bearing_and_distance(
+    position: &Position,
+    bearing: f64,
+    distance: f64,
+) -> Position {
+    let lat1 = position.latitude.to_radians();
+    let long1 = position.longitude.to_radians();
+    let bearing = bearing.to_radians();
+    let angular_distance = distance / 6371.00;
+
+    let lat2 = libm::asin(
+        libm::sin(lat1) * libm::cos(angular_distance)
+            + libm::cos(lat1) * libm::sin(angular_distance) * libm::cos(bearing),
+    );
+    let long2 = long1
+        + libm::atan2(
+            libm::sin(bearing) * libm::sin(angular_distance) * libm::cos(lat1),
+            libm::cos(angular_distance) - libm::sin(lat1) * libm::sin(lat2),
+        );
+
+    Position {
+        latitude: lat2.to_degrees(),
+        // back in to -180..180 after crossing the antimeridian
+        longitude: calc_modulo(long2.to_degrees() + 180.0, 360.0) - 180.0,
+    }
+}
+
 #[must_use]
 pub fn km_to_nm(km: f64) -> f64 {
     km * 0.539_957
@@ -937,4 +1025,128 @@ mod tests {
         assert!(compare_epsilon_f64(position.latitude, expected_lat));
         assert!(compare_epsilon_f64(position.longitude, expected_lon));
     }
+
+    #[test]
+    fn encode_cpr_known_positions() {
+        "debug".enable_logging();
+
+        // the positions decoded in calculate_local_unambiguous and calculate_surface_position_from_local_kabq
+        let airborne = Position {
+            latitude: 52.257_202_148_437_5,
+            longitude: 3.919_372_558_593_75,
+        };
+        assert_eq!(
+            get_cpr_position_airborne(&airborne, CPRFormat::Even),
+            CPRPosition {
+                lat_cpr: 93000,
+                lon_cpr: 51372,
+            }
+        );
+
+        let surface = Position {
+            latitude: 35.037_297_394_316_07,
+            longitude: -106.614_389_419_555_66,
+        };
+        assert_eq!(
+            get_cpr_position_surface(&surface, CPRFormat::Odd),
+            CPRPosition {
+                lat_cpr: 126_995,
+                lon_cpr: 18218,
+            }
+        );
+    }
+
+    /// Is there a change in NL close enough to the latitude for the even and odd frames to land in different zones?
+    fn near_nl_boundary(latitude: f64) -> bool {
+        (cpr_nl(latitude - 0.001) - cpr_nl(latitude + 0.001)).abs() > f64::EPSILON
+    }
+
+    #[test]
+    fn encode_decode_cpr_round_trip() {
+        "debug".enable_logging();
+
+        let mut latitudes: Vec<f64> = (-900..=900)
+            .map(|lat| f64::from(lat) / 10.0 + 0.013)
+            .collect();
+        // the poles, the NL 2 to 1 change and a few zone edges
+        latitudes.extend([
+            -90.0,
+            -87.0,
+            -86.999,
+            0.0,
+            10.470_471_3,
+            86.999,
+            87.0,
+            87.001,
+            90.0,
+        ]);
+        let longitudes = [
+            -180.0, -179.999, -106.6, -0.001, 0.0, 3.9, 90.0, 150.3, 179.999, 180.0,
+        ];
+
+        for latitude in latitudes
+            .into_iter()
+            .filter(|lat| (-90.0..=90.0).contains(lat))
+        {
+            for longitude in longitudes {
+                let position = Position {
+                    latitude,
+                    longitude,
+                };
+                // towards the equator, so it stays a valid latitude
+                let reference = Position {
+                    latitude: latitude - 0.05_f64.copysign(latitude),
+                    longitude: longitude + 0.05,
+                };
+
+                // airborne positions are good to about 5 meters, surface positions to about 1.5 meters
+                let even = get_cpr_position_airborne(&position, CPRFormat::Even).into();
+                let odd = get_cpr_position_airborne(&position, CPRFormat::Odd).into();
+                for (frame, cpr_flag) in [(&even, CPRFormat::Even), (&odd, CPRFormat::Odd)] {
+                    let decoded =
+                        get_position_from_locally_unabiguous_airborne(frame, &reference, cpr_flag);
+                    assert!(
+                        haversine_distance_position(&position, &decoded) < 0.01,
+                        "{position:?} {cpr_flag:?} decoded locally as {decoded:?}"
+                    );
+
+                    match get_position_from_even_odd_cpr_positions_airborne(&even, &odd, cpr_flag) {
+                        Some(decoded) => assert!(
+                            haversine_distance_position(&position, &decoded) < 0.01,
+                            "{position:?} {cpr_flag:?} decoded as {decoded:?}"
+                        ),
+                        None => assert!(near_nl_boundary(latitude), "{position:?} not decoded"),
+                    }
+                }
+
+                let even = get_cpr_position_surface(&position, CPRFormat::Even).into();
+                let odd = get_cpr_position_surface(&position, CPRFormat::Odd).into();
+                for (frame, cpr_flag) in [(&even, CPRFormat::Even), (&odd, CPRFormat::Odd)] {
+                    let decoded =
+                        get_position_from_locally_unabiguous_surface(frame, &reference, cpr_flag);
+                    assert!(
+                        haversine_distance_position(&position, &decoded) < 0.003,
+                        "{position:?} {cpr_flag:?} decoded locally on the surface as {decoded:?}"
+                    );
+
+                    // 90 is on the same surface zone edge as 0, and nothing lands at the pole
+                    if latitude >= 90.0 {
+                        continue;
+                    }
+                    match get_position_from_even_odd_cpr_positions_surface(
+                        &even, &odd, cpr_flag, &reference,
+                    ) {
+                        Some(decoded) => assert!(
+                            haversine_distance_position(&position, &decoded) < 0.003,
+                            "{position:?} {cpr_flag:?} decoded on the surface as {decoded:?}"
+                        ),
+                        None => assert!(
+                            near_nl_boundary(latitude),
+                            "{position:?} not decoded on the surface"
+                        ),
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,171 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// Syndrome based error correction for extended squitters, as done by dump1090 and readsb.
+// The CRC is linear, so the remainder of a damaged frame is the XOR of the remainders of the
+// flipped bits. Looking the remainder up in a table of every 1- and 2-bit error tells us which
+// bits to flip back.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::decoders::raw_types::helper_functions::modes_checksum;
+
+/// Bytes in a long frame, the only ones that are repaired
+pub const MODES_LONG_MSG_BYTES: usize = 14;
+const MODES_LONG_MSG_BITS: usize = MODES_LONG_MSG_BYTES * 8;
+/// The DF field is never repaired, a fix there would turn the frame into a different format.
+const DF_FIELD_BITS: usize = 5;
+
+/// How many flipped bits `correct_errors` may repair
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ErrorCorrection {
+    #[default]
+    Disabled,
+    SingleBit,
+    TwoBits,
+}
+
+impl ErrorCorrection {
+    #[must_use]
+    pub const fn max_bits(&self) -> usize {
+        match self {
+            ErrorCorrection::Disabled => 0,
+            ErrorCorrection::SingleBit => 1,
+            ErrorCorrection::TwoBits => 2,
+        }
+    }
+}
+
+/// Syndrome of every 1- and 2-bit error outside the DF field of a long frame.
+static SYNDROMES: LazyLock<HashMap<u32, Vec<usize>>> = LazyLock::new(|| {
+    let single: Vec<(usize, u32)> = (DF_FIELD_BITS..MODES_LONG_MSG_BITS)
+        .filter_map(|bit| syndrome_of(&[bit]).map(|syndrome| (bit, syndrome)))
+        .collect();
+
+    let mut table: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut ambiguous: Vec<u32> = Vec::new();
+    let mut insert = |syndrome: u32, bits: Vec<usize>| {
+        if table.insert(syndrome, bits).is_some() {
+            ambiguous.push(syndrome);
+        }
+    };
+
+    for (i, (first, first_syndrome)) in single.iter().enumerate() {
+        insert(*first_syndrome, vec![*first]);
+        for (second, second_syndrome) in &single[i + 1..] {
+            insert(first_syndrome ^ second_syndrome, vec![*first, *second]);
+        }
+    }
+
+    // a syndrome that more than one error pattern produces can't be repaired
+    for syndrome in ambiguous {
+        table.remove(&syndrome);
+    }
+
+    table
+});
+
+fn syndrome_of(bits: &[usize]) -> Option<u32> {
+    let mut message = [0u8; MODES_LONG_MSG_BYTES];
+    for bit in bits {
+        message[bit / 8] ^= 0x80 >> (bit % 8);
+    }
+
+    modes_checksum(&message, MODES_LONG_MSG_BITS).ok()
+}
+
+/// Repair a DF17/DF18 frame in place.
+///
+/// Returns the positions of the flipped bits, counted from the first bit of the DF field, or `None`
+/// if the frame is not a long extended squitter, is already clean, or can't be repaired within
+/// `error_correction`.
+#[must_use]
+pub fn correct_errors(message: &mut [u8], error_correction: ErrorCorrection) -> Option<Vec<usize>> {
+    if error_correction == ErrorCorrection::Disabled || message.len() < MODES_LONG_MSG_BYTES {
+        return None;
+    }
+
+    let df = message[0] >> 3;
+    if df != 17 && df != 18 {
+        return None;
+    }
+
+    let syndrome = modes_checksum(message, MODES_LONG_MSG_BITS).ok()?;
+    if syndrome == 0 {
+        return None;
+    }
+
+    let bits = SYNDROMES.get(&syndrome)?;
+    if bits.len() > error_correction.max_bits() {
+        return None;
+    }
+
+    for bit in bits {
+        message[bit / 8] ^= 0x80 >> (bit % 8);
+    }
+
+    Some(bits.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdre_rust_logging::SetupLogging;
+
+    const CLEAN: [u8; 14] = [
+        0x8D, 0x48, 0x40, 0xD6, 0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x2C, 0xE0, 0x57, 0x60, 0x98,
+    ];
+
+    fn flip(bits: &[usize]) -> Vec<u8> {
+        let mut message = CLEAN.to_vec();
+        for bit in bits {
+            message[bit / 8] ^= 0x80 >> (bit % 8);
+        }
+        message
+    }
+
+    #[test]
+    fn correct_single_bit() {
+        "debug".enable_logging();
+
+        let mut message = flip(&[40]);
+        assert_eq!(
+            correct_errors(&mut message, ErrorCorrection::SingleBit),
+            Some(vec![40])
+        );
+        assert_eq!(message, CLEAN);
+    }
+
+    #[test]
+    fn correct_two_bits() {
+        "debug".enable_logging();
+
+        let mut message = flip(&[12, 100]);
+        assert_eq!(
+            correct_errors(&mut message, ErrorCorrection::SingleBit),
+            None
+        );
+        assert_eq!(
+            correct_errors(&mut message, ErrorCorrection::TwoBits),
+            Some(vec![12, 100])
+        );
+        assert_eq!(message, CLEAN);
+    }
+
+    #[test]
+    fn refuse_df_field() {
+        "debug".enable_logging();
+
+        // DF17 with bits 3 and 4 flipped reads as DF18, only a fix of the DF field would repair it
+        let mut message = flip(&[3, 4]);
+        assert_eq!(correct_errors(&mut message, ErrorCorrection::TwoBits), None);
+        assert_eq!(message, flip(&[3, 4]));
+
+        let mut clean = CLEAN.to_vec();
+        assert_eq!(correct_errors(&mut clean, ErrorCorrection::TwoBits), None);
+    }
+}
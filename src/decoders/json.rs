@@ -21,6 +21,7 @@ use super::{
         time::get_time_as_timestamp,
     },
     json_types::{
+        acasresolutionadvisory::ACASResolutionAdvisoryReport,
         adsbversion::ADSBVersion,
         altimeter::Altimeter,
         altitude::Altitude,
@@ -48,11 +49,15 @@ use super::{
         tisb::TiSB,
         transponderhex::TransponderHex,
     },
-    raw_types::{df::DF, me::ME, surfaceposition::SurfacePosition},
+    raw_types::{
+        acasresolutionadvisory::ACAS_RA_REGISTER, df::DF, me::ME, mvfield::MVField,
+        surfaceposition::SurfacePosition,
+    },
     rawtojson::{
-        update_airborne_velocity, update_aircraft_identification,
-        update_aircraft_position_airborne, update_aircraft_position_surface,
-        update_aircraft_status, update_from_no_position, update_operational_status,
+        get_tisb_fields, message_type_from_control_field, update_airborne_velocity,
+        update_aircraft_identification, update_aircraft_position_airborne,
+        update_aircraft_position_surface, update_aircraft_status, update_from_comm_b,
+        update_from_no_position, update_from_surveillance_reply, update_operational_status,
         update_target_state_and_status_information,
     },
 };
@@ -136,7 +141,10 @@ impl JSONMessage {
             transponder_hex: icao.into(),
             timestamp: get_time_as_timestamp(),
             last_time_seen: (0.0).into(),
-            message_type: MessageType::ADSBICAO, // FIXME: this feels wrong. How do we handle data that is UAT?
+            // Deliberately UNKNOWN rather than ADSBICAO: it has the lowest priority, so the first
+            // message sets the real source in `update_message_type`. Starting at ADSBICAO would
+            // keep a Mode S only or TIS-B aircraft marked as ADS-B forever.
+            message_type: MessageType::UNKNOWN,
             ..Default::default()
         }
     }
@@ -206,6 +214,7 @@ impl JSONMessage {
             &mut output,
         );
         pretty_print_field_from_option("True Air Speed", &self.true_air_speed, &mut output);
+        pretty_print_field_from_option("Mach", &self.mach, &mut output);
         pretty_print_field_from_option(
             "True Track Over Ground",
             &self.true_track_over_ground,
@@ -355,6 +364,16 @@ impl JSONMessage {
 
         pretty_print_field_from_option("Wind Speed", &self.wind_speed, &mut output);
         pretty_print_field_from_option("Wind Direction", &self.wind_direction, &mut output);
+        pretty_print_field_from_option(
+            "Outside Air Temperature",
+            &self.outside_air_temperature,
+            &mut output,
+        );
+        pretty_print_field_from_option(
+            "Total Air Temperature",
+            &self.total_air_temperature,
+            &mut output,
+        );
 
         output
     }
@@ -480,8 +499,12 @@ impl JSONMessage {
     }
 
     /// Update the `JSONMessage` from a DF.
+    ///
+    /// Extended squitters (DF17 and the DF18 TIS-B/ADS-R/non-transponder formats), the MB field of Comm-B replies,
+    /// ACAS RA reports in DF16 and the altitude, identity and flight status of the Mode S surveillance replies are used,
+    /// other DFs only refresh the timestamps.
     /// # Errors
-    /// Returns an error if the DF is not an ADSB message.
+    /// Returns an error if the extended squitter could not be used.
     pub fn update_from_df(
         &mut self,
         raw_adsb: &DF,
@@ -492,84 +515,140 @@ impl JSONMessage {
         self.last_time_seen = SecondsAgo::now();
         self.timestamp = get_time_as_timestamp();
 
-        if let DF::ADSB(adsb) = raw_adsb {
-            match &adsb.me {
-                ME::AirborneVelocity(velocity) => {
-                    if *use_strict_mode && !velocity.is_reserved_zero() {
-                        return Err(ConversionError::ReservedIsNotZero {
-                            source_name: "Airborne Velocity".into(),
-                        });
-                    }
+        update_from_surveillance_reply(self, raw_adsb);
 
-                    update_airborne_velocity(self, velocity);
-                }
-                ME::NoPosition(no_position) => {
-                    update_from_no_position(self, no_position);
-                }
-                ME::AircraftIdentification(_, id) => {
-                    update_aircraft_identification(self, id);
-                }
-                ME::SurfacePosition(_, surfaceposition) => {
-                    return self.handle_surface_position(surfaceposition, reference_position);
-                }
-                ME::AirbornePositionGNSSAltitude(_, altitude)
-                | ME::AirbornePositionBaroAltitude(_, altitude) => {
-                    let baro_altitude = matches!(adsb.me, ME::AirbornePositionBaroAltitude(..));
-                    return self.handle_airborne_position(
-                        altitude,
-                        reference_position,
-                        baro_altitude,
-                    );
+        match raw_adsb {
+            DF::ADSB(adsb) => {
+                self.update_message_type(MessageType::ADSBICAO);
+                self.update_from_me(&adsb.me, reference_position, *use_strict_mode)?;
+                // the fields are now backed by the aircraft itself
+                let fields = get_tisb_fields(&adsb.me);
+                self.tisb.retain(|field| !fields.contains(field));
+            }
+            DF::TisB { cf, .. } => {
+                let Some(message_type) = message_type_from_control_field(cf) else {
+                    return Ok(());
+                };
+
+                self.update_message_type(message_type.clone());
+                self.update_from_me(&cf.me, reference_position, *use_strict_mode)?;
+                let fields = get_tisb_fields(&cf.me);
+                if matches!(
+                    message_type,
+                    MessageType::ADSBICAOSECONDARYSURVEILLANCE
+                        | MessageType::ADSBOTHERSECONDARYSURVEILLANCE
+                        | MessageType::ADSBTRACKFILE
+                ) {
+                    for field in fields {
+                        if !self.tisb.contains(&field) {
+                            self.tisb.push(field);
+                        }
+                    }
+                } else {
+                    self.tisb.retain(|field| !fields.contains(field));
                 }
-                ME::Reserved0(_) => {
-                    return Err(ConversionError::NotImplemented {
-                        source_name: "Reserved0".into(),
-                    });
+            }
+            DF::CommBAltitudeReply { bds, .. } | DF::CommBIdentityReply { bds, .. } => {
+                self.update_message_type(MessageType::MODES);
+                update_from_comm_b(self, bds);
+            }
+            DF::LongAirAir { mv, .. } => {
+                self.update_message_type(MessageType::MODES);
+                if let MVField::ResolutionAdvisory(ra) = mv {
+                    self.acas_ra = Some(ACASResolutionAdvisoryReport::new(ACAS_RA_REGISTER, ra));
                 }
-                ME::SurfaceSystemStatus(..) => {
-                    return Err(ConversionError::NotImplemented {
-                        source_name: "Surface System Status".into(),
+            }
+            DF::ShortAirAirSurveillance { .. }
+            | DF::SurveillanceAltitudeReply { .. }
+            | DF::SurveillanceIdentityReply { .. }
+            | DF::AllCallReply { .. } => {
+                self.update_message_type(MessageType::MODES);
+            }
+            DF::ExtendedQuitterMilitaryApplication { .. }
+            | DF::CommDExtendedLengthMessage { .. } => (),
+        }
+
+        Ok(())
+    }
+
+    /// Only take the message type of a source at least as good as the one we have.
+    fn update_message_type(&mut self, message_type: MessageType) {
+        if message_type.priority() <= self.message_type.priority() {
+            self.message_type = message_type;
+        }
+    }
+
+    /// Update the `JSONMessage` from the ME field of an extended squitter.
+    fn update_from_me(
+        &mut self,
+        me: &ME,
+        reference_position: &Position,
+        use_strict_mode: bool,
+    ) -> Result<(), ConversionError> {
+        match me {
+            ME::AirborneVelocity(velocity) => {
+                if use_strict_mode && !velocity.is_reserved_zero() {
+                    return Err(ConversionError::ReservedIsNotZero {
+                        source_name: "Airborne Velocity".into(),
                     });
                 }
-                ME::Reserved1(..) => {
-                    return Err(ConversionError::NotImplemented {
-                        source_name: "Reserved1".into(),
+
+                update_airborne_velocity(self, velocity);
+            }
+            ME::NoPosition(no_position) => {
+                update_from_no_position(self, no_position);
+            }
+            ME::AircraftIdentification(_, id) => {
+                update_aircraft_identification(self, id);
+            }
+            ME::SurfacePosition(_, surfaceposition) => {
+                return self.handle_surface_position(surfaceposition, reference_position);
+            }
+            ME::AirbornePositionGNSSAltitude(_, altitude)
+            | ME::AirbornePositionBaroAltitude(_, altitude) => {
+                let baro_altitude = matches!(me, ME::AirbornePositionBaroAltitude(..));
+                return self.handle_airborne_position(altitude, reference_position, baro_altitude);
+            }
+            ME::Reserved0(_) => {
+                return Err(ConversionError::NotImplemented {
+                    source_name: "Reserved0".into(),
+                });
+            }
+            // nothing in these is published, they only refresh the timestamps
+            ME::SurfaceSystemStatus(_) | ME::AircraftOperationalCoordination(_) => (),
+            ME::Reserved1(..) => {
+                return Err(ConversionError::NotImplemented {
+                    source_name: "Reserved1".into(),
+                });
+            }
+            ME::AircraftStatus(status) => {
+                if use_strict_mode && !status.is_reserved_zero() {
+                    return Err(ConversionError::ReservedIsNotZero {
+                        source_name: "Aircraft Status".into(),
                     });
                 }
-                ME::AircraftStatus(status) => {
-                    if *use_strict_mode && !status.is_reserved_zero() {
-                        return Err(ConversionError::ReservedIsNotZero {
-                            source_name: "Aircraft Status".into(),
-                        });
-                    }
 
-                    update_aircraft_status(self, status);
-                }
-                ME::TargetStateAndStatusInformation(target_state_and_status_information) => {
-                    if *use_strict_mode && !target_state_and_status_information.is_reserved_zero() {
-                        return Err(ConversionError::ReservedIsNotZero {
-                            source_name: "Target State and Status Information".into(),
-                        });
-                    }
-                    update_target_state_and_status_information(
-                        self,
-                        target_state_and_status_information,
-                    );
+                update_aircraft_status(self, status);
+            }
+            ME::TargetStateAndStatusInformation(target_state_and_status_information) => {
+                if use_strict_mode && !target_state_and_status_information.is_reserved_zero() {
+                    return Err(ConversionError::ReservedIsNotZero {
+                        source_name: "Target State and Status Information".into(),
+                    });
                 }
-                ME::AircraftOperationalCoordination(_) => {
-                    return Err(ConversionError::NotImplemented {
-                        source_name: "Aircraft Operational Coordination".into(),
+                update_target_state_and_status_information(
+                    self,
+                    target_state_and_status_information,
+                );
+            }
+            ME::AircraftOperationStatus(operation_status) => {
+                if use_strict_mode && !operation_status.is_reserved_zero() {
+                    return Err(ConversionError::ReservedIsNotZero {
+                        source_name: "Aircraft Operation Status".into(),
                     });
                 }
-                ME::AircraftOperationStatus(operation_status) => {
-                    if *use_strict_mode && !operation_status.is_reserved_zero() {
-                        return Err(ConversionError::ReservedIsNotZero {
-                            source_name: "Aircraft Operation Status".into(),
-                        });
-                    }
 
-                    return update_operational_status(self, operation_status);
-                }
+                return update_operational_status(self, operation_status);
             }
         }
 
@@ -624,10 +703,12 @@ pub struct JSONMessage {
     /// Ground speed in knots.
     #[serde(skip_serializing_if = "Option::is_none", rename = "gs")]
     pub ground_speed: Option<Speed>,
+    /// Outside (static) air temperature in degrees C
     #[serde(skip_serializing_if = "Option::is_none", rename = "oat")]
-    pub outside_air_temperature: Option<u32>,
+    pub outside_air_temperature: Option<i32>,
+    /// Total air temperature in degrees C
     #[serde(skip_serializing_if = "Option::is_none", rename = "tat")]
-    pub total_air_temperature: Option<u32>,
+    pub total_air_temperature: Option<i32>,
     /// Indicated Air speed.
     #[serde(skip_serializing_if = "Option::is_none", rename = "ias")]
     pub indicated_air_speed: Option<Speed>,
@@ -759,6 +840,9 @@ pub struct JSONMessage {
     /// True air speed
     #[serde(skip_serializing_if = "Option::is_none", rename = "tas")]
     pub true_air_speed: Option<Speed>,
+    /// Mach number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mach: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub track_rate: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -771,6 +855,9 @@ pub struct JSONMessage {
     // These are new fields we're adding to the json output
     #[serde(default)]
     pub ident_active: bool,
+    /// Most recent ACAS resolution advisory reported by the aircraft, in the same layout as readsb
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acas_ra: Option<ACASResolutionAdvisoryReport>,
 
     /// These are internal values that should never get serialized, but used for tracking raw even/odd positions
 
@@ -809,6 +896,8 @@ mod tests {
 
     use super::*;
     use crate::DecodeMessage;
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::{icao::ICAO, threatidentity::ThreatIdentity};
     use std::fs::{File, read_dir};
     use std::io::BufRead;
 
@@ -908,6 +997,174 @@ mod tests {
         }
     }
 
+    #[test]
+    fn update_from_comm_b_selected_vertical_intention() {
+        "debug".enable_logging();
+
+        let raw = "A000029C85E42F313000007047D3".to_adsb_raw().unwrap();
+        let mut json_message = JSONMessage::new(format!("{:06X}", raw.crc));
+
+        json_message
+            .update_from_df(&raw.df, &Position::default(), &true)
+            .unwrap();
+
+        assert_eq!(
+            json_message.autopilot_selected_altitude,
+            Some(3008u32.into())
+        );
+        assert_eq!(
+            json_message.flight_management_system_selected_altitude,
+            Some(3008u32.into())
+        );
+        assert!(json_message.selected_altimeter.is_some());
+    }
+
+    #[test]
+    fn update_from_acas_ra_broadcast() {
+        "debug".enable_logging();
+
+        let raw = "8D4840D6E2C2020686CB0CA902E3".to_adsb_raw().unwrap();
+        let mut json_message = JSONMessage::new("4840D6".to_string());
+
+        json_message
+            .update_from_df(&raw.df, &Position::default(), &true)
+            .unwrap();
+
+        let report = json_message.acas_ra.unwrap();
+        assert_eq!(report.bytes, "E2C2020686CB0C");
+        assert_eq!(report.active_resolution_advisories, "1100001");
+        assert_eq!(report.resolution_advisory_complement, "1000");
+        assert_eq!(report.threat_type, "01");
+        assert_eq!(report.threat_id_hex.as_deref(), Some("a1b2c3"));
+
+        let ra = report.get_resolution_advisory().unwrap();
+        assert!(!ra.ra_terminated);
+        assert!(ra.rac.do_not_pass_below);
+    }
+
+    #[test]
+    fn decode_readsb_acas_ra() {
+        "debug".enable_logging();
+
+        // An aircraft from readsb's aircraft.json with the RA from 8D4840D6E2C2020686CB0CA902E3
+        let line = r#"{"hex":"4840d6","type":"adsb_icao","flight":"KLM1023 ","alt_baro":23800,"squawk":"2000","emergency":"none","category":"A3","mlat":[],"tisb":[],"messages":312,"seen":0.2,"rssi":-21.4,"acas_ra":{"utc":"12:31:04.8","unix_timestamp":1718973064.80,"df_type":17,"full_bytes":"8D4840D6E2C2020686CB0CA902E3","bytes":"E2C2020686CB0C","ARA":"1100001","RAT":"0","MTE":"0","RAC":"1000","advisory_complement":"Do not pass below","advisory":"Climb","TTI":"01","threat_id_hex":"a1b2c3"}}"#;
+
+        let json_message = line.to_json().unwrap();
+        let report = json_message.acas_ra.unwrap();
+        assert_eq!(report.df_type, Some(17));
+        assert_eq!(report.advisory, "Climb");
+        assert_eq!(report.threat_id_hex.as_deref(), Some("a1b2c3"));
+
+        let ra = report.get_resolution_advisory().unwrap();
+        assert!(ra.rac.do_not_pass_below);
+        assert_eq!(
+            ra.threat_identity,
+            ThreatIdentity::ModeSAddress {
+                address: ICAO([0xA1, 0xB2, 0xC3]),
+                reserved: 0
+            }
+        );
+    }
+
+    #[test]
+    fn update_from_surveillance_replies() {
+        "debug".enable_logging();
+
+        let mut json_message = JSONMessage::new("4840D6".to_string());
+
+        let altitude_reply = "2000183859C38D".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&altitude_reply.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.barometric_altitude, Some(38000u16.into()));
+        assert_eq!(
+            json_message.flight_status,
+            Some(SurveillanceStatus::NoCondition)
+        );
+
+        let identity_reply = "2A000AAA551EB9".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&identity_reply.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.transponder_squawk_code, Some("7700".into()));
+        assert_eq!(
+            json_message.flight_status,
+            Some(SurveillanceStatus::TemporaryAlert)
+        );
+
+        let ground_reply = "21000098E44DAE".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&ground_reply.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.barometric_altitude, Some("ground".into()));
+    }
+
+    #[test]
+    fn new_message_type_is_set_by_first_message() {
+        "debug".enable_logging();
+
+        let mut json_message = JSONMessage::new("4840D6".to_string());
+        assert_eq!(json_message.message_type, MessageType::UNKNOWN);
+
+        let altitude_reply = "2000183859C38D".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&altitude_reply.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.message_type, MessageType::MODES);
+
+        let identification = "8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&identification.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.message_type, MessageType::ADSBICAO);
+    }
+
+    #[test]
+    fn update_from_tisb_and_adsr() {
+        "debug".enable_logging();
+
+        let mut json_message = JSONMessage::new("A1A3CC".to_string());
+
+        let tisb_velocity = "92A1A3CC9909B814F00412B2FF02".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&tisb_velocity.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(
+            json_message.message_type,
+            MessageType::ADSBICAOSECONDARYSURVEILLANCE
+        );
+        assert!(json_message.tisb.contains(&TiSB::GroundSpeed));
+        assert!(json_message.tisb.contains(&TiSB::NACv));
+
+        // ADS-R is better than TIS-B, and the fields no longer come from TIS-B
+        let adsr_velocity = "96A1A3CC9909B814F004122CCEEB".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&adsr_velocity.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.message_type, MessageType::ADSBICAOREBROADCAST);
+        assert!(json_message.tisb.is_empty());
+
+        // but TIS-B doesn't replace it
+        json_message
+            .update_from_df(&tisb_velocity.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(json_message.message_type, MessageType::ADSBICAOREBROADCAST);
+
+        let mut json_message = JSONMessage::new("4840D6".to_string());
+        let non_transponder = "904840D6202CC371C32CE02A6C6D".to_adsb_raw().unwrap();
+        json_message
+            .update_from_df(&non_transponder.df, &Position::default(), &true)
+            .unwrap();
+        assert_eq!(
+            json_message.message_type,
+            MessageType::ADSBICAONONTRANSPONDER
+        );
+        assert_eq!(
+            json_message.calculated_best_flight_id,
+            Some("KLM1023".to_string().into())
+        );
+    }
+
     #[test]
     fn decode_json_message_individually() {
         "debug".enable_logging();
@@ -0,0 +1,136 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use easy_cast::traits::ConvFloat;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::decoders::{
+    helpers::time::get_time_as_f64,
+    raw_types::{acasresolutionadvisory::ACASResolutionAdvisory, threatidentity::ThreatIdentity},
+};
+
+/// The `acas_ra` object readsb writes to aircraft.json. The bit fields are strings of `0` and `1`,
+/// the same as readsb sends them, and `bytes` is the 56 bit field the RA was read from.
+/// Unknown fields are allowed, as readsb has added to this object over time.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Default)]
+pub struct ACASResolutionAdvisoryReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unix_timestamp: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub df_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_bytes: Option<String>,
+    #[serde(default)]
+    pub bytes: String,
+    /// ARA bits 41-47
+    #[serde(rename = "ARA", default)]
+    pub active_resolution_advisories: String,
+    #[serde(rename = "RAT", default)]
+    pub ra_terminated: String,
+    #[serde(rename = "MTE", default)]
+    pub multiple_threat_encounter: String,
+    #[serde(rename = "RAC", default)]
+    pub resolution_advisory_complement: String,
+    #[serde(default)]
+    pub advisory_complement: String,
+    #[serde(default)]
+    pub advisory: String,
+    #[serde(rename = "TTI", default)]
+    pub threat_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threat_id_hex: Option<String>,
+}
+
+impl ACASResolutionAdvisoryReport {
+    /// Build the report for `ra`, which followed `first_byte` in its field: the register number
+    /// for DF16 and BDS 3,0, or the type and subtype for the ADS-B RA broadcast.
+    #[must_use]
+    pub fn new(first_byte: u8, ra: &ACASResolutionAdvisory) -> Self {
+        let mut bytes = vec![first_byte];
+        bytes.extend(ra.to_bytes().unwrap_or_default());
+
+        let advisory = if ra.ra_terminated {
+            "Clear of Conflict".to_string()
+        } else {
+            ra.get_active_resolution_advisory().to_string()
+        };
+        let advisory_complement = if ra.rac.is_empty() {
+            String::new()
+        } else {
+            ra.rac.to_string()
+        };
+        let threat_id_hex = match ra.threat_identity {
+            ThreatIdentity::ModeSAddress { address, .. } => {
+                Some(format!("{address}").to_lowercase())
+            }
+            _ => None,
+        };
+        let unix_timestamp = get_time_as_f64();
+
+        Self {
+            utc: chrono::DateTime::from_timestamp_millis(i64::conv_nearest(
+                unix_timestamp * 1000.0,
+            ))
+            .map(|utc| {
+                format!(
+                    "{}.{}",
+                    utc.format("%H:%M:%S"),
+                    utc.timestamp_subsec_millis() / 100
+                )
+            }),
+            unix_timestamp: Some(unix_timestamp),
+            df_type: None,
+            full_bytes: None,
+            bytes: hex::encode_upper(bytes),
+            active_resolution_advisories: format!("{:07b}", ra.ara >> 7),
+            ra_terminated: u8::from(ra.ra_terminated).to_string(),
+            multiple_threat_encounter: u8::from(ra.multiple_threat_encounter).to_string(),
+            resolution_advisory_complement: [
+                ra.rac.do_not_pass_below,
+                ra.rac.do_not_pass_above,
+                ra.rac.do_not_turn_left,
+                ra.rac.do_not_turn_right,
+            ]
+            .iter()
+            .map(|set| if *set { '1' } else { '0' })
+            .collect(),
+            advisory_complement,
+            advisory,
+            threat_type: format!("{:02b}", ra.threat_type),
+            threat_id_hex,
+        }
+    }
+
+    /// Decode the RA from `bytes`, skipping the register number or type and subtype in front
+    #[must_use]
+    pub fn get_resolution_advisory(&self) -> Option<ACASResolutionAdvisory> {
+        let bytes = hex::decode(&self.bytes).ok()?;
+        if bytes.len() != 7 {
+            return None;
+        }
+
+        ACASResolutionAdvisory::from_bytes((&bytes[1..], 0))
+            .ok()
+            .map(|(_, ra)| ra)
+    }
+}
+
+impl fmt::Display for ACASResolutionAdvisoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ACAS RA: {}", self.advisory)?;
+        if !self.advisory_complement.is_empty() {
+            write!(f, "\tComplement: {}", self.advisory_complement)?;
+        }
+        if let Some(threat_id_hex) = &self.threat_id_hex {
+            write!(f, "\tThreat: {threat_id_hex}")?;
+        }
+        write!(f, "\tTerminated: {}", self.ra_terminated == "1")
+    }
+}
@@ -51,6 +51,28 @@ pub enum MessageType {
     UNKNOWN,
 }
 
+impl MessageType {
+    /// How much the source is trusted, lower is better. Follows readsb's address type ordering.
+    #[must_use]
+    pub const fn priority(&self) -> u8 {
+        match self {
+            MessageType::ADSBICAO => 0,
+            MessageType::ADSBICAONONTRANSPONDER => 1,
+            MessageType::ADSBICAOREBROADCAST => 2,
+            MessageType::ADSBICAOSECONDARYSURVEILLANCE => 3,
+            MessageType::ADSC => 4,
+            MessageType::MLAT => 5,
+            MessageType::OTHER => 6,
+            MessageType::MODES => 7,
+            MessageType::ADSBOTHER => 8,
+            MessageType::ADSBOTHERREBROADCAST => 9,
+            MessageType::ADSBTRACKFILE => 10,
+            MessageType::ADSBOTHERSECONDARYSURVEILLANCE => 11,
+            MessageType::UNKNOWN => 12,
+        }
+    }
+}
+
 impl Serialize for MessageType {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -0,0 +1,195 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use crate::MessageResult;
+use deku::no_std_io::Cursor;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::helpers::prettyprint::{pretty_print_field, pretty_print_label};
+use super::raw_types::helper_functions::mode_a_to_mode_c;
+
+const MODEAC_FRAME_BYTES: usize = 2;
+const BEAST_MODEAC_FRAME_START_CHARACTER: u8 = 0x31;
+/// Frame type, 6 bytes of MLAT timestamp and the signal level come before the code
+const BEAST_MODEAC_HEADER_BYTES: usize = 8;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+pub trait NewModeACMessage {
+    /// Converts the message to a `ModeACMessage` object.
+    /// # Errors
+    /// If the conversion fails, the error is returned.
+    fn to_mode_ac(&self) -> MessageResult<ModeACMessage>;
+}
+
+impl NewModeACMessage for String {
+    fn to_mode_ac(&self) -> MessageResult<ModeACMessage> {
+        let bytes = hex::decode(self)?;
+        Ok(ModeACMessage::from_bytes(&bytes)?)
+    }
+}
+
+impl NewModeACMessage for str {
+    fn to_mode_ac(&self) -> MessageResult<ModeACMessage> {
+        let bytes = hex::decode(self)?;
+        Ok(ModeACMessage::from_bytes(&bytes)?)
+    }
+}
+
+impl NewModeACMessage for &[u8] {
+    fn to_mode_ac(&self) -> MessageResult<ModeACMessage> {
+        Ok(ModeACMessage::from_bytes(self)?)
+    }
+}
+
+/// Mode A/C reply, as forwarded by dump1090 and readsb in Beast (`0x31`) and AVR (`*XXXX;`) frames.
+///
+/// A Mode A/C reply does not say which interrogation it answers, so the code is both a squawk and,
+/// if it is a valid Gillham code, a Mode C altitude.
+///
+/// Beast frames and AVR `@` and `%` lines also carry the MLAT timestamp and signal level, as for a Mode S frame.
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ModeACMessage {
+    /// The 13 reply bits with the A, B, C and D pulses as the digits of `0xABCD`, SPI is `0x0080`
+    #[deku(endian = "big")]
+    pub code: u16,
+    /// 48 bit MLAT timestamp of a Beast frame or an `@` or `%` line
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub mlat_timestamp: Option<u64>,
+    /// Signal level of a Beast frame or a `%` line
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub signal_level: Option<u8>,
+}
+
+impl fmt::Display for ModeACMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, " Mode A/C")?;
+        writeln!(f, "  Squawk:        {}", self.get_squawk())?;
+        if let Some(altitude) = self.get_altitude() {
+            writeln!(f, "  Altitude:      {altitude} ft barometric")?;
+        }
+        if self.is_spi() {
+            writeln!(f, "  SPI:           set")?;
+        }
+        Ok(())
+    }
+}
+
+impl ModeACMessage {
+    /// Decode the 2 byte code of an AVR frame, or a complete Beast Mode A/C frame starting at the frame type.
+    /// # Errors
+    /// If the input is neither, the error is returned.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, DekuError> {
+        let (header, code) = match buf.split_first_chunk::<BEAST_MODEAC_HEADER_BYTES>() {
+            None if buf.len() == MODEAC_FRAME_BYTES => (None, buf),
+            Some((header, code))
+                if code.len() == MODEAC_FRAME_BYTES
+                    && header[0] == BEAST_MODEAC_FRAME_START_CHARACTER =>
+            {
+                (Some(header), code)
+            }
+            _ => {
+                return Err(DekuError::Parse(
+                    format!("{} bytes is not a Mode A/C frame", buf.len()).into(),
+                ));
+            }
+        };
+
+        let mut cursor = Cursor::new(code);
+        let (_, mut message) = Self::from_reader((&mut cursor, 0))?;
+        if let Some([_, timestamp @ .., signal_level]) = header {
+            message.mlat_timestamp = Some(
+                timestamp
+                    .iter()
+                    .fold(0, |acc, byte| (acc << 8) | u64::from(*byte)),
+            );
+            message.signal_level = (*signal_level != 0).then_some(*signal_level);
+        }
+        Ok(message)
+    }
+
+    /// The Mode A code as four octal digits
+    #[must_use]
+    pub fn get_squawk(&self) -> String {
+        format!("{:04X}", self.code & 0x7777)
+    }
+
+    /// The Mode C altitude in feet, if the code is a valid Gillham code
+    #[must_use]
+    pub fn get_altitude(&self) -> Option<i32> {
+        mode_a_to_mode_c(u32::from(self.code & 0x7777))
+            .ok()
+            .and_then(|altitude| i32::try_from(altitude).ok())
+            .map(|altitude| altitude * 100)
+    }
+
+    /// Special position identification pulse
+    #[must_use]
+    pub const fn is_spi(&self) -> bool {
+        self.code & 0x0080 != 0
+    }
+
+    /// Converts `ModeACMessage` to `String`.
+    /// # Errors
+    /// If the conversion to a `String` fails, the error is returned.
+    pub fn to_string(&self) -> MessageResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Converts `ModeACMessage` to `String` and appends a `\n` to the end.
+    /// # Errors
+    /// If the conversion to a `String` fails, the error is returned.
+    pub fn to_string_newline(&self) -> MessageResult<String> {
+        Ok(format!("{}\n", self.to_string()?))
+    }
+
+    #[must_use]
+    pub fn pretty_print(&self) -> String {
+        let mut output = String::new();
+        pretty_print_label("Mode A/C Message", &mut output);
+        pretty_print_field("", &self, &mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ADSBMessage, DecodeMessage};
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn decode_mode_ac() {
+        "debug".enable_logging();
+
+        // squawk 7700 isn't a valid altitude
+        let message = "7700".to_mode_ac().unwrap();
+        assert_eq!(message.get_squawk(), "7700");
+        assert_eq!(message.get_altitude(), None);
+
+        // A4 and C2 read as Mode C
+        let message = "4020".to_mode_ac().unwrap();
+        assert_eq!(message.get_squawk(), "4020");
+        assert_eq!(message.get_altitude(), Some(6500));
+        assert!(!message.is_spi());
+
+        let beast = [0x31, 0, 0, 0, 0x12, 0x34, 0x56, 0x40, 0x12, 0x34];
+        let message = ModeACMessage::from_bytes(&beast).unwrap();
+        assert_eq!(message.get_squawk(), "1234");
+        assert_eq!(message.mlat_timestamp, Some(0x0012_3456));
+        assert_eq!(message.signal_level, Some(0x40));
+        assert_eq!("7700".to_mode_ac().unwrap().mlat_timestamp, None);
+
+        assert!(ModeACMessage::from_bytes(&[0x12, 0x34, 0x56]).is_err());
+
+        let decoded = beast.as_slice().decode_message().unwrap();
+        assert!(matches!(decoded, ADSBMessage::ModeACMessage(m) if m.code == 0x1234));
+    }
+}
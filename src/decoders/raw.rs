@@ -7,14 +7,22 @@
 // With MASSIVE thanks to https://github.com/rsadsb/adsb_deku
 
 use crate::MessageResult;
+use crate::helpers::encode_adsb_raw_output::encode_extended_squitter;
 use deku::no_std_io::{Cursor, Read, Seek};
 use deku::prelude::*;
 use hex;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self};
 
+use super::helpers::error_correction::{ErrorCorrection, correct_errors};
 use super::helpers::prettyprint::{pretty_print_field, pretty_print_label};
-use super::raw_types::{df::DF, helper_functions::modes_checksum};
+use super::raw_types::{crcstatus::CRCStatus, df::DF, helper_functions::modes_checksum};
+use super::rawtojson::message_type_from_control_field;
+
+/// Frame type, 6 bytes of MLAT timestamp and the signal level in front of a timestamped AVR frame
+const AVR_HEADER_BYTES: usize = 8;
+const AVR_SHORT_FRAME_BYTES: usize = 7;
+const AVR_LONG_FRAME_BYTES: usize = 14;
 
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
@@ -121,11 +129,21 @@ impl<R: Read + Seek> Seek for ReaderCrc<R> {
 }
 
 /// Downlink ADS-B Packet
+///
+/// Frames from AVR `@` and `%` lines also carry the MLAT timestamp and signal level, as a Beast frame does.
 #[derive(Serialize, Deserialize, DekuRead, Debug, Clone, PartialEq)]
 pub struct AdsbRawMessage {
     /// Starting with 5 bit identifier, decode packet
     pub df: DF,
     pub crc: u32,
+    /// 48 bit MLAT timestamp of an `@` or `%` line
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub mlat_timestamp: Option<u64>,
+    /// Signal level of a `%` line
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub signal_level: Option<u8>,
 }
 
 impl fmt::Display for AdsbRawMessage {
@@ -166,7 +184,7 @@ impl fmt::Display for AdsbRawMessage {
                 writeln!(f, "  ICAO Address:  {icao} (Mode S / ADS-B)")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
             }
-            DF::LongAirAir { altitude, .. } => {
+            DF::LongAirAir { altitude, mv, .. } => {
                 writeln!(f, " Long Air-Air ACAS")?;
                 writeln!(f, "  ICAO Address:  {crc:06X} (Mode S / ADS-B)")?;
                 if altitude.0 > 0 {
@@ -176,6 +194,7 @@ impl fmt::Display for AdsbRawMessage {
                 } else {
                     writeln!(f, "  Air/Ground:    ground")?;
                 }
+                write!(f, "{mv}")?;
             }
             DF::ADSB(adsb) => {
                 write!(f, "{}", adsb.to_string("(Mode S / ADS-B)"))?;
@@ -220,6 +239,53 @@ impl AdsbRawMessage {
         Self::from_reader(cursor)
     }
 
+    /// Decode a frame from `ADSBRawFramer` or `format_adsb_raw_frames_from_bytes`.
+    ///
+    /// Frames from `@` and `%` lines come with a Beast style header of frame type, MLAT timestamp
+    /// and signal level, which are kept on the message. A signal level of 0 means the line had none.
+    /// # Errors
+    /// If the frame can't be decoded, the error is returned.
+    pub fn from_avr_bytes(buf: &[u8]) -> Result<Self, DekuError> {
+        let Some((header, frame)) = buf.split_first_chunk::<AVR_HEADER_BYTES>() else {
+            return Self::from_bytes(buf);
+        };
+        if !matches!(frame.len(), AVR_SHORT_FRAME_BYTES | AVR_LONG_FRAME_BYTES) {
+            return Self::from_bytes(buf);
+        }
+
+        let [_, timestamp @ .., signal_level] = *header;
+        let mut message = Self::from_bytes(frame)?;
+        message.mlat_timestamp = Some(
+            timestamp
+                .iter()
+                .fold(0, |acc, byte| (acc << 8) | u64::from(*byte)),
+        );
+        message.signal_level = (signal_level != 0).then_some(signal_level);
+        Ok(message)
+    }
+
+    /// Decode a frame, first repairing up to `error_correction` flipped bits if it is a DF17/DF18 that fails the CRC check.
+    ///
+    /// Also returns the positions of the repaired bits, counted from the first bit of the DF field. It is empty if nothing was repaired.
+    /// # Errors
+    /// If the frame can't be decoded, the error is returned.
+    pub fn from_bytes_with_error_correction(
+        buf: &[u8],
+        error_correction: ErrorCorrection,
+    ) -> Result<(Self, Vec<usize>), DekuError> {
+        let mut repaired = buf.to_vec();
+        let corrected_bits = correct_errors(&mut repaired, error_correction).unwrap_or_default();
+
+        Ok((Self::from_bytes(&repaired)?, corrected_bits))
+    }
+
+    /// Encode the message back to a 14 byte frame, with the parity computed again. See `encode_extended_squitter`.
+    /// # Errors
+    /// If the message is not a DF17 or DF18, or a field can't be sent, the error is returned.
+    pub fn to_frame_bytes(&self) -> MessageResult<Vec<u8>> {
+        encode_extended_squitter(&self.df)
+    }
+
     /// # Errors
     /// If the conversion to a `String` fails, the error is returned.
     pub fn from_reader<R: Read + Seek>(r: R) -> Result<Self, DekuError> {
@@ -233,7 +299,12 @@ impl AdsbRawMessage {
 
         let crc = Self::read_crc(&df, &mut reader_crc)?;
 
-        Ok(Self { df, crc })
+        Ok(Self {
+            df,
+            crc,
+            mlat_timestamp: None,
+            signal_level: None,
+        })
     }
 
     /// Read rest as CRC bits
@@ -314,6 +385,63 @@ impl AdsbRawMessage {
         }
     }
 
+    /// Check the parity of the frame against the computed CRC.
+    ///
+    /// Extended squitters (DF17/18/19) carry plain parity, DF11 parity may be XOR-ed with the
+    /// interrogator code. All other formats overlay the address on the parity.
+    #[must_use]
+    pub fn crc_status(&self) -> CRCStatus {
+        match &self.df {
+            DF::ADSB(_) | DF::TisB { .. } | DF::ExtendedQuitterMilitaryApplication { .. } => {
+                if self.crc == 0 {
+                    CRCStatus::Clean
+                } else {
+                    CRCStatus::Corrupt
+                }
+            }
+            DF::AllCallReply { .. } => {
+                if self.crc & 0x00ff_ff80 == 0 {
+                    CRCStatus::Clean
+                } else {
+                    CRCStatus::Corrupt
+                }
+            }
+            _ => CRCStatus::AddressRecovered,
+        }
+    }
+
+    /// The address of the transponder that sent the message, as a hex string.
+    ///
+    /// DF11 and DF17 announce it in the clear. For the other surveillance and Comm-B replies the
+    /// AP field is the parity XOR-ed with the address, so the computed CRC is the address itself.
+    /// A DF11 is only trusted if its parity checks out, apart from the interrogator code.
+    /// DF18 addresses that aren't ICAO addresses get a `~` prefix, as readsb does, so they
+    /// can't collide with a real aircraft.
+    #[must_use]
+    pub fn get_transponder_hex(&self) -> Option<String> {
+        match &self.df {
+            DF::ADSB(adsb) => Some(adsb.icao.to_string()),
+            DF::TisB { cf, .. } => {
+                message_type_from_control_field(cf)?;
+                if cf.is_icao_address() {
+                    Some(cf.aa.to_string())
+                } else {
+                    Some(format!("~{}", cf.aa))
+                }
+            }
+            DF::AllCallReply { icao, .. } => {
+                (!self.crc_status().is_corrupt()).then(|| icao.to_string())
+            }
+            DF::ShortAirAirSurveillance { .. }
+            | DF::SurveillanceAltitudeReply { .. }
+            | DF::SurveillanceIdentityReply { .. }
+            | DF::LongAirAir { .. }
+            | DF::CommBAltitudeReply { .. }
+            | DF::CommBIdentityReply { .. } => Some(format!("{:06X}", self.crc)),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn get_time(&self) -> Option<f64> {
         Some(0.0)
@@ -323,6 +451,7 @@ impl AdsbRawMessage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::DecodeMessage;
     use sdre_rust_logging::SetupLogging;
 
     #[test]
@@ -339,4 +468,103 @@ mod tests {
         info!("Result: {result:?}");
         assert!(result.is_ok(), "Failed to decode message: {result:?}");
     }
+
+    #[test]
+    fn test_transponder_hex_from_parity() {
+        "debug".enable_logging();
+
+        let altitude_reply = "2000183859C38D".to_adsb_raw().unwrap();
+        assert_eq!(
+            altitude_reply.get_transponder_hex(),
+            Some("4840D6".to_string())
+        );
+
+        let all_call = "5D4840D6F8740F".to_adsb_raw().unwrap();
+        assert_eq!(all_call.get_transponder_hex(), Some("4840D6".to_string()));
+
+        // parity does not check out, the announced address can't be trusted
+        let all_call = "5D4840D6EA4059".to_adsb_raw().unwrap();
+        assert_eq!(all_call.get_transponder_hex(), None);
+
+        // fine TIS-B with the IMF bit set and relayed anonymous addresses aren't ICAO addresses
+        let tisb = "92A1A3CC9909B814F00412B2FF02".to_adsb_raw().unwrap();
+        assert_eq!(tisb.get_transponder_hex(), Some("A1A3CC".to_string()));
+        let tisb = "92A1A3CC9989B814F0041223387D".to_adsb_raw().unwrap();
+        assert_eq!(tisb.get_transponder_hex(), Some("~A1A3CC".to_string()));
+        let relay = "95A1A3CC9909B814F00412C45D63".to_adsb_raw().unwrap();
+        assert_eq!(relay.get_transponder_hex(), Some("~A1A3CC".to_string()));
+    }
+
+    #[test]
+    fn test_crc_status() {
+        "debug".enable_logging();
+
+        let clean = "8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap();
+        assert_eq!(clean.crc_status(), CRCStatus::Clean);
+
+        let altitude_reply = "2000183859C38D".to_adsb_raw().unwrap();
+        assert_eq!(altitude_reply.crc_status(), CRCStatus::AddressRecovered);
+
+        // one bit flipped in the ME field
+        let corrupt = "8D4840D6202CC371C32CE1576098";
+        assert_eq!(
+            corrupt.to_adsb_raw().unwrap().crc_status(),
+            CRCStatus::Corrupt
+        );
+        assert!(corrupt.decode_message().is_err());
+        assert!(corrupt.decode_message_with_crc_check(false).is_ok());
+    }
+
+    #[test]
+    fn test_error_correction() {
+        "debug".enable_logging();
+
+        let corrupt = hex::decode("8D4840D6202CC371C32CE1576098").unwrap();
+        let (message, corrected_bits) =
+            AdsbRawMessage::from_bytes_with_error_correction(&corrupt, ErrorCorrection::SingleBit)
+                .unwrap();
+        assert_eq!(message.crc_status(), CRCStatus::Clean);
+        assert_eq!(corrected_bits, vec![87]);
+
+        // through the decode path, which only repairs when asked to
+        let clean_message = "8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap();
+        let corrupt = "8D4840D6202CC371C32CE1576098";
+        assert!(corrupt.decode_message().is_err());
+        assert!(
+            corrupt
+                .decode_message_with_error_correction(ErrorCorrection::Disabled, true)
+                .is_err()
+        );
+        let repaired = corrupt
+            .decode_message_with_error_correction(ErrorCorrection::SingleBit, true)
+            .unwrap();
+        assert!(
+            matches!(repaired, crate::ADSBMessage::AdsbRawMessage(message) if message == clean_message)
+        );
+
+        // the same frame as an AVR line, and one with a timestamp and signal level
+        let corrupt = "*8D4840D6202CC371C32CE1576098;";
+        assert!(corrupt.decode_message().is_err());
+        let repaired = corrupt
+            .decode_message_with_error_correction(ErrorCorrection::SingleBit, true)
+            .unwrap();
+        assert!(
+            matches!(repaired, crate::ADSBMessage::AdsbRawMessage(message) if message == clean_message)
+        );
+        let repaired = "%0001B5BF520C9F8D4840D6202CC371C32CE1576098;\n"
+            .to_string()
+            .decode_message_with_error_correction(ErrorCorrection::SingleBit, true)
+            .unwrap();
+        assert!(
+            matches!(repaired, crate::ADSBMessage::AdsbRawMessage(message) if message.df == clean_message.df && message.signal_level == Some(0x9F))
+        );
+
+        // two flipped bits are too many for a single bit repair
+        let corrupt = "8D4840D6202CC371C32CE1576099";
+        assert!(
+            corrupt
+                .decode_message_with_error_correction(ErrorCorrection::SingleBit, true)
+                .is_err()
+        );
+    }
 }
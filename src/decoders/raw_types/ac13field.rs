@@ -6,13 +6,21 @@
 
 use super::helper_functions::{decode_id13_field, mode_a_to_mode_c};
 use deku::ctx::{BitSize, Endian};
-use deku::no_std_io::{Read, Seek};
+use deku::no_std_io::{Read, Seek, Write};
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 
 /// 13 bit encoded altitude
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
-pub struct AC13Field(#[deku(reader = "Self::read(deku::reader)")] pub u16);
+#[derive(
+    Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
+pub struct AC13Field(
+    #[deku(
+        reader = "Self::read(deku::reader)",
+        writer = "Self::write(deku::writer, *field_0)"
+    )]
+    pub u16,
+);
 
 impl AC13Field {
     // TODO Add unit
@@ -49,4 +57,22 @@ impl AC13Field {
             }
         }
     }
+
+    /// Altitudes are written in 25 ft steps with the Q bit set, 0 as no altitude
+    fn write<W: Write + Seek>(writer: &mut Writer<W>, altitude: u16) -> Result<(), DekuError> {
+        let n = u32::from(altitude) + 1000;
+        let encoded = match altitude {
+            0 => 0,
+            _ if n % 25 == 0 && n / 25 <= 0x07ff => {
+                let n = n / 25;
+                ((n & 0x07e0) << 2) | ((n & 0x0010) << 1) | 0x0010 | (n & 0x000f)
+            }
+            _ => {
+                return Err(DekuError::InvalidParam(
+                    format!("{altitude} ft can't be encoded in 25 ft steps").into(),
+                ));
+            }
+        };
+        encoded.to_writer(writer, (Endian::Big, BitSize(13)))
+    }
 }
@@ -0,0 +1,315 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::{
+    resolutionadvisorycomplement::ResolutionAdvisoryComplement, threatidentity::ThreatIdentity,
+};
+
+/// Active resolution advisory, interpreted from the ARA bits and the MTE flag (4.3.8.4.2.2.1.1)
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ActiveResolutionAdvisory {
+    /// No vertical RA has been generated
+    NoVerticalRA,
+
+    /// An RA against one or more threats, ARA bit 41 set
+    ThreatRA {
+        corrective: bool,
+        downward_sense: bool,
+        increased_rate: bool,
+        sense_reversal: bool,
+        altitude_crossing: bool,
+        /// Positive climb or descend, otherwise a vertical speed limit
+        positive: bool,
+    },
+
+    /// Separate RAs against multiple threats, ARA bit 41 clear and MTE set
+    MultipleThreatRA {
+        correction_upward: bool,
+        positive_climb: bool,
+        correction_downward: bool,
+        positive_descend: bool,
+        crossing: bool,
+        sense_reversal: bool,
+    },
+}
+
+impl fmt::Display for ActiveResolutionAdvisory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let flags: Vec<&str> = match self {
+            Self::NoVerticalRA => return write!(f, "no vertical RA"),
+            Self::ThreatRA {
+                corrective,
+                downward_sense,
+                increased_rate,
+                sense_reversal,
+                altitude_crossing,
+                positive,
+            } => [
+                (
+                    true,
+                    if *corrective {
+                        "corrective"
+                    } else {
+                        "preventive"
+                    },
+                ),
+                (
+                    true,
+                    if *downward_sense {
+                        "downward"
+                    } else {
+                        "upward"
+                    },
+                ),
+                (*increased_rate, "increased rate"),
+                (*sense_reversal, "sense reversal"),
+                (*altitude_crossing, "altitude crossing"),
+                (
+                    true,
+                    if *positive {
+                        "positive"
+                    } else {
+                        "vertical speed limit"
+                    },
+                ),
+            ]
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, text)| *text)
+            .collect(),
+            Self::MultipleThreatRA {
+                correction_upward,
+                positive_climb,
+                correction_downward,
+                positive_descend,
+                crossing,
+                sense_reversal,
+            } => [
+                (*correction_upward, "correction upward"),
+                (*positive_climb, "positive climb"),
+                (*correction_downward, "correction downward"),
+                (*positive_descend, "positive descend"),
+                (*crossing, "crossing"),
+                (*sense_reversal, "sense reversal"),
+            ]
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, text)| *text)
+            .collect(),
+        };
+
+        write!(f, "{}", flags.join(", "))
+    }
+}
+
+/// VDS of the MV field / BDS register number that carries an RA report
+pub const ACAS_RA_REGISTER: u8 = 0x30;
+
+/// ACAS Resolution Advisory report (Table A-2-48). Carried in the MV field of DF16 and in
+/// Comm-B BDS 3,0 after the register number, and in the ADS-B RA broadcast (TC28 subtype 2)
+/// after the type and subtype.
+#[derive(
+    Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
+pub struct ACASResolutionAdvisory {
+    /// ARA: Active Resolution Advisories
+    #[deku(bits = "14", endian = "big")]
+    pub ara: u16,
+    /// RAC: Resolution Advisory Complements record
+    pub rac: ResolutionAdvisoryComplement,
+    /// RAT: the RA has been terminated
+    #[deku(bits = "1")]
+    pub ra_terminated: bool,
+    /// MTE: Multiple Threat Encounter
+    #[deku(bits = "1")]
+    pub multiple_threat_encounter: bool,
+    /// TTI: Threat Type Indicator
+    #[deku(bits = "2")]
+    pub threat_type: u8,
+    #[deku(ctx = "*threat_type")]
+    pub threat_identity: ThreatIdentity,
+}
+
+impl fmt::Display for ACASResolutionAdvisory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  ACAS RA:       {}",
+            self.get_active_resolution_advisory()
+        )?;
+        writeln!(f, "  RA complement: {}", self.rac)?;
+        writeln!(f, "  RA terminated: {}", self.ra_terminated)?;
+        writeln!(f, "  Multi threat:  {}", self.multiple_threat_encounter)?;
+        writeln!(f, "  Threat:        {}", self.threat_identity)?;
+        Ok(())
+    }
+}
+
+impl ACASResolutionAdvisory {
+    /// Checks the report is plausible: the threat type is assigned and the ARA bits reserved
+    /// for ACAS III are clear.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.threat_type != 3
+            && self.get_acas_iii_bits() == 0
+            && match self.threat_identity {
+                ThreatIdentity::ModeSAddress { reserved, .. } => reserved == 0,
+                _ => true,
+            }
+    }
+
+    /// Decode the 56 bit MV / MB field, which has to start with [`ACAS_RA_REGISTER`]
+    #[must_use]
+    pub fn from_register(field: &[u8; 7]) -> Option<Self> {
+        if field[0] != ACAS_RA_REGISTER {
+            return None;
+        }
+
+        Self::from_bytes((&field[1..], 0))
+            .ok()
+            .map(|(_, ra)| ra)
+            .filter(Self::is_valid)
+    }
+
+    /// ARA bit `bit`, numbered as in the DF16 message (41-54)
+    const fn ara_bit(&self, bit: u16) -> bool {
+        (self.ara >> (54 - bit)) & 1 == 1
+    }
+
+    /// ARA bits 48-54, reserved for ACAS III
+    #[must_use]
+    pub const fn get_acas_iii_bits(&self) -> u16 {
+        self.ara & 0x7F
+    }
+
+    #[must_use]
+    pub const fn get_active_resolution_advisory(&self) -> ActiveResolutionAdvisory {
+        if self.ara_bit(41) {
+            ActiveResolutionAdvisory::ThreatRA {
+                corrective: self.ara_bit(42),
+                downward_sense: self.ara_bit(43),
+                increased_rate: self.ara_bit(44),
+                sense_reversal: self.ara_bit(45),
+                altitude_crossing: self.ara_bit(46),
+                positive: self.ara_bit(47),
+            }
+        } else if self.multiple_threat_encounter {
+            ActiveResolutionAdvisory::MultipleThreatRA {
+                correction_upward: self.ara_bit(42),
+                positive_climb: self.ara_bit(43),
+                correction_downward: self.ara_bit(44),
+                positive_descend: self.ara_bit(45),
+                crossing: self.ara_bit(46),
+                sense_reversal: self.ara_bit(47),
+            }
+        } else {
+            ActiveResolutionAdvisory::NoVerticalRA
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::bds::BDS;
+    use crate::decoders::raw_types::df::DF;
+    use crate::decoders::raw_types::icao::ICAO;
+    use crate::decoders::raw_types::mvfield::MVField;
+
+    use super::*;
+
+    #[test]
+    fn decode_resolution_advisory_long_air_air() {
+        "debug".enable_logging();
+
+        let message = "80E1969030C2020686CB0C7DCC98";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded}");
+
+        assert_eq!(decoded.crc, 0x0048_40D6);
+
+        match decoded.df {
+            DF::LongAirAir { mv, .. } => match mv {
+                MVField::ResolutionAdvisory(ra) => {
+                    assert!(ra.is_valid());
+                    assert_eq!(
+                        ra.get_active_resolution_advisory(),
+                        ActiveResolutionAdvisory::ThreatRA {
+                            corrective: true,
+                            downward_sense: false,
+                            increased_rate: false,
+                            sense_reversal: false,
+                            altitude_crossing: false,
+                            positive: true,
+                        }
+                    );
+                    assert!(ra.rac.do_not_pass_below);
+                    assert!(!ra.ra_terminated);
+                    assert!(!ra.multiple_threat_encounter);
+                    assert_eq!(
+                        ra.threat_identity,
+                        ThreatIdentity::ModeSAddress {
+                            address: ICAO([0xA1, 0xB2, 0xC3]),
+                            reserved: 0
+                        }
+                    );
+                }
+                MVField::Unknown(_) => panic!("MV is not a Resolution Advisory: {mv:?}"),
+            },
+            _ => panic!("DF is not Long Air-Air"),
+        }
+    }
+
+    #[test]
+    fn decode_resolution_advisory_comm_b() {
+        "debug".enable_logging();
+
+        let message = "A00016903060003AB7069042F607";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded}");
+
+        assert_eq!(decoded.crc, 0x0048_40D6);
+
+        match decoded.df {
+            DF::CommBAltitudeReply { bds, .. } => match bds {
+                BDS::ACASResolutionAdvisory(ra) => {
+                    assert!(ra.is_valid());
+                    assert_eq!(
+                        ra.get_active_resolution_advisory(),
+                        ActiveResolutionAdvisory::MultipleThreatRA {
+                            correction_upward: true,
+                            positive_climb: true,
+                            correction_downward: false,
+                            positive_descend: false,
+                            crossing: false,
+                            sense_reversal: false,
+                        }
+                    );
+                    assert!(ra.rac.is_empty());
+                    assert!(ra.ra_terminated);
+                    assert!(ra.multiple_threat_encounter);
+                    match ra.threat_identity {
+                        ThreatIdentity::AltitudeRangeBearing { altitude, .. } => {
+                            assert_eq!(altitude.0, 34000);
+                        }
+                        _ => panic!("Threat identity is not altitude, range and bearing"),
+                    }
+                    assert_eq!(ra.threat_identity.get_range(), Some(2.5));
+                    assert_eq!(ra.threat_identity.get_bearing(), Some(90));
+                }
+                _ => panic!("BDS is not ACAS Resolution Advisory: {bds:?}"),
+            },
+            _ => panic!("DF is not Comm-B Altitude Reply"),
+        }
+    }
+}
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{capability::Capability, icao::ICAO, me::ME};
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Adsb {
     // Transponder Capability
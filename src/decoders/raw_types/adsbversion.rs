@@ -11,8 +11,9 @@ use std::fmt::{self, Formatter};
 /// ADS-B Defined from different ICAO documents
 ///
 /// reference: ICAO 9871 (5.3.2.3)
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
 #[deku(id_type = "u8", bits = "3")]
+#[repr(u8)]
 pub enum ADSBVersion {
     #[deku(id = "0")]
     ADSBVersion0,
@@ -22,8 +23,9 @@ pub enum ADSBVersion {
     ADSBVersion2,
     #[deku(id = "3")]
     ADSBVersion3,
+    /// Versions not defined yet, written back as 7
     #[deku(id_pat = "_")]
-    Unknown,
+    Unknown = 7,
 }
 
 impl fmt::Display for ADSBVersion {
@@ -4,6 +4,8 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
+use deku::ctx::{BitSize, Endian};
+use deku::no_std_io::{Seek, Write};
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -15,7 +17,7 @@ use super::{
 };
 
 /// [`ME::AirborneVelocity`]
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq)]
 pub struct AirborneVelocity {
     #[deku(bits = "3")]
     pub st: u8,
@@ -36,12 +38,29 @@ pub struct AirborneVelocity {
     pub gnss_sign: Sign,
     #[deku(
         bits = "7",
-        map = "|gnss_baro_diff: u16| -> Result<_, DekuError> {Ok(if gnss_baro_diff > 1 {(gnss_baro_diff - 1)* 25} else { 0 })}"
+        map = "|gnss_baro_diff: u16| -> Result<_, DekuError> {Ok(if gnss_baro_diff > 1 {(gnss_baro_diff - 1)* 25} else { 0 })}",
+        writer = "Self::write_gnss_baro_diff(deku::writer, *gnss_baro_diff)"
     )]
     pub gnss_baro_diff: u16,
 }
 
 impl AirborneVelocity {
+    fn write_gnss_baro_diff<W: Write + Seek>(
+        writer: &mut Writer<W>,
+        gnss_baro_diff: u16,
+    ) -> Result<(), DekuError> {
+        let encoded = match gnss_baro_diff {
+            0 => 0,
+            diff if diff % 25 == 0 && diff / 25 < 0x7f => diff / 25 + 1,
+            diff => {
+                return Err(DekuError::InvalidParam(
+                    format!("GNSS/baro difference of {diff} ft can't be encoded").into(),
+                ));
+            }
+        };
+        encoded.to_writer(writer, (Endian::Big, BitSize(7)))
+    }
+
     #[must_use]
     pub const fn is_reserved_zero(&self) -> bool {
         self.reserved1 == 0 && self.reserved2 == 0
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use super::{airspeeddecoding::AirspeedDecoding, groundspeeddecoding::GroundSpeedDecoding};
 
 /// Airborne Velocity Message “Subtype” Code Field Encoding
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq)]
 #[deku(ctx = "st: u8", id = "st")]
 pub enum AirborneVelocitySubType {
     #[deku(id = "0")]
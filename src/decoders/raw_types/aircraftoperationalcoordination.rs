@@ -0,0 +1,62 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// [`ME::AircraftOperationalCoordination`], TC30
+///
+/// DO-260B reserves this type code for aircraft operational coordination but does not
+/// publish a format for the content, so only the subtype is decoded and the rest is kept as
+/// it was received.
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AircraftOperationalCoordination {
+    #[deku(bits = "3")]
+    pub sub_type: u8,
+    pub reserved: [u8; 6],
+}
+
+impl fmt::Display for AircraftOperationalCoordination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Subtype:       {}", self.sub_type)?;
+        writeln!(f, "  Data:          {}", hex::encode_upper(self.reserved))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use super::*;
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::df::DF;
+    use crate::decoders::raw_types::icao::ICAO;
+    use crate::decoders::raw_types::me::ME;
+
+    #[test]
+    fn decode_aircraft_operational_coordination() {
+        "debug".enable_logging();
+
+        let message = "8D4840D6F0CAFEBABE00425E361A";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded}");
+
+        match decoded.df {
+            DF::ADSB(adsb) => {
+                assert_eq!(
+                    adsb.me,
+                    ME::AircraftOperationalCoordination(AircraftOperationalCoordination {
+                        sub_type: 0,
+                        reserved: [0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x42],
+                    })
+                );
+                assert_eq!(adsb.pi, ICAO([0x5E, 0x36, 0x1A]));
+            }
+            _ => panic!("Wrong DF"),
+        }
+    }
+}
@@ -5,40 +5,54 @@
 // https://opensource.org/licenses/MIT.
 
 use deku::prelude::*;
-use radix_fmt::radix;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    aircraftstatustype::AircraftStatusType, emergencystate::EmergencyState,
-    helper_functions::decode_id13_field,
+    acasresolutionadvisory::ACASResolutionAdvisory, aircraftstatustype::AircraftStatusType,
+    emergencyprioritystatus::EmergencyPriorityStatus,
 };
 
-// FIXME: there appear to be 4 different variants of this message type.
+/// First ME byte of the ACAS RA broadcast, type code 28 and subtype 2
+pub const ACAS_RA_BROADCAST_HEADER: u8 = (28 << 3) | 2;
 
+/// Aircraft Status, TC28. The subtype decides what the remaining 48 bits hold.
+///
 /// Table: A-2-97
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
-pub struct AircraftStatus {
-    pub sub_type: AircraftStatusType,
-    pub emergency_state: EmergencyState,
-    #[deku(
-        bits = "13",
-        endian = "big",
-        map = "|squawk: u32| -> Result<_, DekuError> {Ok(decode_id13_field(squawk))}"
-    )]
-    pub squawk: u32,
-    #[deku(bits = "32")]
-    pub reserved: u32,
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
+#[deku(id_type = "u8", bits = "3")]
+pub enum AircraftStatus {
+    #[deku(id = "0")]
+    NoInformation([u8; 6]),
+
+    #[deku(id = "1")]
+    EmergencyPriorityStatus(EmergencyPriorityStatus),
+
+    /// Subtype 2, the RA broadcast from ACAS (Table A-2-97B)
+    #[deku(id = "2")]
+    ACASRaBroadcast(ACASResolutionAdvisory),
+
+    #[deku(id_pat = "3..=7")]
+    Reserved(u8, [u8; 6]),
 }
 
 impl AircraftStatus {
     #[must_use]
     pub const fn is_reserved_zero(&self) -> bool {
-        self.reserved == 0
+        match self {
+            Self::EmergencyPriorityStatus(status) => status.is_reserved_zero(),
+            Self::ACASRaBroadcast(ra) => ra.get_acas_iii_bits() == 0,
+            Self::NoInformation(_) | Self::Reserved(..) => true,
+        }
     }
 
     #[must_use]
-    pub fn get_squawk_as_octal_string(&self) -> String {
-        format!("{:04}", radix(self.squawk, 16))
+    pub const fn get_sub_type(&self) -> AircraftStatusType {
+        match self {
+            Self::NoInformation(_) => AircraftStatusType::NoInformation,
+            Self::EmergencyPriorityStatus(_) => AircraftStatusType::EmergencyPriorityStatus,
+            Self::ACASRaBroadcast(_) => AircraftStatusType::ACASRaBroadcast,
+            Self::Reserved(..) => AircraftStatusType::Reserved,
+        }
     }
 }
 
@@ -48,8 +62,12 @@ pub mod test {
 
     use super::*;
     use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::acasresolutionadvisory::ActiveResolutionAdvisory;
     use crate::decoders::raw_types::aircraftstatus::AircraftStatus;
     use crate::decoders::raw_types::df::DF;
+    use crate::decoders::raw_types::emergencystate::EmergencyState;
+    use crate::decoders::raw_types::icao::ICAO;
+    use crate::decoders::raw_types::threatidentity::ThreatIdentity;
 
     #[test]
     fn decode_aircraftstatus() {
@@ -60,17 +78,60 @@ pub mod test {
 
         info!("{decoded:?}");
 
-        let expected = AircraftStatus {
-            sub_type: AircraftStatusType::EmergencyPriorityStatus,
+        let expected = AircraftStatus::EmergencyPriorityStatus(EmergencyPriorityStatus {
             emergency_state: EmergencyState::None,
             squawk: 25092,
             reserved: 0,
-        };
+        });
 
         match decoded.df {
             DF::ADSB(adsb) => match adsb.me {
                 crate::decoders::raw_types::me::ME::AircraftStatus(status) => {
                     assert_eq!(status, expected);
+                    assert_eq!(
+                        status.get_sub_type(),
+                        AircraftStatusType::EmergencyPriorityStatus
+                    );
+                }
+                _ => panic!("Wrong ME"),
+            },
+            _ => panic!("Wrong DF"),
+        }
+    }
+
+    #[test]
+    fn decode_aircraftstatus_ra_broadcast() {
+        "debug".enable_logging();
+
+        let message = "8D4840D6E2C2020686CB0CA902E3";
+        let decoded = message.to_adsb_raw().unwrap();
+
+        info!("{decoded:?}");
+
+        match decoded.df {
+            DF::ADSB(adsb) => match adsb.me {
+                crate::decoders::raw_types::me::ME::AircraftStatus(
+                    AircraftStatus::ACASRaBroadcast(ra),
+                ) => {
+                    assert!(ra.is_valid());
+                    assert_eq!(
+                        ra.get_active_resolution_advisory(),
+                        ActiveResolutionAdvisory::ThreatRA {
+                            corrective: true,
+                            downward_sense: false,
+                            increased_rate: false,
+                            sense_reversal: false,
+                            altitude_crossing: false,
+                            positive: true,
+                        }
+                    );
+                    assert_eq!(
+                        ra.threat_identity,
+                        ThreatIdentity::ModeSAddress {
+                            address: ICAO([0xA1, 0xB2, 0xC3]),
+                            reserved: 0
+                        }
+                    );
                 }
                 _ => panic!("Wrong ME"),
             },
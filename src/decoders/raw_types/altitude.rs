@@ -6,13 +6,13 @@
 
 use crate::decoders::common_types::surveillancestatus::SurveillanceStatus;
 use deku::ctx::{BitSize, Endian};
-use deku::no_std_io::{Read, Seek};
+use deku::no_std_io::{Read, Seek, Write};
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Formatter};
 
 use super::cprheaders::CPRFormat;
-use super::helper_functions::{decode_id13_field, mode_a_to_mode_c};
+use super::helper_functions::{decode_id13_field, encode_ac12_field, mode_a_to_mode_c};
 
 /// Latitude, Longitude and Altitude information
 ///
@@ -23,7 +23,17 @@ use super::helper_functions::{decode_id13_field, mode_a_to_mode_c};
 /// letting it be read (and thus the bitstream position advanced) a second
 /// time here.
 #[derive(
-    Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Default,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Default,
 )]
 #[deku(ctx = "tc: u8")]
 pub struct Altitude {
@@ -33,7 +43,10 @@ pub struct Altitude {
     #[deku(bits = "1")]
     /// nic supplement b
     pub saf_or_imf: u8,
-    #[deku(reader = "Self::read(deku::reader)")]
+    #[deku(
+        reader = "Self::read(deku::reader)",
+        writer = "Self::write(deku::writer, *alt)"
+    )]
     pub alt: Option<u16>,
     /// UTC sync or not
     #[deku(bits = "1")]
@@ -86,6 +99,13 @@ impl Altitude {
             }
         }
     }
+
+    fn write<W: Write + Seek>(
+        writer: &mut Writer<W>,
+        altitude: Option<u16>,
+    ) -> Result<(), DekuError> {
+        encode_ac12_field(altitude)?.to_writer(writer, (Endian::Big, BitSize(12)))
+    }
 }
 
 #[cfg(test)]
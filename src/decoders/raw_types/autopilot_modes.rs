@@ -8,7 +8,7 @@ use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Formatter};
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum AutopilotEngaged {
     #[deku(id = "1")]
@@ -26,7 +26,7 @@ impl fmt::Display for AutopilotEngaged {
     }
 }
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum VNAVEngaged {
     #[deku(id = "1")]
@@ -44,7 +44,7 @@ impl fmt::Display for VNAVEngaged {
     }
 }
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum AltitudeHold {
     #[deku(id = "1")]
@@ -62,7 +62,7 @@ impl fmt::Display for AltitudeHold {
     }
 }
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum ApproachMode {
     #[deku(id = "1")]
@@ -80,7 +80,7 @@ impl fmt::Display for ApproachMode {
     }
 }
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum TCAS {
     #[deku(id = "1")]
@@ -98,7 +98,7 @@ impl fmt::Display for TCAS {
     }
 }
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum LNAV {
     #[deku(id = "1")]
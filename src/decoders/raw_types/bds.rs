@@ -4,36 +4,160 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
+use deku::no_std_io::{Cursor, Read, Seek};
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Formatter};
 
 use super::{
-    datalinkcapability::DataLinkCapability, helper_functions::aircraft_identification_read,
+    acasresolutionadvisory::ACASResolutionAdvisory,
+    bdsinference::{BDSRegister, CommBContext, infer_bds},
+    datalinkcapability::DataLinkCapability,
+    headingandspeedreport::HeadingAndSpeedReport,
+    helper_functions::aircraft_identification_read,
+    meteorologicalhazardreport::MeteorologicalHazardReport,
+    meteorologicalroutineairreport::MeteorologicalRoutineAirReport,
+    selectedverticalintention::SelectedVerticalIntention,
+    trackandturnreport::TrackAndTurnReport,
 };
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq)]
-#[deku(id_type = "u8", bits = "8")]
+/// MB field of a Comm-B reply (DF20/DF21)
+///
+/// The MB field does not say which register it holds. Only BDS 1,0, 2,0 and 3,0 start with
+/// their own register number, every other register has to be recognised from its content.
+/// Because of that this type is read by hand (see `from_mb`) rather than through a deku id,
+/// and MB fields that fit more than one register equally well are left as `Unknown`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum BDS {
     /// (1, 0) Table A-2-16
-    #[deku(id = "0x00")]
     Empty([u8; 6]),
 
     /// (1, 0) Table A-2-16
-    #[deku(id = "0x10")]
     DataLinkCapability(DataLinkCapability),
 
     /// (2, 0) Table A-2-32
-    #[deku(id = "0x20")]
-    AircraftIdentification(#[deku(reader = "aircraft_identification_read(deku::reader)")] String),
+    AircraftIdentification(String),
 
-    // The leading `u8` captures the matched (unrecognized) BDS id, because
-    // deku requires the first field of an `id_pat` variant to store the
-    // matched id when the enum has no top-level `id`.
-    #[deku(id_pat = "_")]
+    /// (3, 0) Table A-2-48
+    ACASResolutionAdvisory(ACASResolutionAdvisory),
+
+    /// (4, 0) Table A-2-64
+    SelectedVerticalIntention(SelectedVerticalIntention),
+
+    /// (4, 4) Table A-2-68
+    MeteorologicalRoutineAirReport(MeteorologicalRoutineAirReport),
+
+    /// (4, 5) Table A-2-69
+    MeteorologicalHazardReport(MeteorologicalHazardReport),
+
+    /// (5, 0) Table A-2-80
+    TrackAndTurnReport(TrackAndTurnReport),
+
+    /// (6, 0) Table A-2-96
+    HeadingAndSpeedReport(HeadingAndSpeedReport),
+
+    /// The first byte of the MB field followed by the remaining six bytes.
     Unknown(u8, [u8; 6]),
 }
 
+impl DekuReader<'_, ()> for BDS {
+    fn from_reader_with_ctx<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        (): (),
+    ) -> Result<Self, DekuError> {
+        let mb = <[u8; 7]>::from_reader_with_ctx(reader, ())?;
+        Ok(Self::from_mb(&mb))
+    }
+}
+
+impl BDS {
+    /// Decode the 56 bit MB field of a Comm-B reply.
+    #[must_use]
+    pub fn from_mb(mb: &[u8; 7]) -> Self {
+        Self::from_mb_with_context(mb, &CommBContext::default())
+    }
+
+    /// Decode the 56 bit MB field of a Comm-B reply, using what is known about the aircraft to
+    /// pick a register when the content alone is ambiguous. See [`infer_bds`].
+    #[must_use]
+    pub fn from_mb_with_context(mb: &[u8; 7], context: &CommBContext) -> Self {
+        let mut rest: [u8; 6] = [0; 6];
+        rest.copy_from_slice(&mb[1..]);
+
+        if mb.iter().all(|byte| *byte == 0) {
+            return Self::Empty(rest);
+        }
+
+        match infer_bds(mb, context).best() {
+            Some(candidate) => Self::from_mb_as_register(mb, candidate.register),
+            None => Self::Unknown(mb[0], rest),
+        }
+    }
+
+    /// Decode the MB field as the given register, without checking it is plausible.
+    /// Registers that are recognised but not decoded yet are returned as `Unknown`.
+    #[must_use]
+    pub fn from_mb_as_register(mb: &[u8; 7], register: BDSRegister) -> Self {
+        let mut rest: [u8; 6] = [0; 6];
+        rest.copy_from_slice(&mb[1..]);
+
+        let decoded = match register {
+            BDSRegister::DataLinkCapability => DataLinkCapability::from_bytes((&rest, 0))
+                .ok()
+                .map(|(_, capability)| Self::DataLinkCapability(capability)),
+            BDSRegister::AircraftIdentification => {
+                let mut cursor = Cursor::new(rest);
+                let mut reader = Reader::new(&mut cursor);
+                aircraft_identification_read(&mut reader)
+                    .ok()
+                    .map(Self::AircraftIdentification)
+            }
+            BDSRegister::SelectedVerticalIntention => {
+                SelectedVerticalIntention::from_bytes((mb, 0))
+                    .ok()
+                    .map(|(_, intention)| Self::SelectedVerticalIntention(intention))
+            }
+            BDSRegister::TrackAndTurnReport => TrackAndTurnReport::from_bytes((mb, 0))
+                .ok()
+                .map(|(_, report)| Self::TrackAndTurnReport(report)),
+            BDSRegister::HeadingAndSpeedReport => HeadingAndSpeedReport::from_bytes((mb, 0))
+                .ok()
+                .map(|(_, report)| Self::HeadingAndSpeedReport(report)),
+            BDSRegister::MeteorologicalRoutineAirReport => {
+                MeteorologicalRoutineAirReport::from_bytes((mb, 0))
+                    .ok()
+                    .map(|(_, report)| Self::MeteorologicalRoutineAirReport(report))
+            }
+            BDSRegister::MeteorologicalHazardReport => {
+                MeteorologicalHazardReport::from_bytes((mb, 0))
+                    .ok()
+                    .map(|(_, report)| Self::MeteorologicalHazardReport(report))
+            }
+            BDSRegister::ACASResolutionAdvisory => ACASResolutionAdvisory::from_bytes((&rest, 0))
+                .ok()
+                .map(|(_, ra)| Self::ACASResolutionAdvisory(ra)),
+            BDSRegister::CommonUsageGICBCapability => None,
+        };
+
+        decoded.unwrap_or(Self::Unknown(mb[0], rest))
+    }
+
+    /// The raw MB field, if this variant still holds it
+    #[must_use]
+    pub fn get_mb(&self) -> Option<[u8; 7]> {
+        let (first, rest) = match self {
+            Self::Empty(rest) => (0, rest),
+            Self::Unknown(first, rest) => (*first, rest),
+            _ => return None,
+        };
+
+        let mut mb: [u8; 7] = [0; 7];
+        mb[0] = first;
+        mb[1..].copy_from_slice(rest);
+        Some(mb)
+    }
+}
+
 impl fmt::Display for BDS {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -47,6 +171,30 @@ impl fmt::Display for BDS {
             Self::DataLinkCapability(_) => {
                 writeln!(f, "Comm-B format: BDS1,0 Datalink capabilities")?;
             }
+            Self::ACASResolutionAdvisory(ra) => {
+                writeln!(f, "Comm-B format: BDS3,0 ACAS active resolution advisory")?;
+                write!(f, "{ra}")?;
+            }
+            Self::SelectedVerticalIntention(intention) => {
+                writeln!(f, "Comm-B format: BDS4,0 Selected vertical intention")?;
+                write!(f, "{intention}")?;
+            }
+            Self::MeteorologicalRoutineAirReport(report) => {
+                writeln!(f, "Comm-B format: BDS4,4 Meteorological routine air report")?;
+                write!(f, "{report}")?;
+            }
+            Self::MeteorologicalHazardReport(report) => {
+                writeln!(f, "Comm-B format: BDS4,5 Meteorological hazard report")?;
+                write!(f, "{report}")?;
+            }
+            Self::TrackAndTurnReport(report) => {
+                writeln!(f, "Comm-B format: BDS5,0 Track and turn report")?;
+                write!(f, "{report}")?;
+            }
+            Self::HeadingAndSpeedReport(report) => {
+                writeln!(f, "Comm-B format: BDS6,0 Heading and speed report")?;
+                write!(f, "{report}")?;
+            }
             Self::Unknown(..) => {
                 writeln!(f, "Comm-B format: unknown format")?;
             }
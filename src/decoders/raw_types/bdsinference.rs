@@ -0,0 +1,520 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fmt::{self, Formatter};
+
+use super::{
+    acasresolutionadvisory::ACASResolutionAdvisory, headingandspeedreport::HeadingAndSpeedReport,
+    meteorologicalhazardreport::MeteorologicalHazardReport,
+    meteorologicalroutineairreport::MeteorologicalRoutineAirReport,
+    selectedverticalintention::SelectedVerticalIntention, trackandturnreport::TrackAndTurnReport,
+};
+
+/// Score given to registers that carry their own register number in the first byte of the MB field
+const REGISTER_NUMBER_SCORE: i32 = 4;
+/// Score added (or removed) when a decoded value agrees (or disagrees) with what is known about the aircraft
+const CONTEXT_SCORE: i32 = 3;
+
+/// Characters allowed in BDS 2,0. `#` marks the codes that are not assigned.
+const IDENTIFICATION_CHARS: &[u8; 64] =
+    b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+/// Comm-B registers the inference engine knows how to recognise
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum BDSRegister {
+    /// (1, 0)
+    DataLinkCapability,
+    /// (1, 7)
+    CommonUsageGICBCapability,
+    /// (2, 0)
+    AircraftIdentification,
+    /// (3, 0)
+    ACASResolutionAdvisory,
+    /// (4, 0)
+    SelectedVerticalIntention,
+    /// (4, 4)
+    MeteorologicalRoutineAirReport,
+    /// (4, 5)
+    MeteorologicalHazardReport,
+    /// (5, 0)
+    TrackAndTurnReport,
+    /// (6, 0)
+    HeadingAndSpeedReport,
+}
+
+impl BDSRegister {
+    /// All registers, in the order they are tried
+    pub const ALL: [Self; 9] = [
+        Self::DataLinkCapability,
+        Self::CommonUsageGICBCapability,
+        Self::AircraftIdentification,
+        Self::ACASResolutionAdvisory,
+        Self::SelectedVerticalIntention,
+        Self::MeteorologicalRoutineAirReport,
+        Self::MeteorologicalHazardReport,
+        Self::TrackAndTurnReport,
+        Self::HeadingAndSpeedReport,
+    ];
+
+    /// Register number as (BDS1, BDS2)
+    #[must_use]
+    pub const fn number(&self) -> (u8, u8) {
+        match self {
+            Self::DataLinkCapability => (1, 0),
+            Self::CommonUsageGICBCapability => (1, 7),
+            Self::AircraftIdentification => (2, 0),
+            Self::ACASResolutionAdvisory => (3, 0),
+            Self::SelectedVerticalIntention => (4, 0),
+            Self::MeteorologicalRoutineAirReport => (4, 4),
+            Self::MeteorologicalHazardReport => (4, 5),
+            Self::TrackAndTurnReport => (5, 0),
+            Self::HeadingAndSpeedReport => (6, 0),
+        }
+    }
+}
+
+impl fmt::Display for BDSRegister {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (bds1, bds2) = self.number();
+        write!(f, "BDS{bds1},{bds2}")
+    }
+}
+
+/// What is already known about the aircraft that sent the reply, usually from ADS-B.
+/// Every field is optional, an empty context scores the MB field on its content alone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommBContext {
+    /// Ground speed in knots
+    pub ground_speed: Option<f64>,
+    /// True track in degrees
+    pub track: Option<f64>,
+    /// Barometric altitude in feet
+    pub altitude: Option<i32>,
+    pub callsign: Option<String>,
+}
+
+/// A register the MB field could hold, and how confident the engine is about it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BDSCandidate {
+    pub register: BDSRegister,
+    pub score: i32,
+}
+
+/// Result of scoring an MB field against every known register
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct BDSInference {
+    /// Every register the MB field is plausible for, highest score first
+    pub candidates: Vec<BDSCandidate>,
+}
+
+impl BDSInference {
+    /// The highest scoring register, if it scores strictly higher than every other candidate
+    #[must_use]
+    pub fn best(&self) -> Option<BDSCandidate> {
+        match self.candidates.as_slice() {
+            [] => None,
+            [first] => Some(*first),
+            [first, second, ..] => (first.score > second.score).then_some(*first),
+        }
+    }
+
+    /// The plausible registers other than the best match. If there is no best match this is
+    /// every candidate.
+    #[must_use]
+    pub fn others(&self) -> &[BDSCandidate] {
+        if self.best().is_some() {
+            &self.candidates[1..]
+        } else {
+            &self.candidates
+        }
+    }
+
+    /// More than one register is plausible and none of them stands out
+    #[must_use]
+    pub fn is_ambiguous(&self) -> bool {
+        self.candidates.len() > 1 && self.best().is_none()
+    }
+}
+
+/// Score a 56 bit MB field against every known register.
+///
+/// A register is only a candidate if the MB field is structurally valid for it (status bits,
+/// reserved bits, value ranges). The score starts from how much of the register is populated
+/// and is then adjusted with `context`.
+///
+/// Replies are decoded with an empty context, and the state machine only scores them again with the
+/// aircraft's context when that gave no single best register (`BDS::Unknown`). The decoded registers
+/// don't keep the MB field, so context can't overrule a plausible register picked from the content alone.
+#[must_use]
+pub fn infer_bds(mb: &[u8; 7], context: &CommBContext) -> BDSInference {
+    let data = mb
+        .iter()
+        .fold(0_u64, |acc, byte| (acc << 8) | u64::from(*byte));
+
+    if data == 0 {
+        return BDSInference::default();
+    }
+
+    let mut candidates: Vec<BDSCandidate> = BDSRegister::ALL
+        .iter()
+        .filter_map(|register| {
+            let score = match register {
+                BDSRegister::DataLinkCapability => score_data_link_capability(data),
+                BDSRegister::CommonUsageGICBCapability => score_common_usage_gicb_capability(data),
+                BDSRegister::AircraftIdentification => score_aircraft_identification(data, context),
+                BDSRegister::ACASResolutionAdvisory => score_acas_resolution_advisory(*mb),
+                BDSRegister::SelectedVerticalIntention => score_selected_vertical_intention(*mb),
+                BDSRegister::MeteorologicalRoutineAirReport => {
+                    score_meteorological_routine_air_report(*mb, context)
+                }
+                BDSRegister::MeteorologicalHazardReport => {
+                    score_meteorological_hazard_report(*mb, context)
+                }
+                BDSRegister::TrackAndTurnReport => score_track_and_turn_report(*mb, context),
+                BDSRegister::HeadingAndSpeedReport => score_heading_and_speed_report(*mb, context),
+            }?;
+
+            Some(BDSCandidate {
+                register: *register,
+                score,
+            })
+        })
+        .collect();
+
+    candidates.sort_by_key(|candidate| Reverse(candidate.score));
+
+    BDSInference { candidates }
+}
+
+/// Bits `first` to `last` of the MB field, numbered from 1 as in the ICAO tables
+const fn field(data: u64, first: u32, last: u32) -> u64 {
+    (data >> (56 - last)) & ((1 << (last - first + 1)) - 1)
+}
+
+const fn bit(data: u64, position: u32) -> bool {
+    field(data, position, position) == 1
+}
+
+fn context_score(agrees: bool) -> i32 {
+    if agrees {
+        CONTEXT_SCORE
+    } else {
+        -CONTEXT_SCORE
+    }
+}
+
+/// Smallest difference between two angles in degrees
+fn angle_difference(a: f64, b: f64) -> f64 {
+    let difference = (a - b).rem_euclid(360.0);
+    difference.min(360.0 - difference)
+}
+
+fn score_data_link_capability(data: u64) -> Option<i32> {
+    if field(data, 1, 8) != 0x10 || field(data, 10, 14) != 0 {
+        return None;
+    }
+
+    // the overlay command capability and the subnetwork version have to agree
+    let overlay = bit(data, 15);
+    let version = field(data, 17, 23);
+    if (overlay && version < 5) || (!overlay && version > 4) {
+        return None;
+    }
+
+    Some(REGISTER_NUMBER_SCORE + 1)
+}
+
+fn score_common_usage_gicb_capability(data: u64) -> Option<i32> {
+    // bits 25-56 are reserved, and any aircraft answering Comm-B supports BDS 2,0 (bit 7)
+    if field(data, 25, 56) != 0 || !bit(data, 7) {
+        return None;
+    }
+
+    Some(1)
+}
+
+fn score_aircraft_identification(data: u64, context: &CommBContext) -> Option<i32> {
+    if field(data, 1, 8) != 0x20 {
+        return None;
+    }
+
+    let callsign = (0..8)
+        .map(|index| {
+            let first = 9 + index * 6;
+            IDENTIFICATION_CHARS[usize::try_from(field(data, first, first + 5)).unwrap_or(0)]
+                as char
+        })
+        .collect::<String>();
+
+    if callsign.contains('#') {
+        return None;
+    }
+
+    let mut score = REGISTER_NUMBER_SCORE + 1;
+    if let Some(known) = &context.callsign {
+        score += context_score(known.trim() == callsign.trim());
+    }
+
+    Some(score)
+}
+
+fn score_acas_resolution_advisory(mb: [u8; 7]) -> Option<i32> {
+    ACASResolutionAdvisory::from_register(&mb)?;
+
+    Some(REGISTER_NUMBER_SCORE + 1)
+}
+
+fn score_selected_vertical_intention(mb: [u8; 7]) -> Option<i32> {
+    let (_, intention) = SelectedVerticalIntention::from_bytes((&mb, 0)).ok()?;
+    if !intention.is_valid() {
+        return None;
+    }
+
+    Some(
+        [
+            intention.mcp_fcu_altitude_status,
+            intention.fms_altitude_status,
+            intention.barometric_pressure_status,
+            intention.mode_status,
+            intention.target_altitude_source_status,
+        ]
+        .iter()
+        .map(|status| i32::from(*status))
+        .sum(),
+    )
+}
+
+fn score_meteorological_routine_air_report(mb: [u8; 7], context: &CommBContext) -> Option<i32> {
+    let (_, report) = MeteorologicalRoutineAirReport::from_bytes((&mb, 0)).ok()?;
+    if !report.is_valid() {
+        return None;
+    }
+
+    let mut score = [
+        report.wind_status,
+        report.average_static_pressure_status,
+        report.turbulence_status,
+        report.humidity_status,
+    ]
+    .iter()
+    .map(|status| i32::from(*status))
+    .sum();
+
+    if let Some(altitude) = context.altitude {
+        score += context_score(
+            (report.get_static_air_temperature() - isa_temperature(altitude)).abs() <= 25.0,
+        );
+    }
+
+    Some(score)
+}
+
+fn score_meteorological_hazard_report(mb: [u8; 7], context: &CommBContext) -> Option<i32> {
+    let (_, report) = MeteorologicalHazardReport::from_bytes((&mb, 0)).ok()?;
+    if !report.is_valid() {
+        return None;
+    }
+
+    let mut score = [
+        report.turbulence_status,
+        report.wind_shear_status,
+        report.microburst_status,
+        report.icing_status,
+        report.wake_vortex_status,
+        report.static_air_temperature_status,
+        report.average_static_pressure_status,
+        report.radio_height_status,
+    ]
+    .iter()
+    .map(|status| i32::from(*status))
+    .sum();
+
+    if let (Some(altitude), Some(temperature)) =
+        (context.altitude, report.get_static_air_temperature())
+    {
+        score += context_score((temperature - isa_temperature(altitude)).abs() <= 25.0);
+    }
+
+    Some(score)
+}
+
+fn score_track_and_turn_report(mb: [u8; 7], context: &CommBContext) -> Option<i32> {
+    let (_, report) = TrackAndTurnReport::from_bytes((&mb, 0)).ok()?;
+    if !report.is_valid() {
+        return None;
+    }
+
+    let mut score = [
+        report.roll_angle_status,
+        report.true_track_angle_status,
+        report.ground_speed_status,
+        report.track_angle_rate_status,
+        report.true_airspeed_status,
+    ]
+    .iter()
+    .map(|status| i32::from(*status))
+    .sum();
+
+    if let (Some(known), Some(ground_speed)) = (context.ground_speed, report.get_ground_speed()) {
+        score += context_score((known - f64::from(ground_speed)).abs() <= 30.0);
+    }
+
+    if let (Some(known), Some(track)) = (context.track, report.get_true_track_angle()) {
+        score += context_score(angle_difference(known, track) <= 15.0);
+    }
+
+    Some(score)
+}
+
+fn score_heading_and_speed_report(mb: [u8; 7], context: &CommBContext) -> Option<i32> {
+    let (_, report) = HeadingAndSpeedReport::from_bytes((&mb, 0)).ok()?;
+    if !report.is_valid() {
+        return None;
+    }
+
+    let mut score = [
+        report.magnetic_heading_status,
+        report.indicated_airspeed_status,
+        report.mach_status,
+        report.barometric_altitude_rate_status,
+        report.inertial_vertical_velocity_status,
+    ]
+    .iter()
+    .map(|status| i32::from(*status))
+    .sum();
+
+    // heading and track differ by the wind correction angle and the magnetic variation
+    if let (Some(known), Some(heading)) = (context.track, report.get_magnetic_heading()) {
+        score += context_score(angle_difference(known, heading) <= 45.0);
+    }
+
+    if let (Some(altitude), Some(indicated_airspeed), Some(mach)) = (
+        context.altitude,
+        report.get_indicated_airspeed(),
+        report.get_mach(),
+    ) {
+        let calibrated_airspeed = mach_to_calibrated_airspeed(mach, altitude);
+        score += context_score((calibrated_airspeed - f64::from(indicated_airspeed)).abs() <= 20.0);
+    }
+
+    Some(score)
+}
+
+/// ISA temperature in degrees C at a pressure altitude in feet
+fn isa_temperature(altitude: i32) -> f64 {
+    (15.0 - 0.001_981_2 * f64::from(altitude)).max(-56.5)
+}
+
+/// Calibrated airspeed in knots for a Mach number at a pressure altitude in feet, using the ISA
+fn mach_to_calibrated_airspeed(mach: f64, altitude: i32) -> f64 {
+    const SEA_LEVEL_PRESSURE: f64 = 101_325.0;
+    const SEA_LEVEL_SPEED_OF_SOUND: f64 = 661.47;
+
+    let meters = f64::from(altitude) * 0.3048;
+    let pressure = if meters < 11_000.0 {
+        SEA_LEVEL_PRESSURE * (1.0 - 0.000_022_557_7 * meters).powf(5.255_88)
+    } else {
+        22_632.06 * (-0.000_157_688 * (meters - 11_000.0)).exp()
+    };
+
+    let impact_pressure = pressure * ((1.0 + 0.2 * mach * mach).powf(3.5) - 1.0);
+
+    SEA_LEVEL_SPEED_OF_SOUND
+        * (5.0 * ((impact_pressure / SEA_LEVEL_PRESSURE + 1.0).powf(2.0 / 7.0) - 1.0)).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use super::*;
+
+    fn mb_from_message(message: &str) -> [u8; 7] {
+        let bytes = hex::decode(message).unwrap();
+        let mut mb: [u8; 7] = [0; 7];
+        mb.copy_from_slice(&bytes[4..11]);
+        mb
+    }
+
+    #[test]
+    fn infer_registers_without_context() {
+        "debug".enable_logging();
+
+        let messages = [
+            (
+                "A000083E202CC371C31DE0AA1CCF",
+                BDSRegister::AircraftIdentification,
+            ),
+            (
+                "A000029C85E42F313000007047D3",
+                BDSRegister::SelectedVerticalIntention,
+            ),
+            (
+                "A000139381951536E024D4CCF6B5",
+                BDSRegister::TrackAndTurnReport,
+            ),
+            (
+                "A00004128F39F91A7E27C46ADC21",
+                BDSRegister::HeadingAndSpeedReport,
+            ),
+        ];
+
+        for (message, register) in messages {
+            let inference = infer_bds(&mb_from_message(message), &CommBContext::default());
+            info!("{message}: {inference:?}");
+            assert_eq!(
+                inference.best().map(|candidate| candidate.register),
+                Some(register),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn infer_registers_with_context() {
+        "debug".enable_logging();
+
+        let mb = mb_from_message("A000139381951536E024D4CCF6B5");
+
+        let matching = CommBContext {
+            ground_speed: Some(440.0),
+            track: Some(114.0),
+            ..Default::default()
+        };
+        let inference = infer_bds(&mb, &matching);
+        let best = inference.best().unwrap();
+        assert_eq!(best.register, BDSRegister::TrackAndTurnReport);
+        assert_eq!(best.score, 5 + 2 * CONTEXT_SCORE);
+
+        // the same MB field from an aircraft flying somewhere else entirely
+        let conflicting = CommBContext {
+            ground_speed: Some(150.0),
+            track: Some(300.0),
+            ..Default::default()
+        };
+        let inference = infer_bds(&mb, &conflicting);
+        assert!(
+            inference
+                .candidates
+                .iter()
+                .any(
+                    |candidate| candidate.register == BDSRegister::TrackAndTurnReport
+                        && candidate.score == 5 - 2 * CONTEXT_SCORE
+                )
+        );
+    }
+
+    #[test]
+    fn infer_empty_mb() {
+        let inference = infer_bds(&[0; 7], &CommBContext::default());
+        assert!(inference.candidates.is_empty());
+        assert!(inference.best().is_none());
+        assert!(!inference.is_ambiguous());
+    }
+}
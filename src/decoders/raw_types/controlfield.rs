@@ -15,15 +15,35 @@ use super::{controlfieldtype::ControlFieldType, icao::ICAO, me::ME};
 /// Control Field (B.3) for [`crate::DF::TisB`]
 ///
 /// reference: ICAO 9871
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, PartialEq)]
 pub struct ControlField {
-    t: ControlFieldType,
+    /// CF: control field, the kind of source and address
+    pub t: ControlFieldType,
     /// AA: Address, Announced
     pub aa: ICAO,
     /// ME: message, extended quitter
     pub me: ME,
 }
 
+impl ControlField {
+    /// The AA field is a 24-bit ICAO address. ES/NT devices with another address and relayed
+    /// anonymous addresses say so in the control field, fine TIS-B and ADS-R set the IMF bit
+    /// in the messages that carry one.
+    #[must_use]
+    pub fn is_icao_address(&self) -> bool {
+        match self.t {
+            ControlFieldType::ADSB_ES_NT_ALT | ControlFieldType::TISB_ADSB_RELAY => false,
+            ControlFieldType::TISB_FINE | ControlFieldType::TISB_ADSB => match &self.me {
+                ME::AirbornePositionBaroAltitude(_, altitude)
+                | ME::AirbornePositionGNSSAltitude(_, altitude) => altitude.saf_or_imf == 0,
+                ME::AirborneVelocity(velocity) => velocity.intent_change == 0,
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+}
+
 impl fmt::Display for ControlField {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
@@ -8,15 +8,15 @@ use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Formatter};
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq)]
 #[deku(id_type = "u8", bits = "3")]
 #[allow(non_camel_case_types)]
 pub enum ControlFieldType {
-    /// ADS-B Message from a non-transponder device
+    /// ADS-B Message from a non-transponder device, 24-bit ICAO address
     #[deku(id = "0")]
     ADSB_ES_NT,
 
-    /// Reserved for ADS-B for ES/NT devices for alternate address space
+    /// ADS-B Message from a non-transponder device using another address
     #[deku(id = "1")]
     ADSB_ES_NT_ALT,
 
@@ -28,7 +28,7 @@ pub enum ControlFieldType {
     #[deku(id = "3")]
     TISB_COARSE,
 
-    /// Code 4, Coarse Format TIS-B Message
+    /// Code 4, TIS-B and ADS-R Management Message
     #[deku(id = "4")]
     TISB_MANAGE,
 
@@ -38,7 +38,7 @@ pub enum ControlFieldType {
     #[deku(id = "5")]
     TISB_ADSB_RELAY,
 
-    /// Code 6, TIS-B Message, Same as DF=17
+    /// Code 6, ADS-R Message, rebroadcast using the same format as DF=17
     #[deku(id = "6")]
     TISB_ADSB,
 
@@ -52,7 +52,8 @@ impl fmt::Display for ControlFieldType {
         match self {
             Self::ADSB_ES_NT | Self::ADSB_ES_NT_ALT => write!(f, "(ADS-B)"),
             Self::TISB_COARSE | Self::TISB_ADSB_RELAY | Self::TISB_FINE => write!(f, "(TIS-B)"),
-            Self::TISB_MANAGE | Self::TISB_ADSB => write!(f, "(ADS-R)"),
+            Self::TISB_MANAGE => write!(f, "(TIS-B/ADS-R management)"),
+            Self::TISB_ADSB => write!(f, "(ADS-R)"),
             Self::Reserved => write!(f, "(unknown addressing scheme)"),
         }
     }
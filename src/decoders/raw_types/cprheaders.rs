@@ -10,7 +10,17 @@ use std::fmt::{self, Formatter};
 
 /// Even / Odd
 #[derive(
-    Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Default,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Default,
 )]
 #[deku(id_type = "u8", bits = "1")]
 #[repr(u8)]
@@ -0,0 +1,37 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// Result of checking the parity of a frame against the CRC computed over it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CRCStatus {
+    /// The parity checks out. For DF11 the syndrome may still hold the interrogator code.
+    Clean,
+    /// The parity is XOR-ed with the address, so the CRC is the address and can't be checked
+    /// without already knowing the aircraft.
+    AddressRecovered,
+    /// The parity does not check out, the frame was damaged on the way.
+    Corrupt,
+}
+
+impl CRCStatus {
+    #[must_use]
+    pub const fn is_corrupt(&self) -> bool {
+        matches!(self, CRCStatus::Corrupt)
+    }
+}
+
+impl fmt::Display for CRCStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CRCStatus::Clean => write!(f, "clean"),
+            CRCStatus::AddressRecovered => write!(f, "address recovered from parity"),
+            CRCStatus::Corrupt => write!(f, "corrupt"),
+        }
+    }
+}
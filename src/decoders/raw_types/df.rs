@@ -11,7 +11,7 @@ use super::{
     ac13field::AC13Field, adsb::Adsb, bds::BDS, capability::Capability, controlfield::ControlField,
     downlinkrequest::DownlinkRequest, flightstatus::FlightStatus,
     helper_functions::decode_id13_field, icao::ICAO, identitycode::IdentityCode, ke::KE,
-    utilitymessage::UtilityMessage,
+    mvfield::MVField, utilitymessage::UtilityMessage,
 };
 
 /// Downlink Format (3.1.2.3.2.1.2)
@@ -115,8 +115,7 @@ pub enum DF {
         /// AC: altitude code
         altitude: AC13Field,
         /// MV: message, acas
-        #[deku(count = "7")]
-        mv: Vec<u8>,
+        mv: MVField,
         /// AP: address, parity
         parity: ICAO,
     },
@@ -0,0 +1,41 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use radix_fmt::radix;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    emergencystate::EmergencyState,
+    helper_functions::{decode_id13_field, encode_id13_field},
+};
+
+/// Aircraft Status subtype 1, Emergency/Priority Status (Table A-2-97)
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct EmergencyPriorityStatus {
+    pub emergency_state: EmergencyState,
+    #[deku(
+        bits = "13",
+        endian = "big",
+        map = "|squawk: u32| -> Result<_, DekuError> {Ok(decode_id13_field(squawk))}",
+        writer = "encode_id13_field(*squawk).to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(13)))"
+    )]
+    pub squawk: u32,
+    #[deku(bits = "32")]
+    pub reserved: u32,
+}
+
+impl EmergencyPriorityStatus {
+    #[must_use]
+    pub const fn is_reserved_zero(&self) -> bool {
+        self.reserved == 0
+    }
+
+    #[must_use]
+    pub fn get_squawk_as_octal_string(&self) -> String {
+        format!("{:04}", radix(self.squawk, 16))
+    }
+}
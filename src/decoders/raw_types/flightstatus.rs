@@ -23,6 +23,17 @@ pub enum FlightStatus {
     NotAssigned = 0b111,
 }
 
+impl FlightStatus {
+    /// The aircraft reports it is on the ground. The SPI codes don't say either way.
+    #[must_use]
+    pub const fn is_on_ground(&self) -> bool {
+        matches!(
+            self,
+            FlightStatus::NoAlertNoSPIOnGround | FlightStatus::AlertNoSPIOnGround
+        )
+    }
+}
+
 impl fmt::Display for FlightStatus {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
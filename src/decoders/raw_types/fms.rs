@@ -8,7 +8,7 @@ use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Formatter};
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq, Copy)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum IsFMS {
     #[deku(id = "1")]
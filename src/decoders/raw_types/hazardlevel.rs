@@ -0,0 +1,34 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// Severity of a meteorological hazard (Table A-2-68, A-2-69)
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[deku(id_type = "u8", bits = "2")]
+pub enum HazardLevel {
+    #[deku(id = "0")]
+    Nil,
+    #[deku(id = "1")]
+    Light,
+    #[deku(id = "2")]
+    Moderate,
+    #[deku(id = "3")]
+    Severe,
+}
+
+impl fmt::Display for HazardLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nil => write!(f, "nil"),
+            Self::Light => write!(f, "light"),
+            Self::Moderate => write!(f, "moderate"),
+            Self::Severe => write!(f, "severe"),
+        }
+    }
+}
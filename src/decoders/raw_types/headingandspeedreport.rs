@@ -0,0 +1,246 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::{sign::Sign, trackandturnreport::twos_complement};
+
+/// Heading and Speed Report, Comm-B BDS 6,0 (Table A-2-96)
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HeadingAndSpeedReport {
+    #[deku(bits = "1")]
+    pub magnetic_heading_status: bool,
+    pub magnetic_heading_sign: Sign,
+    /// Magnetic heading, LSB 90/512 degrees
+    #[deku(bits = "10", endian = "big")]
+    pub magnetic_heading: u16,
+    #[deku(bits = "1")]
+    pub indicated_airspeed_status: bool,
+    /// Indicated airspeed in knots
+    #[deku(bits = "10", endian = "big")]
+    pub indicated_airspeed: u16,
+    #[deku(bits = "1")]
+    pub mach_status: bool,
+    /// Mach number, LSB 2.048/512
+    #[deku(bits = "10", endian = "big")]
+    pub mach: u16,
+    #[deku(bits = "1")]
+    pub barometric_altitude_rate_status: bool,
+    pub barometric_altitude_rate_sign: Sign,
+    /// Barometric altitude rate, LSB 32 feet/minute
+    #[deku(bits = "9", endian = "big")]
+    pub barometric_altitude_rate: u16,
+    #[deku(bits = "1")]
+    pub inertial_vertical_velocity_status: bool,
+    pub inertial_vertical_velocity_sign: Sign,
+    /// Inertial vertical velocity, LSB 32 feet/minute
+    #[deku(bits = "9", endian = "big")]
+    pub inertial_vertical_velocity: u16,
+}
+
+impl fmt::Display for HeadingAndSpeedReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(heading) = self.get_magnetic_heading() {
+            writeln!(f, "  Mag heading:   {heading:.1} degrees")?;
+        }
+        if let Some(speed) = self.get_indicated_airspeed() {
+            writeln!(f, "  IAS:           {speed} knots")?;
+        }
+        if let Some(mach) = self.get_mach() {
+            writeln!(f, "  Mach:          {mach:.3}")?;
+        }
+        if let Some(rate) = self.get_barometric_altitude_rate() {
+            writeln!(f, "  Baro rate:     {rate} ft/min")?;
+        }
+        if let Some(rate) = self.get_inertial_vertical_velocity() {
+            writeln!(f, "  Inertial rate: {rate} ft/min")?;
+        }
+        Ok(())
+    }
+}
+
+impl HeadingAndSpeedReport {
+    /// Checks the register is plausible: no value is present without its status bit, and the
+    /// values fall inside what an aircraft can actually report.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !self.magnetic_heading_status
+            && (self.magnetic_heading != 0 || self.magnetic_heading_sign == Sign::Negative)
+        {
+            return false;
+        }
+
+        if !self.indicated_airspeed_status && self.indicated_airspeed != 0 {
+            return false;
+        }
+
+        if !self.mach_status && self.mach != 0 {
+            return false;
+        }
+
+        if !self.barometric_altitude_rate_status
+            && (self.barometric_altitude_rate != 0
+                || self.barometric_altitude_rate_sign == Sign::Negative)
+        {
+            return false;
+        }
+
+        if !self.inertial_vertical_velocity_status
+            && (self.inertial_vertical_velocity != 0
+                || self.inertial_vertical_velocity_sign == Sign::Negative)
+        {
+            return false;
+        }
+
+        if !(self.magnetic_heading_status
+            || self.indicated_airspeed_status
+            || self.mach_status
+            || self.barometric_altitude_rate_status
+            || self.inertial_vertical_velocity_status)
+        {
+            return false;
+        }
+
+        if self
+            .get_indicated_airspeed()
+            .is_some_and(|speed| speed > 500)
+        {
+            return false;
+        }
+
+        if self.get_mach().is_some_and(|mach| mach > 1.0) {
+            return false;
+        }
+
+        if self
+            .get_barometric_altitude_rate()
+            .is_some_and(|rate| rate.abs() > 6000)
+        {
+            return false;
+        }
+
+        if self
+            .get_inertial_vertical_velocity()
+            .is_some_and(|rate| rate.abs() > 6000)
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Magnetic heading in degrees (0-360)
+    #[must_use]
+    pub fn get_magnetic_heading(&self) -> Option<f64> {
+        if !self.magnetic_heading_status {
+            return None;
+        }
+
+        let heading = f64::from(twos_complement(
+            self.magnetic_heading_sign,
+            self.magnetic_heading,
+            1024,
+        )) * 90.0
+            / 512.0;
+
+        Some(if heading < 0.0 {
+            heading + 360.0
+        } else {
+            heading
+        })
+    }
+
+    #[must_use]
+    pub const fn get_indicated_airspeed(&self) -> Option<u16> {
+        if self.indicated_airspeed_status {
+            Some(self.indicated_airspeed)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn get_mach(&self) -> Option<f64> {
+        if self.mach_status {
+            Some(f64::from(self.mach) * 2.048 / 512.0)
+        } else {
+            None
+        }
+    }
+
+    /// Barometric altitude rate in feet/minute
+    #[must_use]
+    pub fn get_barometric_altitude_rate(&self) -> Option<i32> {
+        if !self.barometric_altitude_rate_status {
+            return None;
+        }
+
+        Some(vertical_rate(
+            self.barometric_altitude_rate_sign,
+            self.barometric_altitude_rate,
+        ))
+    }
+
+    /// Inertial vertical velocity in feet/minute
+    #[must_use]
+    pub fn get_inertial_vertical_velocity(&self) -> Option<i32> {
+        if !self.inertial_vertical_velocity_status {
+            return None;
+        }
+
+        Some(vertical_rate(
+            self.inertial_vertical_velocity_sign,
+            self.inertial_vertical_velocity,
+        ))
+    }
+}
+
+/// All zeros and all ones in the value bits both mean level flight.
+fn vertical_rate(sign: Sign, value: u16) -> i32 {
+    if value == 0 || value == 0x1FF {
+        return 0;
+    }
+
+    twos_complement(sign, value, 512) * 32
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::bds::BDS;
+    use crate::decoders::raw_types::df::DF;
+
+    #[test]
+    fn decode_heading_and_speed_report() {
+        "debug".enable_logging();
+
+        let message = "A00004128F39F91A7E27C46ADC21";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded:?}");
+
+        match decoded.df {
+            DF::CommBAltitudeReply { bds, .. } => match bds {
+                BDS::HeadingAndSpeedReport(report) => {
+                    assert!(report.is_valid());
+                    let heading = report.get_magnetic_heading().unwrap();
+                    assert!((heading - 42.715).abs() < 0.001, "Heading was {heading}");
+                    assert_eq!(report.get_indicated_airspeed(), Some(252));
+                    let mach = report.get_mach().unwrap();
+                    assert!((mach - 0.42).abs() < 0.001, "Mach was {mach}");
+                    assert_eq!(report.get_barometric_altitude_rate(), Some(-1920));
+                    assert_eq!(report.get_inertial_vertical_velocity(), Some(-1920));
+                }
+                _ => panic!("BDS is not Heading and Speed Report: {bds:?}"),
+            },
+            _ => panic!("DF is not Comm-B Altitude Reply"),
+        }
+    }
+}
@@ -5,7 +5,7 @@
 // https://opensource.org/licenses/MIT.
 
 use deku::ctx::BitSize;
-use deku::no_std_io::{Read, Seek};
+use deku::no_std_io::{Read, Seek, Write};
 use deku::prelude::*;
 use deku::{DekuError, error::NeedSize};
 
@@ -53,6 +53,48 @@ pub(crate) fn decode_id13_field(id13_field: u32) -> u32 {
     hex_gillham
 }
 
+/// Inverse of `decode_id13_field`
+pub(crate) fn encode_id13_field(hex_gillham: u32) -> u32 {
+    // (bit in the hex Gillham code, bit in the ID13 field)
+    const ID13_BITS: [(u32, u32); 12] = [
+        (0x0010, 0x1000), // C1
+        (0x1000, 0x0800), // A1
+        (0x0020, 0x0400), // C2
+        (0x2000, 0x0200), // A2
+        (0x0040, 0x0100), // C4
+        (0x4000, 0x0080), // A4
+        (0x0100, 0x0020), // B1
+        (0x0001, 0x0010), // D1 or Q
+        (0x0200, 0x0008), // B2
+        (0x0002, 0x0004), // D2
+        (0x0400, 0x0002), // B4
+        (0x0004, 0x0001), // D4
+    ];
+
+    ID13_BITS
+        .iter()
+        .filter(|(gillham_bit, _)| hex_gillham & gillham_bit != 0)
+        .fold(0, |id13_field, (_, id13_bit)| id13_field | id13_bit)
+}
+
+/// Inverse of the 12 bit altitude readers of `Altitude` and `NoPosition`. Altitudes are written in 25 ft
+/// steps with the Q bit set, no altitude and 0 as all zeroes, like `AC13Field`.
+pub(crate) fn encode_ac12_field(altitude: Option<u16>) -> Result<u32, DekuError> {
+    let Some(altitude) = altitude.filter(|altitude| *altitude != 0) else {
+        return Ok(0);
+    };
+
+    let n = u32::from(altitude) + 1000;
+    if n % 25 != 0 || n / 25 > 0x07ff {
+        return Err(DekuError::InvalidParam(
+            format!("{altitude} ft can't be encoded in 25 ft steps").into(),
+        ));
+    }
+
+    let n = n / 25;
+    Ok(((n & 0x07f0) << 1) | 0x0010 | (n & 0x000f))
+}
+
 pub(crate) fn mode_a_to_mode_c(mode_a: u32) -> Result<u32, String> {
     let mut five_hundreds: u32 = 0;
     let mut one_hundreds: u32 = 0;
@@ -124,6 +166,8 @@ pub(crate) fn mode_a_to_mode_c(mode_a: u32) -> Result<u32, String> {
 }
 
 const CHAR_LOOKUP: &[u8; 64] = b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+/// Characters of the callsign in an identification message
+const IDENTIFICATION_CHARACTERS: usize = 8;
 
 pub const CRC_TABLE: [u32; 256] = [
     0x0000_0000,
@@ -412,7 +456,7 @@ pub(crate) fn aircraft_identification_read<R: Read + Seek>(
     reader: &mut Reader<R>,
 ) -> Result<String, DekuError> {
     let mut chars = vec![];
-    for _ in 0..=6 {
+    for _ in 0..IDENTIFICATION_CHARACTERS {
         let c = <u8>::from_reader_with_ctx(reader, BitSize(6))?;
         if c != 32 {
             chars.push(c);
@@ -425,3 +469,35 @@ pub(crate) fn aircraft_identification_read<R: Read + Seek>(
 
     Ok(encoded)
 }
+
+/// Writes the callsign padded with spaces. Characters that don't exist in the 6 bit alphabet are an error.
+pub(crate) fn aircraft_identification_write<W: Write + Seek>(
+    writer: &mut Writer<W>,
+    cn: &str,
+) -> Result<(), DekuError> {
+    if cn.len() > IDENTIFICATION_CHARACTERS {
+        return Err(DekuError::InvalidParam(
+            format!("{cn} is longer than {IDENTIFICATION_CHARACTERS} characters").into(),
+        ));
+    }
+
+    for character in cn
+        .bytes()
+        .chain(std::iter::repeat(b' '))
+        .take(IDENTIFICATION_CHARACTERS)
+    {
+        let Some(index) = CHAR_LOOKUP
+            .iter()
+            .position(|lookup| *lookup == character.to_ascii_uppercase())
+        else {
+            return Err(DekuError::InvalidParam(
+                format!("{} can't be sent in an identification", character as char).into(),
+            ));
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        (index as u8).to_writer(writer, BitSize(6))?;
+    }
+
+    Ok(())
+}
@@ -7,14 +7,17 @@
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use super::{helper_functions::aircraft_identification_read, typecoding::TypeCoding};
+use super::{
+    helper_functions::{aircraft_identification_read, aircraft_identification_write},
+    typecoding::TypeCoding,
+};
 
 /// `tc` (the ADS-B Type Code) is not read from the wire by this struct: the
 /// enclosing [`ME`](super::me::ME) enum already has to consume those 5 bits
 /// to pick the `AircraftIdentification` variant, so it forwards the
 /// already-read value in via `ctx` instead of letting it be read (and thus
 /// the bitstream position advanced) a second time here.
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Eq, PartialEq)]
 #[deku(ctx = "tc: u8")]
 pub struct Identification {
     #[deku(skip, default = "TypeCoding::from(tc)")]
@@ -24,7 +27,10 @@ pub struct Identification {
     pub ca: u8,
 
     /// N-Number / Tail Number
-    #[deku(reader = "aircraft_identification_read(deku::reader)")]
+    #[deku(
+        reader = "aircraft_identification_read(deku::reader)",
+        writer = "aircraft_identification_write(deku::writer, cn)"
+    )]
     pub cn: String,
 }
 
@@ -35,7 +41,9 @@ pub mod test {
     use super::*;
     use crate::decoders::raw::NewAdsbRawMessage;
     use crate::decoders::raw_types::df::DF;
+    use crate::decoders::raw_types::icao::ICAO;
     use crate::decoders::raw_types::identification::Identification;
+    use crate::decoders::raw_types::me::ME;
 
     #[test]
     fn decode_identification() {
@@ -62,4 +70,35 @@ pub mod test {
             _ => panic!("Wrong DF"),
         }
     }
+
+    #[test]
+    fn decode_identification_parity() {
+        "debug".enable_logging();
+
+        // all 8 characters are read, so the PI field after them is read from the right bits
+        for (message, callsign, pi) in [
+            (
+                "8D4840D6202CC371C32CE0576098",
+                "KLM1023",
+                [0x57, 0x60, 0x98],
+            ),
+            (
+                "8D406B902015A678D4D202AB9671",
+                "EZY85MHB",
+                [0xAB, 0x96, 0x71],
+            ),
+        ] {
+            let decoded = message.to_adsb_raw().unwrap();
+            match decoded.df {
+                DF::ADSB(adsb) => {
+                    assert_eq!(adsb.pi, ICAO(pi));
+                    match adsb.me {
+                        ME::AircraftIdentification(_, id) => assert_eq!(id.cn, callsign),
+                        _ => panic!("Wrong ME"),
+                    }
+                }
+                _ => panic!("Wrong DF"),
+            }
+        }
+    }
 }
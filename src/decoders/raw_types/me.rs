@@ -11,10 +11,12 @@ use std::fmt::{Error, Write};
 use super::{
     airbornevelocity::AirborneVelocity,
     airbornevelocitysubtype::AirborneVelocitySubType,
+    aircraftoperationalcoordination::AircraftOperationalCoordination,
     aircraftstatus::AircraftStatus,
     altitude::Altitude,
     autopilot_modes::{AltitudeHold, ApproachMode, AutopilotEngaged, LNAV, TCAS, VNAVEngaged},
     capability::Capability,
+    emergencyprioritystatus::EmergencyPriorityStatus,
     emergencystate::EmergencyState,
     heading::SelectedHeadingStatus,
     icao::ICAO,
@@ -24,12 +26,13 @@ use super::{
     operationstatusairborne::OperationStatusAirborne,
     operationstatussurface::OperationStatusSurface,
     surfaceposition::SurfacePosition,
+    surfacesystemstatus::SurfaceSystemStatus,
     targetstateandstatusinformation::TargetStateAndStatusInformation,
 };
 /// ADS-B Message, 5 first bits are known as Type Code (TC)
 ///
 /// reference: ICAO 9871 (A.2.3.1)
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, PartialEq)]
 #[deku(id_type = "u8", bits = "5")]
 pub enum ME {
     // NOTE: the leading `u8` on each `id_pat` variant below captures the
@@ -60,8 +63,8 @@ pub enum ME {
     #[deku(id = "23")]
     Reserved0([u8; 6]),
 
-    #[deku(id_pat = "24")]
-    SurfaceSystemStatus(u8, [u8; 6]),
+    #[deku(id = "24")]
+    SurfaceSystemStatus(SurfaceSystemStatus),
 
     #[deku(id_pat = "25..=27")]
     Reserved1(u8, [u8; 6]),
@@ -73,7 +76,7 @@ pub enum ME {
     TargetStateAndStatusInformation(TargetStateAndStatusInformation), // Done
 
     #[deku(id = "30")]
-    AircraftOperationalCoordination([u8; 6]),
+    AircraftOperationalCoordination(AircraftOperationalCoordination),
 
     #[deku(id = "31")]
     AircraftOperationStatus(OperationStatus), // Done
@@ -198,19 +201,22 @@ impl ME {
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
             }
-            ME::SurfaceSystemStatus(..) => {
+            ME::SurfaceSystemStatus(surface_system_status) => {
                 writeln!(
                     f,
                     " Extended Squitter{transponder}Reserved for surface system status",
                 )?;
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
+                write!(f, "{surface_system_status}")?;
             }
-            ME::AircraftStatus(AircraftStatus {
-                emergency_state,
-                squawk,
-                ..
-            }) => {
+            ME::AircraftStatus(AircraftStatus::EmergencyPriorityStatus(
+                EmergencyPriorityStatus {
+                    emergency_state,
+                    squawk,
+                    ..
+                },
+            )) => {
                 print_aircraft_status(
                     &mut f,
                     transponder,
@@ -221,6 +227,20 @@ impl ME {
                     *squawk,
                 )?;
             }
+            ME::AircraftStatus(AircraftStatus::ACASRaBroadcast(ra)) => {
+                writeln!(f, " Extended Squitter{transponder}ACAS RA broadcast")?;
+                writeln!(f, "  Address:       {icao} {address_type}")?;
+                writeln!(f, "  Air/Ground:    {capability}")?;
+                write!(f, "{ra}")?;
+            }
+            ME::AircraftStatus(AircraftStatus::NoInformation(_) | AircraftStatus::Reserved(..)) => {
+                writeln!(
+                    f,
+                    " Extended Squitter{transponder}Aircraft status (no information)"
+                )?;
+                writeln!(f, "  Address:       {icao} {address_type}")?;
+                writeln!(f, "  Air/Ground:    {capability}")?;
+            }
             ME::TargetStateAndStatusInformation(target_info) => {
                 print_target_state_and_status_information(
                     &mut f,
@@ -231,12 +251,13 @@ impl ME {
                     target_info,
                 )?;
             }
-            ME::AircraftOperationalCoordination(_) => {
+            ME::AircraftOperationalCoordination(coordination) => {
                 print_aircraft_operational_coordination_message(
                     &mut f,
                     transponder,
                     icao,
                     address_type,
+                    *coordination,
                 )?;
             }
             ME::AircraftOperationStatus(OperationStatus::Airborne(opstatus_airborne)) => {
@@ -342,12 +363,14 @@ fn print_aircraft_operational_coordination_message(
     transponder: &str,
     icao: ICAO,
     address_type: &str,
+    coordination: AircraftOperationalCoordination,
 ) -> Result<(), Error> {
     writeln!(
         f,
         " Extended Squitter{transponder}Aircraft Operational Coordination",
     )?;
     writeln!(f, "  Address:       {icao} {address_type}")?;
+    write!(f, "{coordination}")?;
 
     Ok(())
 }
@@ -0,0 +1,243 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::{hazardlevel::HazardLevel, sign::Sign, trackandturnreport::twos_complement};
+
+/// Meteorological Hazard Report, Comm-B BDS 4,5 (Table A-2-69)
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MeteorologicalHazardReport {
+    #[deku(bits = "1")]
+    pub turbulence_status: bool,
+    pub turbulence: HazardLevel,
+    #[deku(bits = "1")]
+    pub wind_shear_status: bool,
+    pub wind_shear: HazardLevel,
+    #[deku(bits = "1")]
+    pub microburst_status: bool,
+    pub microburst: HazardLevel,
+    #[deku(bits = "1")]
+    pub icing_status: bool,
+    pub icing: HazardLevel,
+    #[deku(bits = "1")]
+    pub wake_vortex_status: bool,
+    pub wake_vortex: HazardLevel,
+    #[deku(bits = "1")]
+    pub static_air_temperature_status: bool,
+    pub static_air_temperature_sign: Sign,
+    /// Static air temperature, LSB 0.25 degrees C
+    #[deku(bits = "9", endian = "big")]
+    pub static_air_temperature: u16,
+    #[deku(bits = "1")]
+    pub average_static_pressure_status: bool,
+    /// Average static pressure in hPa
+    #[deku(bits = "11", endian = "big")]
+    pub average_static_pressure: u16,
+    #[deku(bits = "1")]
+    pub radio_height_status: bool,
+    /// Radio height in feet
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|height: u16| -> Result<_, DekuError> {Ok(u32::from(height) * 16)}"
+    )]
+    pub radio_height: u32,
+    #[deku(bits = "5")]
+    pub reserved: u8,
+}
+
+impl fmt::Display for MeteorologicalHazardReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(turbulence) = self.get_turbulence() {
+            writeln!(f, "  Turbulence:    {turbulence}")?;
+        }
+        if let Some(wind_shear) = self.get_wind_shear() {
+            writeln!(f, "  Wind shear:    {wind_shear}")?;
+        }
+        if let Some(microburst) = self.get_microburst() {
+            writeln!(f, "  Microburst:    {microburst}")?;
+        }
+        if let Some(icing) = self.get_icing() {
+            writeln!(f, "  Icing:         {icing}")?;
+        }
+        if let Some(wake_vortex) = self.get_wake_vortex() {
+            writeln!(f, "  Wake vortex:   {wake_vortex}")?;
+        }
+        if let Some(temperature) = self.get_static_air_temperature() {
+            writeln!(f, "  Temperature:   {temperature:.2} C")?;
+        }
+        if let Some(pressure) = self.get_average_static_pressure() {
+            writeln!(f, "  Pressure:      {pressure} hPa")?;
+        }
+        if let Some(height) = self.get_radio_height() {
+            writeln!(f, "  Radio height:  {height} ft")?;
+        }
+        Ok(())
+    }
+}
+
+impl MeteorologicalHazardReport {
+    #[must_use]
+    pub const fn is_reserved_zero(&self) -> bool {
+        self.reserved == 0
+    }
+
+    /// Checks the register is plausible: reserved bits are zero, no value is present without
+    /// its status bit and the temperature is within what the atmosphere can produce.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !self.is_reserved_zero() {
+            return false;
+        }
+
+        let hazards = [
+            (self.turbulence_status, self.turbulence),
+            (self.wind_shear_status, self.wind_shear),
+            (self.microburst_status, self.microburst),
+            (self.icing_status, self.icing),
+            (self.wake_vortex_status, self.wake_vortex),
+        ];
+
+        if hazards
+            .iter()
+            .any(|(status, level)| !status && *level != HazardLevel::Nil)
+        {
+            return false;
+        }
+
+        if !self.static_air_temperature_status
+            && (self.static_air_temperature != 0
+                || self.static_air_temperature_sign == Sign::Negative)
+        {
+            return false;
+        }
+
+        if !self.average_static_pressure_status && self.average_static_pressure != 0 {
+            return false;
+        }
+
+        if !self.radio_height_status && self.radio_height != 0 {
+            return false;
+        }
+
+        if self
+            .get_static_air_temperature()
+            .is_some_and(|temperature| !(-80.0..=60.0).contains(&temperature))
+        {
+            return false;
+        }
+
+        hazards.iter().any(|(status, _)| *status)
+            || self.static_air_temperature_status
+            || self.average_static_pressure_status
+            || self.radio_height_status
+    }
+
+    #[must_use]
+    pub const fn get_turbulence(&self) -> Option<HazardLevel> {
+        if self.turbulence_status {
+            Some(self.turbulence)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_wind_shear(&self) -> Option<HazardLevel> {
+        if self.wind_shear_status {
+            Some(self.wind_shear)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_microburst(&self) -> Option<HazardLevel> {
+        if self.microburst_status {
+            Some(self.microburst)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_icing(&self) -> Option<HazardLevel> {
+        if self.icing_status {
+            Some(self.icing)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_wake_vortex(&self) -> Option<HazardLevel> {
+        if self.wake_vortex_status {
+            Some(self.wake_vortex)
+        } else {
+            None
+        }
+    }
+
+    /// Static air temperature in degrees C
+    #[must_use]
+    pub fn get_static_air_temperature(&self) -> Option<f64> {
+        if !self.static_air_temperature_status {
+            return None;
+        }
+
+        Some(
+            f64::from(twos_complement(
+                self.static_air_temperature_sign,
+                self.static_air_temperature,
+                512,
+            )) * 0.25,
+        )
+    }
+
+    #[must_use]
+    pub const fn get_average_static_pressure(&self) -> Option<u16> {
+        if self.average_static_pressure_status {
+            Some(self.average_static_pressure)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_radio_height(&self) -> Option<u32> {
+        if self.radio_height_status {
+            Some(self.radio_height)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_meteorological_hazard_report() {
+        // moderate turbulence, light icing, -20 C and a radio height of 1600 ft
+        let mb = hex::decode("C051EC00020C80").unwrap();
+        let (_, report) = MeteorologicalHazardReport::from_bytes((&mb, 0)).unwrap();
+
+        assert!(report.is_valid());
+        assert_eq!(report.get_turbulence(), Some(HazardLevel::Moderate));
+        assert_eq!(report.get_wind_shear(), None);
+        assert_eq!(report.get_microburst(), None);
+        assert_eq!(report.get_icing(), Some(HazardLevel::Light));
+        assert_eq!(report.get_wake_vortex(), None);
+        assert_eq!(report.get_static_air_temperature(), Some(-20.0));
+        assert_eq!(report.get_average_static_pressure(), None);
+        assert_eq!(report.get_radio_height(), Some(1600));
+    }
+}
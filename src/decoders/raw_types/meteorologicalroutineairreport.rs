@@ -0,0 +1,228 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::{hazardlevel::HazardLevel, sign::Sign, trackandturnreport::twos_complement};
+
+/// Figure of merit / source of the meteorological data (Table A-2-68)
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[deku(id_type = "u8", bits = "4")]
+pub enum MeteorologicalSource {
+    #[deku(id = "0")]
+    Invalid,
+    #[deku(id = "1")]
+    INS,
+    #[deku(id = "2")]
+    GNSS,
+    #[deku(id = "3")]
+    DMEDME,
+    #[deku(id = "4")]
+    VORDME,
+    #[deku(id_pat = "_")]
+    Reserved,
+}
+
+impl fmt::Display for MeteorologicalSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "invalid"),
+            Self::INS => write!(f, "INS"),
+            Self::GNSS => write!(f, "GNSS"),
+            Self::DMEDME => write!(f, "DME/DME"),
+            Self::VORDME => write!(f, "VOR/DME"),
+            Self::Reserved => write!(f, "reserved"),
+        }
+    }
+}
+
+/// Meteorological Routine Air Report, Comm-B BDS 4,4 (Table A-2-68)
+///
+/// The static air temperature has no status bit of its own.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MeteorologicalRoutineAirReport {
+    pub source: MeteorologicalSource,
+    #[deku(bits = "1")]
+    pub wind_status: bool,
+    /// Wind speed in knots
+    #[deku(bits = "9", endian = "big")]
+    pub wind_speed: u16,
+    /// Wind direction, LSB 180/256 degrees
+    #[deku(bits = "9", endian = "big")]
+    pub wind_direction: u16,
+    pub static_air_temperature_sign: Sign,
+    /// Static air temperature, LSB 0.25 degrees C
+    #[deku(bits = "10", endian = "big")]
+    pub static_air_temperature: u16,
+    #[deku(bits = "1")]
+    pub average_static_pressure_status: bool,
+    /// Average static pressure in hPa
+    #[deku(bits = "11", endian = "big")]
+    pub average_static_pressure: u16,
+    #[deku(bits = "1")]
+    pub turbulence_status: bool,
+    pub turbulence: HazardLevel,
+    #[deku(bits = "1")]
+    pub humidity_status: bool,
+    /// Humidity, LSB 100/64 percent
+    #[deku(bits = "6")]
+    pub humidity: u8,
+}
+
+impl fmt::Display for MeteorologicalRoutineAirReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Source:        {}", self.source)?;
+        if let Some((speed, direction)) = self.get_wind() {
+            writeln!(
+                f,
+                "  Wind:          {speed} knots from {direction:.1} degrees"
+            )?;
+        }
+        writeln!(
+            f,
+            "  Temperature:   {:.2} C",
+            self.get_static_air_temperature()
+        )?;
+        if let Some(pressure) = self.get_average_static_pressure() {
+            writeln!(f, "  Pressure:      {pressure} hPa")?;
+        }
+        if let Some(turbulence) = self.get_turbulence() {
+            writeln!(f, "  Turbulence:    {turbulence}")?;
+        }
+        if let Some(humidity) = self.get_humidity() {
+            writeln!(f, "  Humidity:      {humidity:.1} %")?;
+        }
+        Ok(())
+    }
+}
+
+impl MeteorologicalRoutineAirReport {
+    /// Checks the register is plausible: no value is present without its status bit, the
+    /// source is not reserved and the values are within what the atmosphere can produce.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if self.source == MeteorologicalSource::Reserved {
+            return false;
+        }
+
+        if !self.wind_status && (self.wind_speed != 0 || self.wind_direction != 0) {
+            return false;
+        }
+
+        if !self.average_static_pressure_status && self.average_static_pressure != 0 {
+            return false;
+        }
+
+        if !self.turbulence_status && self.turbulence != HazardLevel::Nil {
+            return false;
+        }
+
+        if !self.humidity_status && self.humidity != 0 {
+            return false;
+        }
+
+        if self.get_wind().is_some_and(|(speed, _)| speed > 250) {
+            return false;
+        }
+
+        (-80.0..=60.0).contains(&self.get_static_air_temperature())
+    }
+
+    /// Wind speed in knots and the direction it blows from in degrees
+    #[must_use]
+    pub fn get_wind(&self) -> Option<(u16, f64)> {
+        if self.wind_status {
+            Some((
+                self.wind_speed,
+                f64::from(self.wind_direction) * 180.0 / 256.0,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Static air temperature in degrees C
+    #[must_use]
+    pub fn get_static_air_temperature(&self) -> f64 {
+        f64::from(twos_complement(
+            self.static_air_temperature_sign,
+            self.static_air_temperature,
+            1024,
+        )) * 0.25
+    }
+
+    #[must_use]
+    pub const fn get_average_static_pressure(&self) -> Option<u16> {
+        if self.average_static_pressure_status {
+            Some(self.average_static_pressure)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_turbulence(&self) -> Option<HazardLevel> {
+        if self.turbulence_status {
+            Some(self.turbulence)
+        } else {
+            None
+        }
+    }
+
+    /// Humidity in percent
+    #[must_use]
+    pub fn get_humidity(&self) -> Option<f64> {
+        if self.humidity_status {
+            Some(f64::from(self.humidity) * 100.0 / 64.0)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::bds::BDS;
+    use crate::decoders::raw_types::df::DF;
+
+    use super::*;
+
+    #[test]
+    fn decode_meteorological_routine_air_report() {
+        "debug".enable_logging();
+
+        let message = "A0001692185BD5CF400000DFC696";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded:?}");
+
+        match decoded.df {
+            DF::CommBAltitudeReply { bds, .. } => match bds {
+                BDS::MeteorologicalRoutineAirReport(report) => {
+                    assert!(report.is_valid());
+                    assert_eq!(report.source, MeteorologicalSource::INS);
+                    let (speed, direction) = report.get_wind().unwrap();
+                    assert_eq!(speed, 22);
+                    assert!((direction - 344.5).abs() < 0.1, "Direction was {direction}");
+                    let temperature = report.get_static_air_temperature();
+                    assert!(
+                        (temperature + 48.75).abs() < 0.01,
+                        "Temperature was {temperature}"
+                    );
+                    assert_eq!(report.get_average_static_pressure(), None);
+                    assert_eq!(report.get_humidity(), None);
+                }
+                _ => panic!("BDS is not Meteorological Routine Air Report: {bds:?}"),
+            },
+            _ => panic!("DF is not Comm-B Altitude Reply"),
+        }
+    }
+}
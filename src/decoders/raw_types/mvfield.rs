@@ -0,0 +1,44 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::no_std_io::{Read, Seek};
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::acasresolutionadvisory::ACASResolutionAdvisory;
+
+/// MV: Message, Comm-V of a Long Air-Air Surveillance reply (DF16)
+///
+/// The first byte (VDS) says what the message holds, only the RA report (0x30) is decoded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MVField {
+    ResolutionAdvisory(ACASResolutionAdvisory),
+    Unknown([u8; 7]),
+}
+
+impl DekuReader<'_, ()> for MVField {
+    fn from_reader_with_ctx<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        (): (),
+    ) -> Result<Self, DekuError> {
+        let mv = <[u8; 7]>::from_reader_with_ctx(reader, ())?;
+
+        Ok(ACASResolutionAdvisory::from_register(&mv)
+            .map_or(Self::Unknown(mv), Self::ResolutionAdvisory))
+    }
+}
+
+impl fmt::Display for MVField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ResolutionAdvisory(ra) => write!(f, "{ra}"),
+            Self::Unknown(mv) => {
+                writeln!(f, "  MV:            {}", hex::encode_upper(mv))
+            }
+        }
+    }
+}
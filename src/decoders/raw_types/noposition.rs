@@ -5,23 +5,30 @@
 // https://opensource.org/licenses/MIT.
 
 use deku::ctx::{BitSize, Endian};
-use deku::no_std_io::{Read, Seek};
+use deku::no_std_io::{Read, Seek, Write};
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use super::helper_functions::{decode_id13_field, mode_a_to_mode_c};
+use super::helper_functions::{decode_id13_field, encode_ac12_field, mode_a_to_mode_c};
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
 pub struct NoPosition {
     #[deku(bits = "3")]
     pub st: u8,
-    #[deku(reader = "Self::read(deku::reader)")]
+    #[deku(
+        reader = "Self::read(deku::reader)",
+        writer = "Self::write(deku::writer, *altitude)"
+    )]
     pub altitude: Option<u16>,
 }
 
+/// The ME field is unused after the altitude
+const NO_POSITION_UNUSED_BITS: usize = 36;
+
 impl NoPosition {
     fn read<R: Read + Seek>(reader: &mut Reader<R>) -> Result<Option<u16>, DekuError> {
         let num = u32::from_reader_with_ctx(reader, (Endian::Big, BitSize(12)))?;
+        u64::from_reader_with_ctx(reader, (Endian::Big, BitSize(NO_POSITION_UNUSED_BITS)))?;
         let q = num & 0x10;
 
         if q > 0 {
@@ -43,6 +50,14 @@ impl NoPosition {
             }
         }
     }
+
+    fn write<W: Write + Seek>(
+        writer: &mut Writer<W>,
+        altitude: Option<u16>,
+    ) -> Result<(), DekuError> {
+        encode_ac12_field(altitude)?.to_writer(writer, (Endian::Big, BitSize(12)))?;
+        0_u64.to_writer(writer, (Endian::Big, BitSize(NO_POSITION_UNUSED_BITS)))
+    }
 }
 
 #[cfg(test)]
@@ -74,6 +89,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_no_position_parity() {
+        "debug".enable_logging();
+
+        // the unused rest of the ME field is skipped, so the PI field is read from the right bits
+        let message = "8DADC035002D8000000000B16E64";
+        let decoded = message.to_adsb_raw().unwrap();
+        match decoded.df {
+            crate::decoders::raw_types::df::DF::ADSB(adsb) => {
+                assert_eq!(
+                    adsb.pi,
+                    crate::decoders::raw_types::icao::ICAO([0xB1, 0x6E, 0x64])
+                );
+            }
+            _ => panic!("Wrong DF"),
+        }
+    }
+
+    #[test]
+    fn test_no_position_encode_zero() {
+        "debug".enable_logging();
+
+        // 0 ft reads back as no altitude, so both are written as all zeroes like AC13Field does
+        assert_eq!(encode_ac12_field(Some(0)).unwrap(), 0);
+        assert_eq!(encode_ac12_field(None).unwrap(), 0);
+        assert_eq!(encode_ac12_field(Some(8000)).unwrap(), 0x2D8);
+        assert!(encode_ac12_field(Some(8010)).is_err());
+    }
+
     #[test]
     fn test_no_position_alternate() {
         "debug".enable_logging();
@@ -11,7 +11,7 @@ use std::fmt::{self, Formatter};
 use crate::decoders::common_types::sda::SystemDesignAssurance;
 
 /// `OperationMode` field not including the last 8 bits that are different for Surface/Airborne
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
 pub struct OperationalMode {
     /// (0, 0) in Version 2, reserved for other values
     #[deku(bits = "2", assert_eq = "0")]
@@ -15,7 +15,7 @@ use super::{
 };
 
 /// Aircraft Operational Status Subtype
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
 #[deku(id_type = "u8", bits = "3")]
 pub enum OperationStatus {
     #[deku(id = "0")]
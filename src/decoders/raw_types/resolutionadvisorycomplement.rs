@@ -0,0 +1,56 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// RAC: Resolution Advisory Complements, the RAs received from other ACAS aircraft (4.3.8.4.2.2.1.2)
+#[allow(clippy::struct_excessive_bools)]
+#[derive(
+    Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
+pub struct ResolutionAdvisoryComplement {
+    #[deku(bits = "1")]
+    pub do_not_pass_below: bool,
+    #[deku(bits = "1")]
+    pub do_not_pass_above: bool,
+    #[deku(bits = "1")]
+    pub do_not_turn_left: bool,
+    #[deku(bits = "1")]
+    pub do_not_turn_right: bool,
+}
+
+impl ResolutionAdvisoryComplement {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        !(self.do_not_pass_below
+            || self.do_not_pass_above
+            || self.do_not_turn_left
+            || self.do_not_turn_right)
+    }
+}
+
+impl fmt::Display for ResolutionAdvisoryComplement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let complements = [
+            (self.do_not_pass_below, "do not pass below"),
+            (self.do_not_pass_above, "do not pass above"),
+            (self.do_not_turn_left, "do not turn left"),
+            (self.do_not_turn_right, "do not turn right"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, text)| *text)
+        .collect::<Vec<_>>();
+
+        if complements.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", complements.join(", "))
+        }
+    }
+}
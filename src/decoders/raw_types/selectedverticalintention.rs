@@ -0,0 +1,242 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::autopilot_modes::{AltitudeHold, ApproachMode, VNAVEngaged};
+
+/// Source of the altitude the aircraft is currently targeting (Table A-2-64)
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+#[deku(id_type = "u8", bits = "2")]
+pub enum TargetAltitudeSource {
+    #[deku(id = "0")]
+    Unknown,
+    #[deku(id = "1")]
+    AircraftAltitude,
+    #[deku(id = "2")]
+    MCPFCUSelectedAltitude,
+    #[deku(id = "3")]
+    FMSSelectedAltitude,
+}
+
+impl fmt::Display for TargetAltitudeSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::AircraftAltitude => write!(f, "aircraft altitude"),
+            Self::MCPFCUSelectedAltitude => write!(f, "MCP/FCU selected altitude"),
+            Self::FMSSelectedAltitude => write!(f, "FMS selected altitude"),
+        }
+    }
+}
+
+/// Selected Vertical Intention, Comm-B BDS 4,0 (Table A-2-64)
+///
+/// Every value in the register is preceded by a status bit. When the status bit is clear
+/// the value bits are required to be zero, which is what `is_valid` uses to decide if an
+/// MB field really holds this register.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, PartialEq)]
+pub struct SelectedVerticalIntention {
+    #[deku(bits = "1")]
+    pub mcp_fcu_altitude_status: bool,
+    /// MCP/FCU selected altitude in feet
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|altitude: u32| -> Result<_, DekuError> {Ok(altitude * 16)}"
+    )]
+    pub mcp_fcu_altitude: u32,
+    #[deku(bits = "1")]
+    pub fms_altitude_status: bool,
+    /// FMS selected altitude in feet
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|altitude: u32| -> Result<_, DekuError> {Ok(altitude * 16)}"
+    )]
+    pub fms_altitude: u32,
+    #[deku(bits = "1")]
+    pub barometric_pressure_status: bool,
+    /// Barometric pressure setting in millibars, sent as the offset from 800 mb.
+    /// Without the status bit the raw value is kept so `is_valid` can check it is zero.
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|qnh: u32| -> Result<_, DekuError> {if *barometric_pressure_status { Ok(800.0 + f64::from(qnh) * 0.1) } else { Ok(f64::from(qnh) * 0.1)}}"
+    )]
+    pub barometric_pressure: f64,
+    #[deku(bits = "8")]
+    pub reserved0: u8,
+    #[deku(bits = "1")]
+    pub mode_status: bool,
+    pub vnav: VNAVEngaged,
+    pub alt_hold: AltitudeHold,
+    pub approach: ApproachMode,
+    #[deku(bits = "2")]
+    pub reserved1: u8,
+    #[deku(bits = "1")]
+    pub target_altitude_source_status: bool,
+    pub target_altitude_source: TargetAltitudeSource,
+}
+
+impl fmt::Display for SelectedVerticalIntention {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(altitude) = self.get_mcp_fcu_selected_altitude() {
+            writeln!(f, "  MCP altitude:  {altitude} ft")?;
+        }
+        if let Some(altitude) = self.get_fms_selected_altitude() {
+            writeln!(f, "  FMS altitude:  {altitude} ft")?;
+        }
+        if let Some(qnh) = self.get_barometric_pressure_setting() {
+            writeln!(f, "  QNH:           {qnh:.1} millibars")?;
+        }
+        if self.target_altitude_source_status {
+            writeln!(f, "  Target source: {}", self.target_altitude_source)?;
+        }
+        Ok(())
+    }
+}
+
+impl SelectedVerticalIntention {
+    #[must_use]
+    pub const fn is_reserved_zero(&self) -> bool {
+        self.reserved0 == 0 && self.reserved1 == 0
+    }
+
+    /// Checks the register is self-consistent: reserved bits are zero and no value is
+    /// present without its status bit being set.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !self.is_reserved_zero() {
+            return false;
+        }
+
+        if !self.mcp_fcu_altitude_status && self.mcp_fcu_altitude != 0 {
+            return false;
+        }
+
+        if !self.fms_altitude_status && self.fms_altitude != 0 {
+            return false;
+        }
+
+        if !self.barometric_pressure_status && self.barometric_pressure != 0.0 {
+            return false;
+        }
+
+        if !self.mode_status
+            && (self.vnav == VNAVEngaged::Engaged
+                || self.alt_hold == AltitudeHold::Engaged
+                || self.approach == ApproachMode::Engaged)
+        {
+            return false;
+        }
+
+        if !self.target_altitude_source_status
+            && self.target_altitude_source != TargetAltitudeSource::Unknown
+        {
+            return false;
+        }
+
+        // an all-zero register is the empty reply, not a selected vertical intention
+        self.mcp_fcu_altitude_status
+            || self.fms_altitude_status
+            || self.barometric_pressure_status
+            || self.mode_status
+            || self.target_altitude_source_status
+    }
+
+    #[must_use]
+    pub const fn get_mcp_fcu_selected_altitude(&self) -> Option<u32> {
+        if self.mcp_fcu_altitude_status {
+            Some(self.mcp_fcu_altitude)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_fms_selected_altitude(&self) -> Option<u32> {
+        if self.fms_altitude_status {
+            Some(self.fms_altitude)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_barometric_pressure_setting(&self) -> Option<f64> {
+        if self.barometric_pressure_status {
+            Some(self.barometric_pressure)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::bds::BDS;
+    use crate::decoders::raw_types::df::DF;
+    use crate::decoders::raw_types::helper_functions::modes_checksum;
+
+    #[test]
+    fn decode_selected_vertical_intention() {
+        "debug".enable_logging();
+
+        let message = "A000029C85E42F313000007047D3";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded:?}");
+
+        assert_eq!(decoded.crc, 0x0042_43D0);
+
+        match decoded.df {
+            DF::CommBAltitudeReply { bds, .. } => match bds {
+                BDS::SelectedVerticalIntention(intention) => {
+                    assert!(intention.is_valid());
+                    assert_eq!(intention.get_mcp_fcu_selected_altitude(), Some(3008));
+                    assert_eq!(intention.get_fms_selected_altitude(), Some(3008));
+                    let qnh = intention.get_barometric_pressure_setting().unwrap();
+                    assert!((qnh - 1020.0).abs() < 0.01, "QNH was {qnh}");
+                }
+                _ => panic!("BDS is not Selected Vertical Intention: {bds:?}"),
+            },
+            _ => panic!("DF is not Comm-B Altitude Reply"),
+        }
+    }
+
+    #[test]
+    fn decode_selected_vertical_intention_lowest_qnh() {
+        "debug".enable_logging();
+
+        // the frame above with the QNH value cleared, a raw value of 0 with the status bit set is 800 mb
+        let mut frame = hex::decode("A000029C85E42F313000007047D3").unwrap();
+        for bit in 59..71 {
+            frame[bit / 8] &= !(0x80 >> (bit % 8));
+        }
+        frame[11..].fill(0);
+        let parity = modes_checksum(&frame, 112).unwrap() ^ 0x0042_43D0;
+        frame[11..].copy_from_slice(&parity.to_be_bytes()[1..]);
+
+        let decoded = hex::encode(frame).to_adsb_raw().unwrap();
+        match decoded.df {
+            DF::CommBAltitudeReply {
+                bds: BDS::SelectedVerticalIntention(intention),
+                ..
+            } => {
+                assert!(intention.is_valid());
+                let qnh = intention.get_barometric_pressure_setting().unwrap();
+                assert!((qnh - 800.0).abs() < 0.01, "QNH was {qnh}");
+            }
+            df => panic!("Not a Selected Vertical Intention: {df:?}"),
+        }
+    }
+}
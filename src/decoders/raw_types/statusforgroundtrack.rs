@@ -7,7 +7,9 @@
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq, PartialOrd)]
+#[derive(
+    Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
 #[deku(id_type = "u8", bits = "1")]
 #[repr(u8)]
 pub enum StatusForGroundTrack {
@@ -16,7 +16,9 @@ use super::{
 /// those 5 bits to pick the `SurfacePosition` variant, so it forwards the
 /// already-read value in via `ctx` instead of letting it be read (and thus
 /// the bitstream position advanced) a second time here.
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq, PartialOrd)]
+#[derive(
+    Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
 #[deku(ctx = "type_code: u8")]
 pub struct SurfacePosition {
     #[deku(skip, default = "type_code")]
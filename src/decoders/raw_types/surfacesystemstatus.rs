@@ -0,0 +1,63 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// [`ME::SurfaceSystemStatus`], TC24
+///
+/// DO-260B reserves this type code for status messages from surface systems (such as
+/// multilateration ground stations) but does not publish a format for the content, so only
+/// the subtype is decoded and the rest is kept as it was received.
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SurfaceSystemStatus {
+    #[deku(bits = "3")]
+    pub sub_type: u8,
+    pub reserved: [u8; 6],
+}
+
+impl fmt::Display for SurfaceSystemStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Subtype:       {}", self.sub_type)?;
+        writeln!(f, "  Data:          {}", hex::encode_upper(self.reserved))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use super::*;
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::df::DF;
+    use crate::decoders::raw_types::icao::ICAO;
+    use crate::decoders::raw_types::me::ME;
+
+    #[test]
+    fn decode_surface_system_status() {
+        "debug".enable_logging();
+
+        let message = "8D4840D6C1123456789ABCFC9F73";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded}");
+
+        match decoded.df {
+            DF::ADSB(adsb) => {
+                assert_eq!(
+                    adsb.me,
+                    ME::SurfaceSystemStatus(SurfaceSystemStatus {
+                        sub_type: 1,
+                        reserved: [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC],
+                    })
+                );
+                // the whole ME field is consumed, so the parity lines up
+                assert_eq!(adsb.pi, ICAO([0xFC, 0x9F, 0x73]));
+            }
+            _ => panic!("Wrong DF"),
+        }
+    }
+}
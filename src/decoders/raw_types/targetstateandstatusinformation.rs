@@ -4,6 +4,8 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
+use deku::ctx::{BitSize, Endian};
+use deku::no_std_io::{Seek, Write};
 use deku::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -15,7 +17,7 @@ use super::{
 };
 
 /// Target State and Status (§2.2.3.2.7.1)
-#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, PartialEq)]
 pub struct TargetStateAndStatusInformation {
     // TODO Support Target State and Status defined in DO-260A, ADS-B Version=1
     // TODO Support reserved 2..=3
@@ -25,20 +27,23 @@ pub struct TargetStateAndStatusInformation {
     #[deku(
         bits = "12",
         endian = "big",
-        map = "|altitude: u32| -> Result<_, DekuError> {Ok(if altitude > 1 {(altitude - 1) * 32} else {0} )}"
+        map = "|altitude: u32| -> Result<_, DekuError> {Ok(if altitude > 1 {(altitude - 1) * 32} else {0} )}",
+        writer = "Self::write_altitude(deku::writer, *altitude)"
     )]
     pub altitude: u32,
     #[deku(
         bits = "9",
         endian = "big",
-        map = "|qnh: u32| -> Result<_, DekuError> {if qnh == 0 { Ok(0.0) } else { Ok(800.0 + f64::from(qnh - 1) * 0.8)}}"
+        map = "|qnh: u32| -> Result<_, DekuError> {if qnh == 0 { Ok(0.0) } else { Ok(800.0 + f64::from(qnh - 1) * 0.8)}}",
+        writer = "Self::write_qnh(deku::writer, *qnh)"
     )]
     pub qnh: f64,
     pub is_heading: SelectedHeadingStatus,
     #[deku(
         bits = "9",
         endian = "big",
-        map = "|heading: u16| -> Result<_, DekuError> {Ok(f64::from(heading) * 180.0 / 256.0)}"
+        map = "|heading: u16| -> Result<_, DekuError> {Ok(f64::from(heading) * 180.0 / 256.0)}",
+        writer = "Self::write_heading(deku::writer, *heading)"
     )]
     pub heading: f64,
     #[deku(bits = "4")]
@@ -61,6 +66,55 @@ pub struct TargetStateAndStatusInformation {
 }
 
 impl TargetStateAndStatusInformation {
+    fn write_altitude<W: Write + Seek>(
+        writer: &mut Writer<W>,
+        altitude: u32,
+    ) -> Result<(), DekuError> {
+        let encoded = match altitude {
+            0 => 0,
+            altitude if altitude % 32 == 0 && altitude / 32 < 0x0fff => altitude / 32 + 1,
+            altitude => {
+                return Err(DekuError::InvalidParam(
+                    format!("Selected altitude {altitude} ft can't be encoded").into(),
+                ));
+            }
+        };
+        encoded.to_writer(writer, (Endian::Big, BitSize(12)))
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn write_qnh<W: Write + Seek>(writer: &mut Writer<W>, qnh: f64) -> Result<(), DekuError> {
+        let encoded = if qnh == 0.0 {
+            0
+        } else {
+            ((qnh - 800.0) / 0.8).round() as u32 + 1
+        };
+        Self::write_in_range(writer, encoded, "QNH")
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn write_heading<W: Write + Seek>(
+        writer: &mut Writer<W>,
+        heading: f64,
+    ) -> Result<(), DekuError> {
+        let encoded = (heading * 256.0 / 180.0).round() as u32;
+        Self::write_in_range(writer, encoded, "Selected heading")
+    }
+
+    /// QNH and heading are both 9 bits
+    fn write_in_range<W: Write + Seek>(
+        writer: &mut Writer<W>,
+        encoded: u32,
+        name: &str,
+    ) -> Result<(), DekuError> {
+        if encoded > 0x01ff {
+            return Err(DekuError::InvalidParam(
+                format!("{name} is out of range").into(),
+            ));
+        }
+        encoded.to_writer(writer, (Endian::Big, BitSize(9)))
+    }
+
     #[must_use]
     pub const fn is_reserved_zero(&self) -> bool {
         self.reserved0 == 0 && self.reserved1 == 0
@@ -0,0 +1,91 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::{ac13field::AC13Field, icao::ICAO};
+
+/// TID: Threat Identity Data, selected by the TTI: Threat Type Indicator (4.3.8.4.2.2.1.6)
+#[derive(
+    Serialize, Deserialize, DekuRead, DekuWrite, Debug, Clone, Copy, Eq, PartialEq, PartialOrd,
+)]
+#[deku(ctx = "tti: u8", id = "tti")]
+pub enum ThreatIdentity {
+    /// No identity data in the TID
+    #[deku(id = "0")]
+    NoIdentity(#[deku(bits = "26", endian = "big")] u32),
+
+    /// Mode S address of the threat
+    #[deku(id = "1")]
+    ModeSAddress {
+        address: ICAO,
+        #[deku(bits = "2")]
+        reserved: u8,
+    },
+
+    /// Altitude, range and bearing of a threat that is not Mode S equipped
+    #[deku(id = "2")]
+    AltitudeRangeBearing {
+        /// TIDA: altitude code of the threat
+        altitude: AC13Field,
+        /// TIDR: most recent range of the threat
+        #[deku(bits = "7")]
+        range: u8,
+        /// TIDB: most recent bearing of the threat, relative to own heading
+        #[deku(bits = "6")]
+        bearing: u8,
+    },
+
+    #[deku(id = "3")]
+    NotAssigned(#[deku(bits = "26", endian = "big")] u32),
+}
+
+impl ThreatIdentity {
+    /// Range of the threat in nautical miles. 12.6 means more than 12.55 NM.
+    #[must_use]
+    pub fn get_range(&self) -> Option<f64> {
+        match self {
+            Self::AltitudeRangeBearing { range, .. } if *range > 0 => {
+                Some(f64::from(*range - 1) / 10.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Bearing of the threat relative to own heading, as the lower edge of a 6 degree sector
+    #[must_use]
+    pub fn get_bearing(&self) -> Option<u16> {
+        match self {
+            Self::AltitudeRangeBearing { bearing, .. } if (1..=60).contains(bearing) => {
+                Some((u16::from(*bearing) - 1) * 6)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ThreatIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoIdentity(_) => write!(f, "no identity data"),
+            Self::ModeSAddress { address, .. } => write!(f, "Mode S address {address}"),
+            Self::AltitudeRangeBearing { altitude, .. } => {
+                write!(f, "altitude {} ft", altitude.0)?;
+                match self.get_range() {
+                    Some(range) => write!(f, ", range {range:.1} NM")?,
+                    None => write!(f, ", no range")?,
+                }
+                match self.get_bearing() {
+                    Some(bearing) => write!(f, ", bearing {bearing}-{} degrees", bearing + 6),
+                    None => write!(f, ", no bearing"),
+                }
+            }
+            Self::NotAssigned(_) => write!(f, "not assigned"),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use super::sign::Sign;
+
+/// Track and Turn Report, Comm-B BDS 5,0 (Table A-2-80)
+///
+/// Signed values are two's complement, the sign bit being the most significant bit.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, DekuRead, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TrackAndTurnReport {
+    #[deku(bits = "1")]
+    pub roll_angle_status: bool,
+    pub roll_angle_sign: Sign,
+    /// Roll angle, LSB 45/256 degrees
+    #[deku(bits = "9", endian = "big")]
+    pub roll_angle: u16,
+    #[deku(bits = "1")]
+    pub true_track_angle_status: bool,
+    pub true_track_angle_sign: Sign,
+    /// True track angle, LSB 90/512 degrees
+    #[deku(bits = "10", endian = "big")]
+    pub true_track_angle: u16,
+    #[deku(bits = "1")]
+    pub ground_speed_status: bool,
+    /// Ground speed in knots
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|speed: u16| -> Result<_, DekuError> {Ok(speed * 2)}"
+    )]
+    pub ground_speed: u16,
+    #[deku(bits = "1")]
+    pub track_angle_rate_status: bool,
+    pub track_angle_rate_sign: Sign,
+    /// Track angle rate, LSB 8/256 degrees per second
+    #[deku(bits = "9", endian = "big")]
+    pub track_angle_rate: u16,
+    #[deku(bits = "1")]
+    pub true_airspeed_status: bool,
+    /// True airspeed in knots
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|speed: u16| -> Result<_, DekuError> {Ok(speed * 2)}"
+    )]
+    pub true_airspeed: u16,
+}
+
+impl fmt::Display for TrackAndTurnReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(roll) = self.get_roll_angle() {
+            writeln!(f, "  Roll angle:    {roll:.1} degrees")?;
+        }
+        if let Some(track) = self.get_true_track_angle() {
+            writeln!(f, "  Track angle:   {track:.1} degrees")?;
+        }
+        if let Some(speed) = self.get_ground_speed() {
+            writeln!(f, "  Ground speed:  {speed} knots")?;
+        }
+        if let Some(rate) = self.get_track_angle_rate() {
+            writeln!(f, "  Track rate:    {rate:.3} degrees/second")?;
+        }
+        if let Some(speed) = self.get_true_airspeed() {
+            writeln!(f, "  TAS:           {speed} knots")?;
+        }
+        Ok(())
+    }
+}
+
+impl TrackAndTurnReport {
+    /// Checks the register is plausible: no value is present without its status bit, and the
+    /// values fall inside what an aircraft can actually report.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        // the roll sign bit is not checked, some transponders leave it set with no roll angle
+        if !self.roll_angle_status && self.roll_angle != 0 {
+            return false;
+        }
+
+        if !self.true_track_angle_status
+            && (self.true_track_angle != 0 || self.true_track_angle_sign == Sign::Negative)
+        {
+            return false;
+        }
+
+        if !self.ground_speed_status && self.ground_speed != 0 {
+            return false;
+        }
+
+        if !self.track_angle_rate_status
+            && (self.track_angle_rate != 0 || self.track_angle_rate_sign == Sign::Negative)
+        {
+            return false;
+        }
+
+        if !self.true_airspeed_status && self.true_airspeed != 0 {
+            return false;
+        }
+
+        if !(self.roll_angle_status
+            || self.true_track_angle_status
+            || self.ground_speed_status
+            || self.track_angle_rate_status
+            || self.true_airspeed_status)
+        {
+            return false;
+        }
+
+        if self.get_roll_angle().is_some_and(|roll| roll.abs() > 50.0) {
+            return false;
+        }
+
+        if self.get_ground_speed().is_some_and(|speed| speed > 600) {
+            return false;
+        }
+
+        if self.get_true_airspeed().is_some_and(|speed| speed > 600) {
+            return false;
+        }
+
+        if let (Some(ground_speed), Some(true_airspeed)) =
+            (self.get_ground_speed(), self.get_true_airspeed())
+            && ground_speed.abs_diff(true_airspeed) > 200
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Roll angle in degrees, negative is left wing down
+    #[must_use]
+    pub fn get_roll_angle(&self) -> Option<f64> {
+        if !self.roll_angle_status {
+            return None;
+        }
+
+        Some(f64::from(twos_complement(self.roll_angle_sign, self.roll_angle, 512)) * 45.0 / 256.0)
+    }
+
+    /// True track angle in degrees (0-360)
+    #[must_use]
+    pub fn get_true_track_angle(&self) -> Option<f64> {
+        if !self.true_track_angle_status {
+            return None;
+        }
+
+        let track = f64::from(twos_complement(
+            self.true_track_angle_sign,
+            self.true_track_angle,
+            1024,
+        )) * 90.0
+            / 512.0;
+
+        Some(if track < 0.0 { track + 360.0 } else { track })
+    }
+
+    #[must_use]
+    pub const fn get_ground_speed(&self) -> Option<u16> {
+        if self.ground_speed_status {
+            Some(self.ground_speed)
+        } else {
+            None
+        }
+    }
+
+    /// Track angle rate in degrees per second. All ones in the value bits means the rate is
+    /// not available.
+    #[must_use]
+    pub fn get_track_angle_rate(&self) -> Option<f64> {
+        if !self.track_angle_rate_status || self.track_angle_rate == 0x1FF {
+            return None;
+        }
+
+        Some(
+            f64::from(twos_complement(
+                self.track_angle_rate_sign,
+                self.track_angle_rate,
+                512,
+            )) * 8.0
+                / 256.0,
+        )
+    }
+
+    #[must_use]
+    pub const fn get_true_airspeed(&self) -> Option<u16> {
+        if self.true_airspeed_status {
+            Some(self.true_airspeed)
+        } else {
+            None
+        }
+    }
+}
+
+/// Combine a sign bit and the remaining value bits of a two's complement field.
+/// `range` is 2 to the power of the number of value bits.
+pub(crate) fn twos_complement(sign: Sign, value: u16, range: i32) -> i32 {
+    match sign {
+        Sign::Positive => i32::from(value),
+        Sign::Negative => i32::from(value) - range,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sdre_rust_logging::SetupLogging;
+
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::bds::BDS;
+    use crate::decoders::raw_types::df::DF;
+
+    #[test]
+    fn decode_track_and_turn_report() {
+        "debug".enable_logging();
+
+        let message = "A000139381951536E024D4CCF6B5";
+        let decoded = message.to_adsb_raw().unwrap();
+        info!("Decoded {decoded:?}");
+
+        match decoded.df {
+            DF::CommBAltitudeReply { bds, .. } => match bds {
+                BDS::TrackAndTurnReport(report) => {
+                    assert!(report.is_valid());
+                    let roll = report.get_roll_angle().unwrap();
+                    assert!((roll - 2.1).abs() < 0.01, "Roll was {roll}");
+                    let track = report.get_true_track_angle().unwrap();
+                    assert!((track - 114.258).abs() < 0.001, "Track was {track}");
+                    assert_eq!(report.get_ground_speed(), Some(438));
+                    let rate = report.get_track_angle_rate().unwrap();
+                    assert!((rate - 0.125).abs() < 0.001, "Track rate was {rate}");
+                    assert_eq!(report.get_true_airspeed(), Some(424));
+                }
+                _ => panic!("BDS is not Track and Turn Report: {bds:?}"),
+            },
+            _ => panic!("DF is not Comm-B Altitude Reply"),
+        }
+    }
+}
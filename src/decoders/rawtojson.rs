@@ -10,30 +10,44 @@ use crate::decoders::{
 };
 
 use super::{
-    common_types::surveillancestatus::SurveillanceStatus,
+    common_types::{heading::Heading, speed::Speed, surveillancestatus::SurveillanceStatus},
     errors::conversion::ConversionError,
     helpers::{cpr_calculators::Position, time::get_time_as_timestamp},
     json::JSONMessage,
     json_types::{
-        adsbversion::ADSBVersion, emergency::Emergency, emmittercategory::EmitterCategory,
-        nacp::NavigationIntegrityCategory, nacv::NavigationAccuracyVelocity,
-        navigationmodes::NavigationModes, sil::SourceIntegrityLevel,
-        sourceintegritylevel::SourceIntegrityLevelType,
+        acasresolutionadvisory::ACASResolutionAdvisoryReport, adsbversion::ADSBVersion,
+        altitude::Altitude, emergency::Emergency, emmittercategory::EmitterCategory,
+        messagetype::MessageType, nacp::NavigationIntegrityCategory,
+        nacv::NavigationAccuracyVelocity, navigationmodes::NavigationModes,
+        sil::SourceIntegrityLevel, sourceintegritylevel::SourceIntegrityLevelType, tisb::TiSB,
     },
     raw_types::{
+        acasresolutionadvisory::ACAS_RA_REGISTER,
         airbornevelocity::AirborneVelocity,
         airbornevelocitysubtype::AirborneVelocitySubType,
-        aircraftstatus::AircraftStatus,
+        aircraftstatus::{ACAS_RA_BROADCAST_HEADER, AircraftStatus},
         autopilot_modes::{AltitudeHold, ApproachMode, AutopilotEngaged, LNAV, TCAS, VNAVEngaged},
+        bds::BDS,
+        bdsinference::CommBContext,
+        controlfield::ControlField,
+        controlfieldtype::ControlFieldType,
+        df::DF,
+        emergencyprioritystatus::EmergencyPriorityStatus,
         emergencystate::EmergencyState,
+        flightstatus::FlightStatus,
         fms::IsFMS,
         heading::SelectedHeadingStatus,
+        headingandspeedreport::HeadingAndSpeedReport,
         identification::Identification,
+        me::ME,
+        meteorologicalroutineairreport::MeteorologicalRoutineAirReport,
         modevalidity::IsValidMode,
         noposition::NoPosition,
         operationstatus::{CapabilityClass, OperationStatus},
+        selectedverticalintention::SelectedVerticalIntention,
         surfaceposition::SurfacePosition,
         targetstateandstatusinformation::TargetStateAndStatusInformation,
+        trackandturnreport::TrackAndTurnReport,
         verticleratesource::VerticalRateSource,
     },
 };
@@ -174,7 +188,25 @@ pub fn update_operational_status(
     Ok(())
 }
 
-pub fn update_aircraft_status(json: &mut JSONMessage, operation_status: &AircraftStatus) {
+pub fn update_aircraft_status(json: &mut JSONMessage, aircraft_status: &AircraftStatus) {
+    match aircraft_status {
+        AircraftStatus::EmergencyPriorityStatus(emergency_priority_status) => {
+            update_emergency_priority_status(json, emergency_priority_status);
+        }
+        AircraftStatus::ACASRaBroadcast(ra) => {
+            json.acas_ra = Some(ACASResolutionAdvisoryReport::new(
+                ACAS_RA_BROADCAST_HEADER,
+                ra,
+            ));
+        }
+        AircraftStatus::NoInformation(_) | AircraftStatus::Reserved(..) => (),
+    }
+}
+
+pub fn update_emergency_priority_status(
+    json: &mut JSONMessage,
+    operation_status: &EmergencyPriorityStatus,
+) {
     match operation_status.emergency_state {
         EmergencyState::None => {
             json.emergency = Some(Emergency::None);
@@ -268,6 +300,294 @@ pub fn update_target_state_and_status_information(
     }
 }
 
+/// Updates the JSON message from the MB field of a Comm-B reply.
+///
+/// An MB field that could not be told apart from its content alone is decoded again, this time
+/// using what is already known about the aircraft. A register that was picked from the content is
+/// kept as it is, see `infer_bds`.
+pub fn update_from_comm_b(json: &mut JSONMessage, bds: &BDS) {
+    let inferred;
+    let bds = match bds {
+        BDS::Unknown(..) => {
+            let Some(mb) = bds.get_mb() else {
+                return;
+            };
+            inferred = BDS::from_mb_with_context(&mb, &comm_b_context(json));
+            &inferred
+        }
+        _ => bds,
+    };
+
+    match bds {
+        BDS::AircraftIdentification(callsign) => {
+            json.calculated_best_flight_id = Some(callsign.clone().into());
+        }
+        BDS::ACASResolutionAdvisory(ra) => {
+            json.acas_ra = Some(ACASResolutionAdvisoryReport::new(ACAS_RA_REGISTER, ra));
+        }
+        BDS::SelectedVerticalIntention(selected_vertical_intention) => {
+            update_selected_vertical_intention(json, selected_vertical_intention);
+        }
+        BDS::MeteorologicalRoutineAirReport(meteorological_routine_air_report) => {
+            update_meteorological_routine_air_report(json, meteorological_routine_air_report);
+        }
+        BDS::MeteorologicalHazardReport(meteorological_hazard_report) => {
+            if let Some(temperature) = meteorological_hazard_report.get_static_air_temperature() {
+                update_air_temperature(json, temperature);
+            }
+        }
+        BDS::TrackAndTurnReport(track_and_turn_report) => {
+            update_track_and_turn_report(json, track_and_turn_report);
+        }
+        BDS::HeadingAndSpeedReport(heading_and_speed_report) => {
+            update_heading_and_speed_report(json, heading_and_speed_report);
+        }
+        _ => (),
+    }
+}
+
+/// The message type of a DF18 source. Management messages, coarse TIS-B (its ME layout is not
+/// the DF17 one) and the reserved code don't describe an aircraft we can use.
+#[must_use]
+pub fn message_type_from_control_field(control_field: &ControlField) -> Option<MessageType> {
+    let icao = control_field.is_icao_address();
+    match control_field.t {
+        ControlFieldType::ADSB_ES_NT => Some(MessageType::ADSBICAONONTRANSPONDER),
+        ControlFieldType::ADSB_ES_NT_ALT => Some(MessageType::ADSBOTHER),
+        ControlFieldType::TISB_FINE if icao => Some(MessageType::ADSBICAOSECONDARYSURVEILLANCE),
+        ControlFieldType::TISB_FINE => Some(MessageType::ADSBTRACKFILE),
+        ControlFieldType::TISB_ADSB_RELAY => Some(MessageType::ADSBOTHERSECONDARYSURVEILLANCE),
+        ControlFieldType::TISB_ADSB if icao => Some(MessageType::ADSBICAOREBROADCAST),
+        ControlFieldType::TISB_ADSB => Some(MessageType::ADSBOTHERREBROADCAST),
+        ControlFieldType::TISB_COARSE
+        | ControlFieldType::TISB_MANAGE
+        | ControlFieldType::Reserved => None,
+    }
+}
+
+/// The fields of the JSON message an extended squitter ME updates, for the `tisb` list.
+#[must_use]
+pub fn get_tisb_fields(me: &ME) -> Vec<TiSB> {
+    match me {
+        ME::AircraftIdentification(..) => vec![TiSB::Callsign],
+        ME::AirborneVelocity(velocity) => match velocity.vrate_src {
+            VerticalRateSource::BarometricPressureAltitude => {
+                vec![TiSB::GroundSpeed, TiSB::Track, TiSB::BaroRate, TiSB::NACv]
+            }
+            VerticalRateSource::GeometricAltitude => {
+                vec![TiSB::GroundSpeed, TiSB::Track, TiSB::GeomRate, TiSB::NACv]
+            }
+        },
+        ME::SurfacePosition(..) => vec![
+            TiSB::Latitude,
+            TiSB::Longitude,
+            TiSB::NIC,
+            TiSB::RadiusOfContainment,
+            TiSB::GroundSpeed,
+            TiSB::Track,
+        ],
+        ME::AirbornePositionBaroAltitude(..) => vec![
+            TiSB::Latitude,
+            TiSB::Longitude,
+            TiSB::NIC,
+            TiSB::RadiusOfContainment,
+            TiSB::Altitude,
+        ],
+        ME::AirbornePositionGNSSAltitude(..) => vec![
+            TiSB::Latitude,
+            TiSB::Longitude,
+            TiSB::NIC,
+            TiSB::RadiusOfContainment,
+            TiSB::AltGeom,
+        ],
+        ME::AircraftOperationStatus(..) => vec![TiSB::NACp, TiSB::SIL, TiSB::SILType],
+        _ => vec![],
+    }
+}
+
+/// Updates the JSON message from the altitude, identity and flight status carried by the
+/// Mode S surveillance replies (DF0/4/5/16/20/21). Other formats are left alone.
+pub fn update_from_surveillance_reply(json: &mut JSONMessage, df: &DF) {
+    match df {
+        DF::ShortAirAirSurveillance { vs, altitude, .. } | DF::LongAirAir { vs, altitude, .. } => {
+            if *vs == 1 {
+                json.barometric_altitude = Some("ground".into());
+            } else if altitude.0 > 0 {
+                json.barometric_altitude = Some(altitude.0.into());
+            }
+        }
+        DF::SurveillanceAltitudeReply { fs, ac: alt, .. }
+        | DF::CommBAltitudeReply {
+            flight_status: fs,
+            alt,
+            ..
+        } => {
+            update_flight_status(json, *fs);
+            if !fs.is_on_ground() && alt.0 > 0 {
+                json.barometric_altitude = Some(alt.0.into());
+            }
+        }
+        DF::SurveillanceIdentityReply { fs, id, .. } => {
+            update_flight_status(json, *fs);
+            json.transponder_squawk_code = Some(format!("{:04X}", id.0).into());
+        }
+        DF::CommBIdentityReply { fs, id, .. } => {
+            update_flight_status(json, *fs);
+            json.transponder_squawk_code = Some(format!("{id:04X}").into());
+        }
+        _ => (),
+    }
+}
+
+fn update_flight_status(json: &mut JSONMessage, flight_status: FlightStatus) {
+    if flight_status.is_on_ground() {
+        json.barometric_altitude = Some("ground".into());
+    }
+
+    match flight_status {
+        FlightStatus::NoAlertNoSPIAirborne | FlightStatus::NoAlertNoSPIOnGround => {
+            json.flight_status = Some(SurveillanceStatus::NoCondition);
+            json.flight_status_special_position_id_bit = Some(0);
+        }
+        // The FS alert is raised for 18 seconds after the squawk changes, or for as long
+        // as an emergency code is set. We can't tell which from the reply itself.
+        FlightStatus::AlertNoSPIAirborne | FlightStatus::AlertNoSPIOnGround => {
+            json.flight_status = Some(SurveillanceStatus::TemporaryAlert);
+            json.flight_status_special_position_id_bit = Some(0);
+        }
+        FlightStatus::AlertSPIAirborneGround => {
+            json.flight_status = Some(SurveillanceStatus::TemporaryAlert);
+            json.flight_status_special_position_id_bit = Some(1);
+        }
+        FlightStatus::NoAlertSPIAirborneGround => {
+            json.flight_status = Some(SurveillanceStatus::SPICondition);
+            json.flight_status_special_position_id_bit = Some(1);
+        }
+        FlightStatus::Reserved | FlightStatus::NotAssigned => (),
+    }
+}
+
+/// What the JSON message already knows about the aircraft, for scoring Comm-B registers
+#[must_use]
+pub fn comm_b_context(json: &JSONMessage) -> CommBContext {
+    CommBContext {
+        ground_speed: json.ground_speed.as_ref().map(Speed::get_speed),
+        track: json
+            .true_track_over_ground
+            .as_ref()
+            .and_then(Heading::get_heading),
+        altitude: match &json.barometric_altitude {
+            Some(Altitude::U16(altitude)) => Some(i32::from(*altitude)),
+            Some(Altitude::U32(altitude)) => i32::try_from(*altitude).ok(),
+            _ => None,
+        },
+        callsign: json
+            .calculated_best_flight_id
+            .as_ref()
+            .map(ToString::to_string),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn update_meteorological_routine_air_report(
+    json: &mut JSONMessage,
+    meteorological_routine_air_report: &MeteorologicalRoutineAirReport,
+) {
+    if let Some((speed, direction)) = meteorological_routine_air_report.get_wind() {
+        json.wind_speed = Some(u32::from(speed));
+        json.wind_direction = Some(direction.round() as u32 % 360);
+    }
+
+    update_air_temperature(
+        json,
+        meteorological_routine_air_report.get_static_air_temperature(),
+    );
+}
+
+/// Sets the outside air temperature, and the total air temperature when the Mach number is known
+#[allow(clippy::cast_possible_truncation)]
+fn update_air_temperature(json: &mut JSONMessage, static_air_temperature: f64) {
+    json.outside_air_temperature = Some(static_air_temperature.round() as i32);
+
+    if let Some(mach) = json.mach {
+        let mach = f64::from(mach);
+        let total_air_temperature =
+            (static_air_temperature + 273.15) * (1.0 + 0.2 * mach * mach) - 273.15;
+        json.total_air_temperature = Some(total_air_temperature.round() as i32);
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub fn update_track_and_turn_report(
+    json: &mut JSONMessage,
+    track_and_turn_report: &TrackAndTurnReport,
+) {
+    if let Some(roll) = track_and_turn_report.get_roll_angle() {
+        json.roll = Some(roll as f32);
+    }
+
+    if let Some(track_rate) = track_and_turn_report.get_track_angle_rate() {
+        json.track_rate = Some(track_rate as f32);
+    }
+
+    if let Some(true_airspeed) = track_and_turn_report.get_true_airspeed() {
+        json.true_air_speed = Some(f32::from(true_airspeed).into());
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub fn update_heading_and_speed_report(
+    json: &mut JSONMessage,
+    heading_and_speed_report: &HeadingAndSpeedReport,
+) {
+    if let Some(heading) = heading_and_speed_report.get_magnetic_heading() {
+        json.magnetic_heading = Some(heading.into());
+    }
+
+    if let Some(indicated_airspeed) = heading_and_speed_report.get_indicated_airspeed() {
+        json.indicated_air_speed = Some(f32::from(indicated_airspeed).into());
+    }
+
+    if let Some(mach) = heading_and_speed_report.get_mach() {
+        json.mach = Some(mach as f32);
+    }
+}
+
+pub fn update_selected_vertical_intention(
+    json: &mut JSONMessage,
+    selected_vertical_intention: &SelectedVerticalIntention,
+) {
+    if let Some(altitude) = selected_vertical_intention.get_mcp_fcu_selected_altitude() {
+        json.autopilot_selected_altitude = Some(altitude.into());
+    }
+
+    if let Some(altitude) = selected_vertical_intention.get_fms_selected_altitude() {
+        json.flight_management_system_selected_altitude = Some(altitude.into());
+    }
+
+    if let Some(qnh) = selected_vertical_intention.get_barometric_pressure_setting() {
+        json.selected_altimeter = Some(qnh.into());
+    }
+
+    if selected_vertical_intention.mode_status {
+        let mut output_modes: Vec<NavigationModes> = Vec::new();
+
+        if selected_vertical_intention.vnav == VNAVEngaged::Engaged {
+            output_modes.push(NavigationModes::VNAV);
+        }
+
+        if selected_vertical_intention.alt_hold == AltitudeHold::Engaged {
+            output_modes.push(NavigationModes::AltHold);
+        }
+
+        if selected_vertical_intention.approach == ApproachMode::Engaged {
+            output_modes.push(NavigationModes::Approach);
+        }
+
+        json.autopilot_modes = Some(output_modes);
+    }
+}
+
 fn calculate_position_from_even_odd(
     json: &mut JSONMessage,
     even_frame: Option<&Position>,
@@ -0,0 +1,297 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// SBS-1 / BaseStation output, as served on port 30003 by dump1090, readsb and Virtual Radar Server.
+// Every line is 22 comma separated fields:
+//
+//  1 message type (MSG, SEL, ID, AIR, STA, CLK)     12 altitude in feet
+//  2 transmission type (1 to 8 for MSG)             13 ground speed in knots
+//  3 session ID                                     14 track
+//  4 aircraft ID                                    15 latitude
+//  5 hex ident                                      16 longitude
+//  6 flight ID                                      17 vertical rate in feet per minute
+//  7 date generated                                 18 squawk
+//  8 time generated                                 19 alert, the squawk changed
+//  9 date logged                                    20 emergency
+// 10 time logged                                    21 SPI
+// 11 callsign                                       22 on ground
+//
+// The transmission type says which of fields 11 to 22 are set, the rest are empty. Flags are `-1`
+// (some feeders send `1`) for set and `0` for not set.
+
+use std::str::FromStr;
+
+use crate::MessageResult;
+use crate::error_handling::adsb_sbs_error::ADSBSBSError;
+
+use super::{
+    common_types::{speed::Speed, surveillancestatus::SurveillanceStatus},
+    helpers::time::get_time_as_timestamp,
+    json::JSONMessage,
+    json_types::{
+        emergency::Emergency, messagetype::MessageType, secondsago::SecondsAgo, squawk::Squawk,
+    },
+};
+
+const SBS_SEPARATOR: char = ',';
+const SBS_AIRCRAFT_MESSAGE: &str = "MSG";
+const SBS_FIELD_COUNT: usize = 22;
+const SBS_MESSAGE_TYPE: usize = 0;
+const SBS_HEX_IDENT: usize = 4;
+const SBS_CALLSIGN: usize = 10;
+const SBS_ALTITUDE: usize = 11;
+const SBS_GROUND_SPEED: usize = 12;
+const SBS_TRACK: usize = 13;
+const SBS_LATITUDE: usize = 14;
+const SBS_LONGITUDE: usize = 15;
+const SBS_VERTICAL_RATE: usize = 16;
+const SBS_SQUAWK: usize = 17;
+const SBS_ALERT: usize = 18;
+const SBS_EMERGENCY: usize = 19;
+const SBS_SPI: usize = 20;
+const SBS_ON_GROUND: usize = 21;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The input is a single SBS line, with or without the line ending.
+pub trait NewSBSMessage {
+    /// Decodes an SBS `MSG` line to a `JSONMessage` holding only the fields the line carries, with the message type `OTHER`.
+    /// # Errors
+    /// If the line is not an SBS `MSG` line, the error is returned.
+    fn to_sbs(&self) -> MessageResult<JSONMessage>;
+}
+
+impl NewSBSMessage for String {
+    fn to_sbs(&self) -> MessageResult<JSONMessage> {
+        decode_sbs_line(self)
+    }
+}
+
+impl NewSBSMessage for str {
+    fn to_sbs(&self) -> MessageResult<JSONMessage> {
+        decode_sbs_line(self)
+    }
+}
+
+impl NewSBSMessage for &[u8] {
+    fn to_sbs(&self) -> MessageResult<JSONMessage> {
+        match std::str::from_utf8(self) {
+            Ok(line) => decode_sbs_line(line),
+            Err(_) => Err(ADSBSBSError::NotAnSBSLine {
+                line: String::from_utf8_lossy(self).to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+fn decode_sbs_line(line: &str) -> MessageResult<JSONMessage> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let fields: Vec<&str> = line.split(SBS_SEPARATOR).map(str::trim).collect();
+    if fields.len() != SBS_FIELD_COUNT {
+        return Err(ADSBSBSError::NotAnSBSLine {
+            line: line.to_string(),
+        }
+        .into());
+    }
+
+    if fields[SBS_MESSAGE_TYPE] != SBS_AIRCRAFT_MESSAGE {
+        return Err(ADSBSBSError::UnsupportedMessageType {
+            message_type: fields[SBS_MESSAGE_TYPE].to_string(),
+        }
+        .into());
+    }
+
+    if fields[SBS_HEX_IDENT].is_empty() {
+        return Err(ADSBSBSError::MissingAddress.into());
+    }
+
+    let mut json = JSONMessage::new(fields[SBS_HEX_IDENT].to_uppercase());
+    json.message_type = MessageType::OTHER;
+    // the line is the first the state machine hears of an aircraft it doesn't know yet
+    json.last_time_seen = SecondsAgo::now();
+
+    if !fields[SBS_CALLSIGN].is_empty() {
+        json.calculated_best_flight_id = Some(fields[SBS_CALLSIGN].to_string().into());
+    }
+
+    let on_ground = parse_flag(&fields, SBS_ON_GROUND, "on ground flag")?;
+    let altitude: Option<i32> = parse_field(&fields, SBS_ALTITUDE, "altitude")?;
+    if on_ground == Some(true) {
+        json.barometric_altitude = Some("ground".into());
+    } else if let Some(altitude) = altitude.and_then(|altitude| u32::try_from(altitude).ok()) {
+        // the JSON altitude can't be negative
+        json.barometric_altitude = Some(altitude.into());
+    }
+
+    json.ground_speed =
+        parse_field::<f64>(&fields, SBS_GROUND_SPEED, "ground speed")?.map(Speed::from);
+    json.true_track_over_ground = parse_field::<f64>(&fields, SBS_TRACK, "track")?.map(Into::into);
+    json.barometric_altitude_rate =
+        parse_field::<i32>(&fields, SBS_VERTICAL_RATE, "vertical rate")?.map(Into::into);
+
+    let latitude: Option<f64> = parse_field(&fields, SBS_LATITUDE, "latitude")?;
+    let longitude: Option<f64> = parse_field(&fields, SBS_LONGITUDE, "longitude")?;
+    if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+        json.latitude = Some(latitude.into());
+        json.longitude = Some(longitude.into());
+    }
+
+    if !fields[SBS_SQUAWK].is_empty() {
+        json.transponder_squawk_code = Some(Squawk::from(fields[SBS_SQUAWK]));
+    }
+
+    json.emergency =
+        parse_flag(&fields, SBS_EMERGENCY, "emergency flag")?.map(|emergency| {
+            match (emergency, fields[SBS_SQUAWK]) {
+                (false, _) => Emergency::None,
+                (true, "7500") => Emergency::Unlawful,
+                (true, "7600") => Emergency::Nordo,
+                (true, _) => Emergency::General,
+            }
+        });
+
+    let alert = parse_flag(&fields, SBS_ALERT, "alert flag")?;
+    let spi = parse_flag(&fields, SBS_SPI, "SPI flag")?;
+    json.flight_status = match (alert, spi) {
+        (Some(true), _) => Some(SurveillanceStatus::TemporaryAlert),
+        (_, Some(true)) => Some(SurveillanceStatus::SPICondition),
+        (None, None) => None,
+        _ => Some(SurveillanceStatus::NoCondition),
+    };
+    json.flight_status_special_position_id_bit = spi.map(u8::from);
+
+    Ok(json)
+}
+
+fn parse_field<T: FromStr>(fields: &[&str], index: usize, name: &str) -> MessageResult<Option<T>> {
+    let value = fields[index];
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    match value.parse() {
+        Ok(parsed) => Ok(Some(parsed)),
+        Err(_) => Err(ADSBSBSError::InvalidField {
+            field: name.to_string(),
+            value: value.to_string(),
+        }
+        .into()),
+    }
+}
+
+fn parse_flag(fields: &[&str], index: usize, name: &str) -> MessageResult<Option<bool>> {
+    match fields[index] {
+        "" => Ok(None),
+        "0" => Ok(Some(false)),
+        "-1" | "1" => Ok(Some(true)),
+        value => Err(ADSBSBSError::InvalidField {
+            field: name.to_string(),
+            value: value.to_string(),
+        }
+        .into()),
+    }
+}
+
+/// Merge a message decoded from an SBS line in to what is known about the aircraft.
+///
+/// Unlike `JSONMessage::update_from_json`, only the fields the line carried are replaced.
+pub fn update_from_sbs(json: &mut JSONMessage, sbs_message: &JSONMessage) {
+    if sbs_message.calculated_best_flight_id.is_some() {
+        json.calculated_best_flight_id
+            .clone_from(&sbs_message.calculated_best_flight_id);
+    }
+    if sbs_message.barometric_altitude.is_some() {
+        json.barometric_altitude
+            .clone_from(&sbs_message.barometric_altitude);
+    }
+    if sbs_message.ground_speed.is_some() {
+        json.ground_speed.clone_from(&sbs_message.ground_speed);
+    }
+    if sbs_message.true_track_over_ground.is_some() {
+        json.true_track_over_ground
+            .clone_from(&sbs_message.true_track_over_ground);
+    }
+    if sbs_message.barometric_altitude_rate.is_some() {
+        json.barometric_altitude_rate
+            .clone_from(&sbs_message.barometric_altitude_rate);
+    }
+    if sbs_message.latitude.is_some() && sbs_message.longitude.is_some() {
+        json.latitude.clone_from(&sbs_message.latitude);
+        json.longitude.clone_from(&sbs_message.longitude);
+        json.last_known_position = None;
+    }
+    if sbs_message.transponder_squawk_code.is_some() {
+        json.transponder_squawk_code
+            .clone_from(&sbs_message.transponder_squawk_code);
+    }
+    if sbs_message.emergency.is_some() {
+        json.emergency.clone_from(&sbs_message.emergency);
+    }
+    if sbs_message.flight_status.is_some() {
+        json.flight_status.clone_from(&sbs_message.flight_status);
+    }
+    if sbs_message.flight_status_special_position_id_bit.is_some() {
+        json.flight_status_special_position_id_bit =
+            sbs_message.flight_status_special_position_id_bit;
+    }
+    if json.message_type == MessageType::UNKNOWN {
+        json.message_type = MessageType::OTHER;
+    }
+
+    json.timestamp = get_time_as_timestamp();
+    json.last_time_seen = SecondsAgo::now();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn decode_sbs_lines() {
+        "debug".enable_logging();
+
+        let identification = "MSG,1,1,1,4840d6,1,2024/01/01,12:00:00.000,2024/01/01,12:00:00.000,KLM1023,,,,,,,,,,,0\r\n";
+        let message = identification.to_sbs().unwrap();
+        assert_eq!(message.transponder_hex.to_string(), "4840D6");
+        assert_eq!(message.message_type, MessageType::OTHER);
+        let seen = serde_json::to_value(&message).unwrap()["seen"]
+            .as_f64()
+            .unwrap();
+        assert!(seen < 1.0);
+        assert_eq!(
+            message.calculated_best_flight_id.unwrap().to_string(),
+            "KLM1023"
+        );
+        assert_eq!(message.barometric_altitude, None);
+
+        let position = "MSG,3,1,1,4840D6,1,2024/01/01,12:00:01.000,2024/01/01,12:00:01.000,,38000,,,52.25720,3.91937,,,0,0,0,0";
+        let message = position.to_sbs().unwrap();
+        assert_eq!(message.barometric_altitude, Some(38000_u32.into()));
+        assert!((message.latitude.unwrap().latitude - 52.25720).abs() < f64::EPSILON);
+        assert_eq!(message.flight_status, Some(SurveillanceStatus::NoCondition));
+        assert_eq!(message.emergency, Some(Emergency::None));
+
+        let hijack = "MSG,6,1,1,4840D6,1,,,,,,38000,,,,,,7500,-1,-1,0,0";
+        let message = hijack.as_bytes().to_sbs().unwrap();
+        assert_eq!(message.emergency, Some(Emergency::Unlawful));
+        assert_eq!(
+            message.flight_status,
+            Some(SurveillanceStatus::TemporaryAlert)
+        );
+
+        let mut aircraft = identification.to_sbs().unwrap();
+        update_from_sbs(&mut aircraft, &position.to_sbs().unwrap());
+        assert!(aircraft.calculated_best_flight_id.is_some());
+        assert!(aircraft.latitude.is_some());
+
+        assert!("AIR,,1,1,4840D6,1,,,,,,,,,,,,,,,,".to_sbs().is_err());
+        assert!("MSG,3,1,1,,1,,,,,,,,,,,,,,,,".to_sbs().is_err());
+        assert!("MSG,3,1,1,4840D6,1,,,,,,high,,,,,,,,,,".to_sbs().is_err());
+        assert!("*8D4840D6202CC371C32CE0576098;".to_sbs().is_err());
+    }
+}
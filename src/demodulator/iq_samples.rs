@@ -0,0 +1,220 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// IQ captures as written by `rtl_sdr` (`cu8`) and most other SDR tools (`cs16`), turned in to the
+// magnitudes the Mode S demodulator works on. Magnitudes are scaled so full scale is 1.0.
+
+use std::fmt::{self, Formatter};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Beast MLAT timestamps count at 12 MHz
+const MLAT_CLOCK_HZ: u64 = 12_000_000;
+
+/// How the I and Q values of a capture are stored
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum IQSampleFormat {
+    /// Unsigned 8 bit centered on 127.5, as written by `rtl_sdr`
+    #[default]
+    Cu8,
+    /// Signed 16 bit little endian
+    Cs16,
+}
+
+impl IQSampleFormat {
+    /// Bytes of one I/Q pair
+    #[must_use]
+    pub const fn bytes_per_sample(&self) -> usize {
+        match self {
+            IQSampleFormat::Cu8 => 2,
+            IQSampleFormat::Cs16 => 4,
+        }
+    }
+}
+
+impl FromStr for IQSampleFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cu8" => Ok(IQSampleFormat::Cu8),
+            "cs16" => Ok(IQSampleFormat::Cs16),
+            _ => Err(format!("Unsupported IQ sample format {s}")),
+        }
+    }
+}
+
+impl fmt::Display for IQSampleFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IQSampleFormat::Cu8 => write!(f, "cu8"),
+            IQSampleFormat::Cs16 => write!(f, "cs16"),
+        }
+    }
+}
+
+/// The sample rates the demodulator supports, the same ones as dump1090 and readsb
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum SampleRate {
+    /// 2.0 MHz, 2 samples per microsecond
+    TwoMHz,
+    /// 2.4 MHz, 2.4 samples per microsecond
+    #[default]
+    TwoPointFourMHz,
+}
+
+impl SampleRate {
+    #[must_use]
+    pub const fn get_hz(&self) -> u32 {
+        match self {
+            SampleRate::TwoMHz => 2_000_000,
+            SampleRate::TwoPointFourMHz => 2_400_000,
+        }
+    }
+
+    #[must_use]
+    pub fn get_samples_per_microsecond(&self) -> f64 {
+        f64::from(self.get_hz()) / 1_000_000.0
+    }
+
+    /// Ticks of the 12 MHz MLAT clock per sample
+    #[must_use]
+    pub const fn get_mlat_ticks_per_sample(&self) -> u64 {
+        MLAT_CLOCK_HZ / self.get_hz() as u64
+    }
+}
+
+impl TryFrom<u32> for SampleRate {
+    type Error = String;
+
+    fn try_from(hz: u32) -> Result<Self, Self::Error> {
+        match hz {
+            2_000_000 => Ok(SampleRate::TwoMHz),
+            2_400_000 => Ok(SampleRate::TwoPointFourMHz),
+            _ => Err(format!(
+                "Unsupported sample rate {hz} Hz, use 2000000 or 2400000"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SampleRate::TwoMHz => write!(f, "2.0 MHz"),
+            SampleRate::TwoPointFourMHz => write!(f, "2.4 MHz"),
+        }
+    }
+}
+
+/// Magnitude of every `cu8` I/Q pair, indexed by `I << 8 | Q`
+static CU8_MAGNITUDES: LazyLock<Vec<f32>> = LazyLock::new(|| {
+    (0..=u16::MAX)
+        .map(|pair| {
+            let [i, q] = pair.to_be_bytes();
+            magnitude(
+                (f32::from(i) - 127.5) / 127.5,
+                (f32::from(q) - 127.5) / 127.5,
+            )
+        })
+        .collect()
+});
+
+/// Anything beyond full scale is clipped, as the corners of the I/Q square would be over 1.0
+fn magnitude(i: f32, q: f32) -> f32 {
+    i.hypot(q).min(1.0)
+}
+
+/// Turns IQ bytes in to magnitudes. Chunks don't have to end on a whole sample, the rest is kept for the next one.
+#[derive(Debug, Clone, Default)]
+pub struct MagnitudeConverter {
+    format: IQSampleFormat,
+    partial_sample: Vec<u8>,
+}
+
+impl MagnitudeConverter {
+    #[must_use]
+    pub fn new(format: IQSampleFormat) -> MagnitudeConverter {
+        MagnitudeConverter {
+            format,
+            partial_sample: Vec::with_capacity(format.bytes_per_sample()),
+        }
+    }
+
+    /// Append the magnitude of every whole sample in `bytes`, after what was left from the last chunk, to `magnitudes`.
+    pub fn push(&mut self, bytes: &[u8], magnitudes: &mut Vec<f32>) {
+        let bytes_per_sample = self.format.bytes_per_sample();
+        let mut bytes = bytes;
+
+        if !self.partial_sample.is_empty() {
+            let missing = (bytes_per_sample - self.partial_sample.len()).min(bytes.len());
+            self.partial_sample.extend_from_slice(&bytes[..missing]);
+            bytes = &bytes[missing..];
+            if self.partial_sample.len() < bytes_per_sample {
+                return;
+            }
+            let sample = std::mem::take(&mut self.partial_sample);
+            self.push_samples(&sample, magnitudes);
+        }
+
+        let whole = bytes.len() - bytes.len() % bytes_per_sample;
+        self.push_samples(&bytes[..whole], magnitudes);
+        self.partial_sample.extend_from_slice(&bytes[whole..]);
+    }
+
+    fn push_samples(&self, bytes: &[u8], magnitudes: &mut Vec<f32>) {
+        match self.format {
+            IQSampleFormat::Cu8 => {
+                magnitudes.extend(bytes.chunks_exact(2).map(|pair| {
+                    CU8_MAGNITUDES[usize::from(u16::from_be_bytes([pair[0], pair[1]]))]
+                }));
+            }
+            IQSampleFormat::Cs16 => magnitudes.extend(bytes.chunks_exact(4).map(|pair| {
+                magnitude(
+                    f32::from(i16::from_le_bytes([pair[0], pair[1]])) / 32768.0,
+                    f32::from(i16::from_le_bytes([pair[2], pair[3]])) / 32768.0,
+                )
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn convert_iq_samples() {
+        "debug".enable_logging();
+
+        let mut magnitudes = Vec::new();
+        let mut converter = MagnitudeConverter::new(IQSampleFormat::Cu8);
+        // full scale I, nothing on Q, and a sample split over two chunks
+        converter.push(&[255, 128, 127], &mut magnitudes);
+        converter.push(&[128, 0], &mut magnitudes);
+        assert_eq!(magnitudes.len(), 2);
+        assert!((magnitudes[0] - 1.0).abs() < 0.01);
+        assert!(magnitudes[1] < 0.01);
+
+        let mut magnitudes = Vec::new();
+        let mut converter = MagnitudeConverter::new("CS16".parse().unwrap());
+        let sample = [(-16384_i16).to_le_bytes(), 16384_i16.to_le_bytes()].concat();
+        converter.push(&sample[..3], &mut magnitudes);
+        assert!(magnitudes.is_empty());
+        converter.push(&sample[3..], &mut magnitudes);
+        assert!((magnitudes[0] - 0.5_f32.hypot(0.5)).abs() < 0.001);
+
+        assert_eq!(
+            SampleRate::try_from(2_400_000),
+            Ok(SampleRate::TwoPointFourMHz)
+        );
+        assert_eq!(SampleRate::TwoMHz.get_mlat_ticks_per_sample(), 6);
+        assert_eq!(SampleRate::TwoPointFourMHz.get_mlat_ticks_per_sample(), 5);
+        assert!(SampleRate::try_from(2_500_000).is_err());
+        assert!("cf32".parse::<IQSampleFormat>().is_err());
+    }
+}
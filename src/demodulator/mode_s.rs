@@ -0,0 +1,648 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// Mode S demodulation of recorded IQ captures, for re-decoding a capture with different settings.
+//
+// A Mode S transmission is a pulse position modulated 1 Mbit/s signal: an 8 µs preamble with
+// pulses at 0, 1, 3.5 and 4.5 µs, then one bit per µs, a 1 sending its pulse in the first half
+// and a 0 in the second. At 2.0 and 2.4 MHz a half bit is only 1 or 1.2 samples, so every
+// half bit window is integrated over the magnitudes at a few sub-sample phases, and the phase that
+// gives a frame the parity accepts wins.
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+use super::iq_samples::{IQSampleFormat, MagnitudeConverter, SampleRate};
+use crate::MessageResult;
+use crate::decoders::beast::AdsbBeastMessage;
+use crate::decoders::helpers::error_correction::{ErrorCorrection, correct_errors};
+use crate::decoders::raw_types::helper_functions::modes_checksum;
+use crate::state_machine::icao_filter::{ICAO_FILTER_EXPIRY_IN_SECONDS, ICAOFilter};
+
+const PREAMBLE_MICROSECONDS: f64 = 8.0;
+const PREAMBLE_HALF_BITS: usize = 16;
+/// Half bit windows of the preamble with a pulse in them
+const PREAMBLE_PULSES: [usize; 4] = [0, 2, 7, 9];
+/// Half bit windows of the preamble that have to be quiet
+const PREAMBLE_QUIET: [usize; 12] = [1, 3, 4, 5, 6, 8, 10, 11, 12, 13, 14, 15];
+/// Sub-sample offsets tried for every sample
+const SAMPLE_PHASES: [f64; 5] = [0.0, 0.2, 0.4, 0.6, 0.8];
+const MODES_SHORT_MSG_BITS: usize = 56;
+const MODES_LONG_MSG_BITS: usize = 112;
+const BEAST_SHORT_FRAME_CHARACTER: u8 = 0x32;
+const BEAST_LONG_FRAME_CHARACTER: u8 = 0x33;
+const MAX_MLAT_TIMESTAMP: u64 = (1 << 48) - 1;
+/// Bytes read from a capture at a time
+const IQ_READ_BUFFER_BYTES: usize = 256 * 1024;
+
+/// How much a frame is trusted, higher is better
+const RANK_CLEAN: usize = 3;
+const RANK_ADDRESS_RECOVERED: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DemodulatorSettings {
+    pub sample_format: IQSampleFormat,
+    pub sample_rate: SampleRate,
+    /// How many times stronger than the mean of the quiet parts of the preamble every preamble pulse has to be
+    pub preamble_threshold: f64,
+    /// Repair of DF17/DF18 frames that don't pass the parity check
+    pub error_correction: ErrorCorrection,
+}
+
+impl Default for DemodulatorSettings {
+    fn default() -> Self {
+        DemodulatorSettings {
+            sample_format: IQSampleFormat::default(),
+            sample_rate: SampleRate::default(),
+            preamble_threshold: 2.0,
+            error_correction: ErrorCorrection::default(),
+        }
+    }
+}
+
+/// A frame found at one sample and phase
+struct Candidate {
+    frame: Vec<u8>,
+    rank: usize,
+    /// Summed difference between the halves of every bit, the best aligned phase has the most
+    confidence: f64,
+    signal_level: u8,
+    sample: usize,
+    phase: f64,
+}
+
+/// Demodulates Mode S frames from IQ bytes as they are read from a capture.
+///
+/// Frames come out as `AdsbBeastMessage` with the position in the capture as a 12 MHz MLAT timestamp,
+/// counted from the first sample to the start of the preamble, and the RMS pulse magnitude as the signal level.
+///
+/// Frames with the address XOR-ed into the parity (DF0/4/5/16/20/21) are only kept if their address
+/// was confirmed earlier in the capture by a clean DF11, DF17 or DF18, see `ICAOFilter`. Addresses expire by the
+/// time in to the capture, counted from the samples, so it doesn't matter how fast the capture is read.
+#[derive(Debug, Clone)]
+pub struct ModeSDemodulator {
+    settings: DemodulatorSettings,
+    converter: MagnitudeConverter,
+    icao_filter: ICAOFilter,
+    /// Magnitudes not searched yet, and enough before them to finish a frame that started earlier
+    magnitudes: Vec<f32>,
+    /// Sample number in the capture of the first entry of `magnitudes`
+    first_sample: u64,
+    samples_per_microsecond: f64,
+    /// Samples kept back so a frame starting at the last searched sample is complete
+    frame_samples: usize,
+}
+
+impl ModeSDemodulator {
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn new(settings: DemodulatorSettings) -> ModeSDemodulator {
+        let samples_per_microsecond = settings.sample_rate.get_samples_per_microsecond();
+        // the longest frame, started at the last phase of the sample after the one searched
+        let frame_samples = ((PREAMBLE_MICROSECONDS + MODES_LONG_MSG_BITS as f64)
+            * samples_per_microsecond)
+            .ceil() as usize
+            + 3;
+
+        ModeSDemodulator {
+            settings,
+            converter: MagnitudeConverter::new(settings.sample_format),
+            icao_filter: ICAOFilter::new_at(ICAO_FILTER_EXPIRY_IN_SECONDS, 0.0),
+            magnitudes: Vec::new(),
+            first_sample: 0,
+            samples_per_microsecond,
+            frame_samples,
+        }
+    }
+
+    /// Samples converted so far
+    #[must_use]
+    pub fn get_samples_processed(&self) -> u64 {
+        self.first_sample + self.magnitudes.len() as u64
+    }
+
+    /// Add the next chunk of the capture and get the frames that could be completed.
+    /// Chunks can be any size, a frame or sample split between two chunks is still found.
+    pub fn push(&mut self, iq: &[u8]) -> Vec<AdsbBeastMessage> {
+        self.converter.push(iq, &mut self.magnitudes);
+        let end = self.magnitudes.len().saturating_sub(self.frame_samples);
+        self.demodulate(end)
+    }
+
+    /// Search what is left at the end of the capture.
+    pub fn finish(&mut self) -> Vec<AdsbBeastMessage> {
+        let end = self.magnitudes.len();
+        let samples = self.first_sample + end as u64;
+        self.magnitudes.resize(end + self.frame_samples, 0.0);
+        let messages = self.demodulate(end);
+        self.magnitudes.clear();
+        self.first_sample = samples;
+        messages
+    }
+
+    /// Search the samples before `end` and drop the ones that can't be the start of a frame any more.
+    fn demodulate(&mut self, end: usize) -> Vec<AdsbBeastMessage> {
+        let mut messages = Vec::new();
+        let mut sample = 0;
+
+        while sample < end {
+            // the next sample may still hold a better aligned phase of the same frame
+            let Some(candidate) = self.best_candidate(sample) else {
+                sample += 1;
+                continue;
+            };
+            let candidate = match self.best_candidate(sample + 1) {
+                Some(next) if compare_candidates(&next, &candidate).is_gt() => next,
+                _ => candidate,
+            };
+
+            sample = candidate.sample + self.get_frame_samples(candidate.frame.len() * 8);
+            match self.get_message(&candidate) {
+                Ok(message) => {
+                    self.icao_filter.update_at(
+                        &message.raw_message,
+                        self.get_capture_time(candidate.sample),
+                    );
+                    messages.push(message);
+                }
+                Err(e) => debug!(
+                    "Failed to decode demodulated frame {}: {e}",
+                    hex::encode_upper(&candidate.frame)
+                ),
+            }
+        }
+
+        let searched = sample.min(self.magnitudes.len());
+        self.magnitudes.drain(..searched);
+        self.first_sample += searched as u64;
+
+        messages
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn get_frame_samples(&self, bits: usize) -> usize {
+        ((PREAMBLE_MICROSECONDS + bits as f64) * self.samples_per_microsecond) as usize
+    }
+
+    fn best_candidate(&self, sample: usize) -> Option<Candidate> {
+        SAMPLE_PHASES
+            .iter()
+            .filter_map(|phase| self.get_candidate(sample, *phase))
+            .max_by(compare_candidates)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn get_candidate(&self, sample: usize, phase: f64) -> Option<Candidate> {
+        let start = sample as f64 + phase;
+        if !self.is_preamble(start) {
+            return None;
+        }
+
+        let (first_byte, _, _) = self.slice_bits(start, 8);
+        let bits = match first_byte[0] >> 3 {
+            0 | 4 | 5 | 11 => MODES_SHORT_MSG_BITS,
+            16..=21 => MODES_LONG_MSG_BITS,
+            _ => return None,
+        };
+
+        let (mut frame, confidence, signal_level) = self.slice_bits(start, bits);
+        let rank = self.get_rank(&mut frame, self.get_capture_time(sample))?;
+
+        Some(Candidate {
+            frame,
+            rank,
+            confidence,
+            signal_level,
+            sample,
+            phase,
+        })
+    }
+
+    /// Seconds from the start of the capture to `sample`
+    #[allow(clippy::cast_precision_loss)]
+    fn get_capture_time(&self, sample: usize) -> f64 {
+        (self.first_sample + sample as u64) as f64 / f64::from(self.settings.sample_rate.get_hz())
+    }
+
+    /// How much the frame can be trusted, or `None` if it can't. A repairable DF17/DF18 is repaired.
+    /// `capture_time` is when the frame was sent, for checking the address of replies.
+    fn get_rank(&self, frame: &mut [u8], capture_time: f64) -> Option<usize> {
+        let syndrome = modes_checksum(frame, frame.len() * 8).ok()?;
+
+        match frame[0] >> 3 {
+            17..=19 if syndrome == 0 => Some(RANK_CLEAN),
+            11 if syndrome & 0x00ff_ff80 == 0 => Some(RANK_CLEAN),
+            17 | 18 => correct_errors(frame, self.settings.error_correction)
+                .map(|bits| RANK_CLEAN - bits.len()),
+            0 | 4 | 5 | 16 | 20 | 21
+                if self
+                    .icao_filter
+                    .contains_at(&format!("{syndrome:06X}"), capture_time) =>
+            {
+                Some(RANK_ADDRESS_RECOVERED)
+            }
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn is_preamble(&self, start: f64) -> bool {
+        let Some(weakest_pulse) = PREAMBLE_PULSES
+            .iter()
+            .map(|half_bit| self.get_half_bit(start, *half_bit))
+            .min_by(f64::total_cmp)
+        else {
+            return false;
+        };
+
+        let mut quiet = 0.0;
+        for half_bit in PREAMBLE_QUIET {
+            let magnitude = self.get_half_bit(start, half_bit);
+            if magnitude >= weakest_pulse {
+                return false;
+            }
+            quiet += magnitude;
+        }
+
+        weakest_pulse > self.settings.preamble_threshold * quiet / PREAMBLE_QUIET.len() as f64
+    }
+
+    /// The first `bits` bits after the preamble, how clearly they were sent, and the signal level
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn slice_bits(&self, start: f64, bits: usize) -> (Vec<u8>, f64, u8) {
+        let mut frame = vec![0u8; bits.div_ceil(8)];
+        let mut confidence = 0.0;
+        let mut power = 0.0;
+
+        for bit in 0..bits {
+            let first_half = self.get_half_bit(start, PREAMBLE_HALF_BITS + bit * 2);
+            let second_half = self.get_half_bit(start, PREAMBLE_HALF_BITS + bit * 2 + 1);
+            if first_half > second_half {
+                frame[bit / 8] |= 0x80 >> (bit % 8);
+            }
+            confidence += (first_half - second_half).abs();
+            power += first_half.max(second_half).powi(2);
+        }
+
+        let signal_level = ((power / bits as f64).sqrt() * 255.0).round().min(255.0) as u8;
+
+        (frame, confidence, signal_level)
+    }
+
+    /// Mean magnitude over the `half_bit`th half bit after `start`, counted in samples
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn get_half_bit(&self, start: f64, half_bit: usize) -> f64 {
+        let width = self.samples_per_microsecond / 2.0;
+        let from = start + half_bit as f64 * width;
+        let to = from + width;
+
+        let mut sum = 0.0;
+        let mut sample = from.floor();
+        while sample < to {
+            let overlap = (sample + 1.0).min(to) - sample.max(from);
+            sum += f64::from(
+                self.magnitudes
+                    .get(sample as usize)
+                    .copied()
+                    .unwrap_or_default(),
+            ) * overlap;
+            sample += 1.0;
+        }
+
+        sum / width
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn get_message(&self, candidate: &Candidate) -> MessageResult<AdsbBeastMessage> {
+        let ticks_per_sample = self.settings.sample_rate.get_mlat_ticks_per_sample();
+        let mlat_timestamp = ((self.first_sample + candidate.sample as u64) * ticks_per_sample
+            + (candidate.phase * ticks_per_sample as f64).round() as u64)
+            & MAX_MLAT_TIMESTAMP;
+
+        let mut beast = Vec::with_capacity(8 + candidate.frame.len());
+        beast.push(if candidate.frame.len() * 8 == MODES_SHORT_MSG_BITS {
+            BEAST_SHORT_FRAME_CHARACTER
+        } else {
+            BEAST_LONG_FRAME_CHARACTER
+        });
+        beast.extend_from_slice(&mlat_timestamp.to_be_bytes()[2..]);
+        beast.push(candidate.signal_level);
+        beast.extend_from_slice(&candidate.frame);
+
+        Ok(AdsbBeastMessage::from_bytes(&beast)?)
+    }
+}
+
+fn compare_candidates(a: &Candidate, b: &Candidate) -> Ordering {
+    a.rank
+        .cmp(&b.rank)
+        .then(a.confidence.total_cmp(&b.confidence))
+}
+
+/// Demodulate a whole capture read from `reader`.
+/// # Errors
+/// If reading fails, the error is returned.
+pub fn demodulate_iq<R: Read>(
+    mut reader: R,
+    settings: DemodulatorSettings,
+) -> MessageResult<Vec<AdsbBeastMessage>> {
+    let mut demodulator = ModeSDemodulator::new(settings);
+    let mut buffer = vec![0u8; IQ_READ_BUFFER_BYTES];
+    let mut messages = Vec::new();
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => messages.extend(demodulator.push(&buffer[..read])),
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    messages.extend(demodulator.finish());
+
+    Ok(messages)
+}
+
+/// Demodulate a capture file, like one written by `rtl_sdr`.
+/// # Errors
+/// If the file can't be opened or read, the error is returned.
+pub fn demodulate_iq_file<P: AsRef<Path>>(
+    path: P,
+    settings: DemodulatorSettings,
+) -> MessageResult<Vec<AdsbBeastMessage>> {
+    demodulate_iq(File::open(path)?, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::df::DF;
+    use crate::helpers::encode_adsb_beast_input::format_adsb_beast_frames_from_bytes;
+    use sdre_rust_logging::SetupLogging;
+
+    const CAPTURE_MICROSECONDS: f64 = 1600.0;
+
+    /// A frame sent in a synthetic capture, starting `start` µs in to it
+    struct Transmission {
+        start: f64,
+        frame: Vec<u8>,
+        amplitude: f64,
+    }
+
+    /// DF4 altitude reply with `address` in the parity
+    fn get_reply(address: u32) -> Vec<u8> {
+        let mut frame = vec![0x20, 0x00, 0x18, 0x38, 0, 0, 0];
+        let parity = modes_checksum(&frame, MODES_SHORT_MSG_BITS).unwrap() ^ address;
+        frame[4..].copy_from_slice(&parity.to_be_bytes()[1..]);
+        frame
+    }
+
+    fn get_transmissions() -> Vec<Transmission> {
+        let position = hex::decode("8D40621D58C382D690C8AC2863A7").unwrap();
+        let mut damaged = position.clone();
+        damaged[6] ^= 0x10;
+
+        vec![
+            Transmission {
+                start: 100.0,
+                frame: hex::decode("8D4840D6202CC371C32CE0576098").unwrap(),
+                amplitude: 0.5,
+            },
+            Transmission {
+                start: 400.3,
+                frame: get_reply(0x0048_40D6),
+                amplitude: 0.3,
+            },
+            // nothing confirmed this address
+            Transmission {
+                start: 700.6,
+                frame: get_reply(0x00AB_CDEF),
+                amplitude: 0.3,
+            },
+            // one bit flipped, only kept with error correction
+            Transmission {
+                start: 1000.0,
+                frame: damaged,
+                amplitude: 0.4,
+            },
+            Transmission {
+                start: 1300.9,
+                frame: position,
+                amplitude: 0.1,
+            },
+        ]
+    }
+
+    /// Modulate the transmissions on to a slowly rotating carrier with some noise, as an SDR would record them
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn get_capture(transmissions: &[Transmission], settings: &DemodulatorSettings) -> Vec<u8> {
+        let samples_per_microsecond = settings.sample_rate.get_samples_per_microsecond();
+        let mut amplitudes = vec![0.0; (CAPTURE_MICROSECONDS * samples_per_microsecond) as usize];
+
+        for transmission in transmissions {
+            let mut pulses = vec![0.0, 1.0, 3.5, 4.5];
+            for bit in 0..transmission.frame.len() * 8 {
+                let one = transmission.frame[bit / 8] & (0x80 >> (bit % 8)) != 0;
+                pulses.push(PREAMBLE_MICROSECONDS + bit as f64 + if one { 0.0 } else { 0.5 });
+            }
+            for pulse in pulses {
+                let from = (transmission.start + pulse) * samples_per_microsecond;
+                let to = from + samples_per_microsecond / 2.0;
+                let mut sample = from.floor();
+                while sample < to {
+                    amplitudes[sample as usize] +=
+                        transmission.amplitude * ((sample + 1.0).min(to) - sample.max(from));
+                    sample += 1.0;
+                }
+            }
+        }
+
+        let mut seed: u32 = 0x1234_5678;
+        let mut noise = || {
+            (0..4)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    f64::from(seed >> 8) / f64::from(1 << 24) - 0.5
+                })
+                .sum::<f64>()
+                * 0.02
+        };
+
+        let mut capture = Vec::new();
+        for (index, amplitude) in amplitudes.iter().enumerate() {
+            let carrier = index as f64 * 0.13;
+            for value in [
+                amplitude * carrier.cos() + noise(),
+                amplitude * carrier.sin() + noise(),
+            ] {
+                match settings.sample_format {
+                    IQSampleFormat::Cu8 => {
+                        capture.push((127.5 + 127.5 * value).round().clamp(0.0, 255.0) as u8);
+                    }
+                    IQSampleFormat::Cs16 => {
+                        capture
+                            .extend_from_slice(&((value * 32767.0).round() as i16).to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        capture
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn check_messages(messages: &[AdsbBeastMessage], expected: &[&Transmission]) {
+        assert_eq!(messages.len(), expected.len());
+        for (message, transmission) in messages.iter().zip(expected) {
+            // the damaged frame comes out repaired
+            let mut frame = transmission.frame.clone();
+            let _ = correct_errors(&mut frame, ErrorCorrection::SingleBit);
+            assert_eq!(
+                message.raw_message,
+                hex::encode(&frame).to_adsb_raw().unwrap()
+            );
+
+            let timestamp = (transmission.start * 12.0).round() as u64;
+            assert!(message.get_mlat_timestamp().abs_diff(timestamp) <= 3);
+
+            // a pulse split over two samples loses some of its peak
+            let signal_level =
+                f64::from(message.get_signal_level()) / (transmission.amplitude * 255.0);
+            assert!((0.65..=1.05).contains(&signal_level));
+        }
+    }
+
+    #[test]
+    fn demodulate_synthetic_captures() {
+        "debug".enable_logging();
+
+        let transmissions = get_transmissions();
+        for sample_format in [IQSampleFormat::Cu8, IQSampleFormat::Cs16] {
+            for sample_rate in [SampleRate::TwoMHz, SampleRate::TwoPointFourMHz] {
+                let mut settings = DemodulatorSettings {
+                    sample_format,
+                    sample_rate,
+                    ..Default::default()
+                };
+                let capture = get_capture(&transmissions, &settings);
+
+                let messages = demodulate_iq(capture.as_slice(), settings).unwrap();
+                check_messages(
+                    &messages,
+                    &[&transmissions[0], &transmissions[1], &transmissions[4]],
+                );
+
+                settings.error_correction = ErrorCorrection::SingleBit;
+                let messages = demodulate_iq(capture.as_slice(), settings).unwrap();
+                check_messages(
+                    &messages,
+                    &[
+                        &transmissions[0],
+                        &transmissions[1],
+                        &transmissions[3],
+                        &transmissions[4],
+                    ],
+                );
+
+                // chunks that split samples and frames find the same
+                let mut demodulator = ModeSDemodulator::new(settings);
+                let mut chunked = Vec::new();
+                for chunk in capture.chunks(997) {
+                    chunked.extend(demodulator.push(chunk));
+                }
+                chunked.extend(demodulator.finish());
+                assert_eq!(chunked, messages);
+                assert_eq!(
+                    demodulator.get_samples_processed(),
+                    (capture.len() / sample_format.bytes_per_sample()) as u64
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn demodulate_expires_addresses_by_capture_time() {
+        "debug".enable_logging();
+
+        let settings = DemodulatorSettings::default();
+        let transmissions = get_transmissions();
+        let squitter = get_capture(&transmissions[..1], &settings);
+        let reply = get_capture(&transmissions[1..2], &settings);
+        let samples_per_second = u64::from(settings.sample_rate.get_hz());
+
+        for (seconds_between, expected) in [(30, 1), (61, 0)] {
+            let mut demodulator = ModeSDemodulator::new(settings);
+            let mut messages = demodulator.push(&squitter);
+            messages.extend(demodulator.finish());
+            assert_eq!(messages.len(), 1);
+
+            // skip the quiet part of the capture, reading it would take a while
+            demodulator.first_sample += seconds_between * samples_per_second;
+            let mut messages = demodulator.push(&reply);
+            messages.extend(demodulator.finish());
+            assert_eq!(messages.len(), expected);
+        }
+    }
+
+    #[test]
+    fn demodulate_capture_file() {
+        "debug".enable_logging();
+
+        let settings = DemodulatorSettings::default();
+        let capture = get_capture(&get_transmissions(), &settings);
+        let path =
+            std::env::temp_dir().join(format!("sdre-demodulator-{}.cu8", std::process::id()));
+        std::fs::write(&path, &capture).unwrap();
+        let messages = demodulate_iq_file(&path, settings);
+        std::fs::remove_file(&path).unwrap();
+        let messages = messages.unwrap();
+        assert_eq!(messages.len(), 3);
+
+        // the frames, the DF4 reply too, go back out as Beast and decode the same
+        assert!(
+            messages.iter().any(|message| matches!(
+                message.raw_message.df,
+                DF::SurveillanceAltitudeReply { .. }
+            ))
+        );
+        for message in messages {
+            let beast = message.to_beast_bytes().unwrap();
+            let frames = format_adsb_beast_frames_from_bytes(&beast);
+            let decoded = AdsbBeastMessage::from_bytes(&frames.frames[0]).unwrap();
+            assert_eq!(decoded, message);
+            assert_eq!(decoded.to_beast_bytes().unwrap(), beast);
+        }
+
+        assert!(demodulate_iq_file("/nonexistent/capture.cu8", settings).is_err());
+    }
+}
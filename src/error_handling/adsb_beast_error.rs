@@ -10,6 +10,11 @@ custom_error! {pub ADSBBeastError
     ShortFrameTooShort{message: usize}                      = "Found a short frame but not enough bytes ({message}) to decode it",
     LongFrameTooShort {message: usize}                      = "Found a long frame but not enough bytes  ({message}) to decode it",
     ModeACFrameTooShort {message: usize}                    = "Found a Mode A/C frame but not enough bytes  ({message}) to decode it",
+    StatusFrameTooShort {message: usize}                    = "Found a status frame but not enough bytes ({message}) to decode it",
+    ReceiverIdTooShort {message: usize}                     = "Found a receiver ID but not enough bytes ({message}) to decode it",
     StartSequenceError {message: String}                    = "Found a start character ({message}) that wasn't a start sequence",
     FrameTypeNone                                           = "We should be working on a frame but the frame type is None",
+    FrameLengthMismatch {message_type: String, message: usize} = "A {message_type} frame can't be {message} bytes long",
+    TimestampOutOfRange {timestamp: u64}                    = "MLAT timestamp {timestamp} doesn't fit in 48 bits",
+    MissingFrame                                            = "The message wasn't decoded from Beast and isn't an extended squitter, there is no frame to encode",
 }
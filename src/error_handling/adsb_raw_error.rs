@@ -9,6 +9,8 @@ use custom_error::custom_error;
 custom_error! {pub ADSBRawError
     ByteSequenceWrong{size: usize}             = "Not enough bytes in the sequence to parse the message. ADSB Raw messages should be 14 or 28 bytes long. Found {size} bytes.",
     HexEncodingError{message: String}       = "Error converting the in input byte sequence to hex: {message}",
+    CorruptFrame{syndrome: String}          = "The frame failed the CRC check, syndrome {syndrome}",
+    UnsupportedDownlinkFormat{df: String}   = "Only DF17 and DF18 extended squitters can be encoded, not DF{df}",
 }
 
 custom_error! {pub WrongType
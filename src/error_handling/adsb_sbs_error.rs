@@ -0,0 +1,14 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use custom_error::custom_error;
+
+custom_error! {pub ADSBSBSError
+    NotAnSBSLine{line: String}                  = "Not an SBS line: {line}",
+    UnsupportedMessageType{message_type: String} = "SBS {message_type} lines are not decoded, only MSG lines carry aircraft data",
+    MissingAddress                              = "The SBS line has no aircraft address",
+    InvalidField{field: String, value: String}  = "Invalid SBS {field}: {value}",
+}
@@ -0,0 +1,13 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use custom_error::custom_error;
+
+custom_error! {pub ADSBStreamError
+    FramingError{format: String, bytes_dropped: u64}        = "Dropped {bytes_dropped} bytes of {format} input that were not part of a frame",
+    UndetectedFormat{bytes: usize}                          = "Could not detect the input format from the first {bytes} bytes",
+    UnsupportedFormat{format: String}                       = "Decoding {format} input is not supported",
+}
@@ -13,6 +13,8 @@ use std::error::Error;
 
 use super::adsb_beast_error::ADSBBeastError;
 use super::adsb_raw_error::ADSBRawError;
+use super::adsb_sbs_error::ADSBSBSError;
+use super::adsb_stream_error::ADSBStreamError;
 
 custom_error! {pub WrongType
     WrongTypeForAircraft{message: String} = "Wrong type: {message}",
@@ -25,6 +27,8 @@ pub enum DeserializationError {
     HexError(FromHexError),
     ADSBRawError(ADSBRawError),
     ADSBBeastError(ADSBBeastError),
+    ADSBSBSError(ADSBSBSError),
+    ADSBStreamError(ADSBStreamError),
     StardardError(Box<dyn Error + Send + Sync>),
     WrongType(WrongType),
     CombinedError(Vec<DeserializationError>),
@@ -38,6 +42,8 @@ impl std::fmt::Display for DeserializationError {
             DeserializationError::HexError(e) => write!(f, "Hex error: {e}"),
             DeserializationError::ADSBRawError(e) => write!(f, "ADSB Raw error: {e}"),
             DeserializationError::ADSBBeastError(e) => write!(f, "ADSB Beast error: {e}"),
+            DeserializationError::ADSBSBSError(e) => write!(f, "ADSB SBS error: {e}"),
+            DeserializationError::ADSBStreamError(e) => write!(f, "ADSB Stream error: {e}"),
             DeserializationError::StardardError(e) => write!(f, "Standard error: {e}"),
             DeserializationError::WrongType(e) => write!(f, "Wrong type error: {e}"),
             DeserializationError::CombinedError(e) => {
@@ -91,3 +97,21 @@ impl From<ADSBBeastError> for DeserializationError {
         DeserializationError::ADSBBeastError(value)
     }
 }
+
+impl From<ADSBSBSError> for DeserializationError {
+    fn from(value: ADSBSBSError) -> Self {
+        DeserializationError::ADSBSBSError(value)
+    }
+}
+
+impl From<ADSBStreamError> for DeserializationError {
+    fn from(value: ADSBStreamError) -> Self {
+        DeserializationError::ADSBStreamError(value)
+    }
+}
+
+impl From<std::io::Error> for DeserializationError {
+    fn from(value: std::io::Error) -> Self {
+        DeserializationError::StardardError(Box::new(value))
+    }
+}
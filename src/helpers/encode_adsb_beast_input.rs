@@ -5,14 +5,21 @@
 // https://opensource.org/licenses/MIT.
 
 use crate::error_handling::adsb_beast_error::ADSBBeastError;
+use bytes::{Buf, Bytes, BytesMut};
+
+use super::framer::{ADSBFramer, FrameFormat, FrameScan};
 
 const ADSB_BEAST_START_CHARACTER: u8 = 0x1a; // The adsb beast end character sequence is is a '0x3b0a', start is '0x2a'
 const ADSB_BEAST_LONG_FRAME_START_CHARACTER: u8 = 0x33;
 const ADSB_BEAST_SHORT_FRAME_START_CHARACTER: u8 = 0x32;
 const ADSB_BEAST_MODEAC_FRAME_START_CHARACTER: u8 = 0x31;
+const ADSB_BEAST_STATUS_FRAME_START_CHARACTER: u8 = 0x34;
+const ADSB_BEAST_RECEIVER_ID_START_CHARACTER: u8 = 0xe3;
 const ADSB_BEAST_SHORT_FRAME_LENGTH: usize = 15;
 const ADSB_BEAST_LONG_FRAME_LENGTH: usize = 22;
 const ADSB_BEAST_MODEAC_FRAME_LENGTH: usize = 10;
+const ADSB_BEAST_STATUS_FRAME_LENGTH: usize = 22;
+const ADSB_BEAST_RECEIVER_ID_LENGTH: usize = 9;
 
 pub struct ADSBBeastFrames {
     pub frames: Vec<Vec<u8>>,
@@ -31,18 +38,165 @@ impl ADSBBeastFrames {
     }
 }
 
+/// Beast frames for `ADSBFramer`.
+///
+/// Frames are handed out unescaped and without the leading `0x1a`, in the same layout as
+/// `format_adsb_beast_frames_from_bytes`.
+#[derive(Debug, Default)]
+pub struct ADSBBeastFraming;
+
+/// Streaming Beast framer, see `ADSBBeastFraming`
+pub type ADSBBeastFramer = ADSBFramer<ADSBBeastFraming>;
+
+enum BeastScan {
+    /// more bytes are needed
+    Incomplete,
+    /// the bytes up to here can't be the start of a frame
+    Invalid(usize),
+    Complete {
+        end: usize,
+        frame_type: u8,
+    },
+}
+
+impl FrameFormat for ADSBBeastFraming {
+    fn scan(buffer: &mut BytesMut, _lost_sync: bool) -> FrameScan {
+        match buffer
+            .iter()
+            .position(|byte| *byte == ADSB_BEAST_START_CHARACTER)
+        {
+            Some(0) => (),
+            Some(start) => return FrameScan::Drop(start),
+            None => return FrameScan::Drop(buffer.len()),
+        }
+
+        let end = match scan_frame(buffer, 0) {
+            BeastScan::Incomplete => return FrameScan::Incomplete,
+            BeastScan::Invalid(end) => return FrameScan::Drop(end),
+            BeastScan::Complete {
+                end,
+                frame_type: ADSB_BEAST_RECEIVER_ID_START_CHARACTER,
+            } => match scan_frame(buffer, end) {
+                BeastScan::Incomplete => return FrameScan::Incomplete,
+                BeastScan::Invalid(_) => return FrameScan::Drop(end),
+                BeastScan::Complete {
+                    end: frame_end,
+                    frame_type:
+                        ADSB_BEAST_SHORT_FRAME_START_CHARACTER
+                        | ADSB_BEAST_LONG_FRAME_START_CHARACTER
+                        | ADSB_BEAST_STATUS_FRAME_START_CHARACTER,
+                } => frame_end,
+                // a Mode A/C reply has no room for the receiver ID
+                BeastScan::Complete { .. } => return FrameScan::Skip(end),
+            },
+            BeastScan::Complete { end, .. } => end,
+        };
+
+        FrameScan::Frame(split_frame(buffer, end))
+    }
+}
+
+/// Walk the escaped frame whose `0x1a` is at `start`.
+fn scan_frame(buffer: &[u8], start: usize) -> BeastScan {
+    match buffer.get(start) {
+        None => return BeastScan::Incomplete,
+        Some(&ADSB_BEAST_START_CHARACTER) => (),
+        Some(_) => return BeastScan::Invalid(start),
+    }
+
+    let Some(&frame_type) = buffer.get(start + 1) else {
+        return BeastScan::Incomplete;
+    };
+
+    let frame_length = match frame_type {
+        ADSB_BEAST_MODEAC_FRAME_START_CHARACTER => ADSB_BEAST_MODEAC_FRAME_LENGTH,
+        ADSB_BEAST_SHORT_FRAME_START_CHARACTER => ADSB_BEAST_SHORT_FRAME_LENGTH,
+        ADSB_BEAST_LONG_FRAME_START_CHARACTER => ADSB_BEAST_LONG_FRAME_LENGTH,
+        ADSB_BEAST_STATUS_FRAME_START_CHARACTER => ADSB_BEAST_STATUS_FRAME_LENGTH,
+        ADSB_BEAST_RECEIVER_ID_START_CHARACTER => ADSB_BEAST_RECEIVER_ID_LENGTH,
+        _ => return BeastScan::Invalid(start + 1),
+    };
+
+    // the frame type is part of the frame length
+    let mut position = start + 2;
+    for _ in 1..frame_length {
+        match buffer.get(position) {
+            None => return BeastScan::Incomplete,
+            Some(&ADSB_BEAST_START_CHARACTER) => match buffer.get(position + 1) {
+                None => return BeastScan::Incomplete,
+                Some(&ADSB_BEAST_START_CHARACTER) => position += 2,
+                // the next frame started before this one was complete
+                Some(_) => return BeastScan::Invalid(position),
+            },
+            Some(_) => position += 1,
+        }
+    }
+
+    BeastScan::Complete {
+        end: position,
+        frame_type,
+    }
+}
+
+/// Unescape the frame in place and split it off the buffer.
+fn split_frame(buffer: &mut BytesMut, end: usize) -> Bytes {
+    // every 0x1a is either in front of a frame type or the first half of an escaped 0x1a,
+    // drop it and keep the byte after it
+    let mut write = 0;
+    let mut read = 0;
+    while read < end {
+        if buffer[read] == ADSB_BEAST_START_CHARACTER {
+            read += 1;
+        }
+        buffer[write] = buffer[read];
+        write += 1;
+        read += 1;
+    }
+
+    let frame = buffer.split_to(write).freeze();
+    buffer.advance(end - write);
+    frame
+}
+
+/// End of the escaped frame that starts with the `0x1a` at `start`, if there is a complete one.
+pub(crate) fn complete_beast_frame_end(buffer: &[u8], start: usize) -> Option<usize> {
+    match scan_frame(buffer, start) {
+        BeastScan::Complete { end, .. } => Some(end),
+        BeastScan::Incomplete | BeastScan::Invalid(_) => None,
+    }
+}
+
 #[derive(Debug, PartialEq, PartialOrd)]
 enum FrameType {
     Short,
     Long,
     ModeAC,
+    Status,
+    ReceiverId,
     None,
 }
 
+/// Hand over a complete frame, prefixed with the receiver ID that was sent in front of it.
+fn push_frame(
+    formatted_frames: &mut Vec<Vec<u8>>,
+    receiver_id: &mut Option<Vec<u8>>,
+    frame_bytes: &[u8],
+) {
+    match receiver_id.take() {
+        Some(mut frame) => {
+            frame.extend_from_slice(frame_bytes);
+            formatted_frames.push(frame);
+        }
+        None => formatted_frames.push(frame_bytes.to_vec()),
+    }
+}
+
 /// Helper function to format ADSB Beast frames from bytes.
 /// Expected input is a &Vec<Vec<u8>>of the beast frame(s), including the control characters to start and end the frame.
 /// Does not consume the input.
 /// Returns a vector of bytes, with each element of the array being a frame that can be passed in to the ADSB Beast parser.
+///
+/// A `0x1a 0xe3` receiver ID is not a frame of its own, the 9 bytes starting at `0xe3` are put in front of the frame that follows it.
 #[must_use]
 // FIXME: Can/should this be refactored in to less lines?
 #[allow(clippy::too_many_lines)]
@@ -52,6 +206,7 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
     let mut frame_type: FrameType = FrameType::None;
     let mut frame_bytes: Vec<u8> = Vec::new();
     let mut errors: Vec<ADSBBeastError> = Vec::new();
+    let mut receiver_id: Option<Vec<u8>> = None;
 
     // https://github.com/junzis/pyModeS/blob/77273153cba6c2f282f672ea4078a62efcf716d7/pyModeS/extra/tcpclient.py#L65
     // example logic for iterating over this buffer
@@ -76,7 +231,7 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
                 match frame_type {
                     FrameType::Short => {
                         if frame_bytes.len() == ADSB_BEAST_SHORT_FRAME_LENGTH {
-                            formatted_frames.push(frame_bytes.clone());
+                            push_frame(&mut formatted_frames, &mut receiver_id, &frame_bytes);
                             frame_bytes.clear();
                         } else if next_byte.is_some() {
                             errors.push(ADSBBeastError::ShortFrameTooShort {
@@ -87,7 +242,7 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
                     }
                     FrameType::Long => {
                         if frame_bytes.len() == ADSB_BEAST_LONG_FRAME_LENGTH {
-                            formatted_frames.push(frame_bytes.clone());
+                            push_frame(&mut formatted_frames, &mut receiver_id, &frame_bytes);
                             frame_bytes.clear();
                         } else if next_byte.is_some() {
                             errors.push(ADSBBeastError::LongFrameTooShort {
@@ -96,12 +251,36 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
                             frame_bytes.clear();
                         }
                     }
+                    FrameType::Status => {
+                        if frame_bytes.len() == ADSB_BEAST_STATUS_FRAME_LENGTH {
+                            push_frame(&mut formatted_frames, &mut receiver_id, &frame_bytes);
+                            frame_bytes.clear();
+                        } else if next_byte.is_some() {
+                            errors.push(ADSBBeastError::StatusFrameTooShort {
+                                message: frame_bytes.len(),
+                            });
+                            frame_bytes.clear();
+                        }
+                    }
+                    FrameType::ReceiverId => {
+                        if frame_bytes.len() == ADSB_BEAST_RECEIVER_ID_LENGTH {
+                            receiver_id = Some(frame_bytes.clone());
+                            frame_bytes.clear();
+                        } else if next_byte.is_some() {
+                            errors.push(ADSBBeastError::ReceiverIdTooShort {
+                                message: frame_bytes.len(),
+                            });
+                            frame_bytes.clear();
+                        }
+                    }
                     FrameType::None => {
                         frame_bytes.clear();
                     }
                     FrameType::ModeAC => {
                         if frame_bytes.len() == ADSB_BEAST_MODEAC_FRAME_LENGTH {
-                            // Ignore the modeac frame
+                            // a Mode A/C reply has no room for the receiver ID
+                            receiver_id = None;
+                            formatted_frames.push(frame_bytes.clone());
                             frame_bytes.clear();
                         } else if next_byte.is_some() {
                             errors.push(ADSBBeastError::ModeACFrameTooShort {
@@ -127,6 +306,14 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
                     frame_type = FrameType::ModeAC;
                     continue;
                 }
+                Some(&&ADSB_BEAST_STATUS_FRAME_START_CHARACTER) => {
+                    frame_type = FrameType::Status;
+                    continue;
+                }
+                Some(&&ADSB_BEAST_RECEIVER_ID_START_CHARACTER) => {
+                    frame_type = FrameType::ReceiverId;
+                    continue;
+                }
                 None => {
                     // we are at the end of the buffer, continue to exit
                     continue;
@@ -136,6 +323,7 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
                         message: format!("{byte:02X?}"),
                     });
                     frame_type = FrameType::None;
+                    receiver_id = None;
                     continue;
                 }
             }
@@ -175,20 +363,27 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
         match frame_type {
             FrameType::Short => {
                 if frame_bytes.len() == ADSB_BEAST_SHORT_FRAME_LENGTH {
-                    formatted_frames.push(frame_bytes.clone());
+                    push_frame(&mut formatted_frames, &mut receiver_id, &frame_bytes);
                     frame_bytes.clear();
                 }
             }
             FrameType::Long => {
                 if frame_bytes.len() == ADSB_BEAST_LONG_FRAME_LENGTH {
-                    formatted_frames.push(frame_bytes.clone());
+                    push_frame(&mut formatted_frames, &mut receiver_id, &frame_bytes);
                     frame_bytes.clear();
                 }
             }
-            FrameType::None => (),
+            FrameType::Status => {
+                if frame_bytes.len() == ADSB_BEAST_STATUS_FRAME_LENGTH {
+                    push_frame(&mut formatted_frames, &mut receiver_id, &frame_bytes);
+                    frame_bytes.clear();
+                }
+            }
+            FrameType::None | FrameType::ReceiverId => (),
             FrameType::ModeAC => {
                 if frame_bytes.len() == ADSB_BEAST_MODEAC_FRAME_LENGTH {
-                    // Ignore the modeac frame
+                    receiver_id = None;
+                    formatted_frames.push(frame_bytes.clone());
                     frame_bytes.clear();
                 }
             }
@@ -203,7 +398,8 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
         if let Some(
             &ADSB_BEAST_SHORT_FRAME_START_CHARACTER
             | &ADSB_BEAST_LONG_FRAME_START_CHARACTER
-            | &ADSB_BEAST_MODEAC_FRAME_START_CHARACTER,
+            | &ADSB_BEAST_MODEAC_FRAME_START_CHARACTER
+            | &ADSB_BEAST_STATUS_FRAME_START_CHARACTER,
         ) = frame_bytes.first()
         {
             frame_bytes.insert(0, ADSB_BEAST_START_CHARACTER);
@@ -234,6 +430,7 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
                     if **next_byte == ADSB_BEAST_MODEAC_FRAME_START_CHARACTER
                         || **next_byte == ADSB_BEAST_SHORT_FRAME_START_CHARACTER
                         || **next_byte == ADSB_BEAST_LONG_FRAME_START_CHARACTER
+                        || **next_byte == ADSB_BEAST_STATUS_FRAME_START_CHARACTER
                     {
                         new_frame_bytes.push(*byte);
                     } else {
@@ -250,6 +447,19 @@ pub fn format_adsb_beast_frames_from_bytes(bytes: &[u8]) -> ADSBBeastFrames {
         leftbytes = new_frame_bytes;
     }
 
+    // the receiver ID belongs to a frame we haven't seen all of yet, keep it with the leftover bytes
+    if let Some(receiver_id) = receiver_id {
+        let mut escaped_receiver_id: Vec<u8> = vec![ADSB_BEAST_START_CHARACTER];
+        for byte in receiver_id {
+            if byte == ADSB_BEAST_START_CHARACTER {
+                escaped_receiver_id.push(byte);
+            }
+            escaped_receiver_id.push(byte);
+        }
+        escaped_receiver_id.append(&mut leftbytes);
+        leftbytes = escaped_receiver_id;
+    }
+
     ADSBBeastFrames {
         frames: formatted_frames,
         left_over: leftbytes,
@@ -262,12 +472,15 @@ mod tests {
     use sdre_rust_logging::SetupLogging;
 
     use super::*;
+    use crate::decoders::{beast::AdsbBeastMessage, beaststatus::BeastStatusMessage};
+    use crate::{ADSBMessage, DecodeMessage};
+    use tokio_util::codec::Decoder;
 
     #[test]
     fn test_adsb_beast_parsing_input() {
         "debug".enable_logging();
 
-        // there are 33 frames in this input. 5 of the are MODEAC frames, which we don't want to decode
+        // there are 33 frames in this input. 5 of them are MODEAC frames
         let raw_frames = [
             0x1a_u8, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1a, 0x31, 0x0, 0x0, 0x0,
             0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1a, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
@@ -329,8 +542,8 @@ mod tests {
             info!("Frame: {frame:02X?}");
         }
         assert!(
-            frames.frames.len() == 38,
-            "Expected 38 frames, got {}",
+            frames.frames.len() == 43,
+            "Expected 43 frames, got {}",
             frames.frames.len()
         );
 
@@ -338,7 +551,8 @@ mod tests {
         for frame in &frames.frames {
             assert!(
                 frame.len() == ADSB_BEAST_SHORT_FRAME_LENGTH
-                    || frame.len() == ADSB_BEAST_LONG_FRAME_LENGTH,
+                    || frame.len() == ADSB_BEAST_LONG_FRAME_LENGTH
+                    || frame.len() == ADSB_BEAST_MODEAC_FRAME_LENGTH,
                 "Frame is not the correct length: {}",
                 frame.len()
             );
@@ -356,7 +570,7 @@ mod tests {
     fn test_extra_bytes_in_input() {
         "debug".enable_logging();
 
-        // there are 33 frames in this input. 5 of the are MODEAC frames, which we don't want to decode
+        // there are 33 frames in this input. 5 of them are MODEAC frames
         let raw_frames = [
             0x1a_u8, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1a, 0x31, 0x0, 0x0, 0x0,
             0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1a, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
@@ -418,8 +632,8 @@ mod tests {
             info!("Frame: {frame:02X?}");
         }
         assert!(
-            frames.frames.len() == 38,
-            "Expected 38 frames, got {}",
+            frames.frames.len() == 43,
+            "Expected 43 frames, got {}",
             frames.frames.len()
         );
 
@@ -427,7 +641,8 @@ mod tests {
         for frame in &frames.frames {
             assert!(
                 frame.len() == ADSB_BEAST_SHORT_FRAME_LENGTH
-                    || frame.len() == ADSB_BEAST_LONG_FRAME_LENGTH,
+                    || frame.len() == ADSB_BEAST_LONG_FRAME_LENGTH
+                    || frame.len() == ADSB_BEAST_MODEAC_FRAME_LENGTH,
                 "Frame is not the correct length: {}",
                 frame.len()
             );
@@ -445,7 +660,7 @@ mod tests {
     fn test_extra_bytes_at_end_input() {
         "debug".enable_logging();
 
-        // there are 33 frames in this input. 5 of the are MODEAC frames, which we don't want to decode
+        // there are 33 frames in this input. 5 of them are MODEAC frames
         let raw_frames = [
             0x1a_u8, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1a, 0x31, 0x0, 0x0, 0x0,
             0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1a, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
@@ -508,8 +723,8 @@ mod tests {
             info!("Frame: {frame:02X?}");
         }
         assert!(
-            frames.frames.len() == 38,
-            "Expected 38 frames, got {}",
+            frames.frames.len() == 43,
+            "Expected 43 frames, got {}",
             frames.frames.len()
         );
 
@@ -517,7 +732,8 @@ mod tests {
         for frame in &frames.frames {
             assert!(
                 frame.len() == ADSB_BEAST_SHORT_FRAME_LENGTH
-                    || frame.len() == ADSB_BEAST_LONG_FRAME_LENGTH,
+                    || frame.len() == ADSB_BEAST_LONG_FRAME_LENGTH
+                    || frame.len() == ADSB_BEAST_MODEAC_FRAME_LENGTH,
                 "Frame is not the correct length: {}",
                 frame.len()
             );
@@ -662,4 +878,111 @@ mod tests {
             frames.left_over.len(),
         );
     }
+
+    #[test]
+    fn test_status_and_receiver_id() {
+        "debug".enable_logging();
+
+        let long_frame = [
+            0x33_u8, 0x01, 0xB5, 0xBF, 0x52, 0x0C, 0x77, 0x1D, 0x8D, 0xAB, 0x97, 0x40, 0x59, 0x2F,
+            0x16, 0x98, 0xB5, 0x94, 0x6E, 0xDE, 0x10, 0x8E,
+        ];
+        let status_frame = [
+            0x34_u8, 0x0, 0x3e, 0x95, 0x68, 0x8c, 0x89, 0x0, 0x85, 0xfe, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        ];
+
+        // receiver ID 0011221A44556677, the 0x1a is escaped
+        let mut raw_frames = vec![
+            0x1a_u8, 0xe3, 0x00, 0x11, 0x22, 0x1a, 0x1a, 0x44, 0x55, 0x66, 0x77, 0x1a,
+        ];
+        raw_frames.extend_from_slice(&long_frame);
+        raw_frames.push(0x1a);
+        raw_frames.extend_from_slice(&status_frame);
+
+        let frames = format_adsb_beast_frames_from_bytes(&raw_frames);
+        assert!(frames.errors.is_empty(), "Unexpected errors");
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames.frames[0][..9],
+            [0xe3, 0x00, 0x11, 0x22, 0x1a, 0x44, 0x55, 0x66, 0x77]
+        );
+        assert_eq!(frames.frames[0][9..], long_frame);
+        assert_eq!(frames.frames[1], status_frame);
+
+        let message = AdsbBeastMessage::from_bytes(&frames.frames[0]).unwrap();
+        assert_eq!(message.receiver_id, Some(0x0011_221A_4455_6677));
+
+        let status = BeastStatusMessage::from_bytes(&frames.frames[1]).unwrap();
+        assert_eq!(status.receiver_id, None);
+        assert_eq!(status.timestamp_error, -2);
+        assert!(status.dip_switches.binary_format);
+        assert!(status.dip_switches.mlat);
+        assert!(status.dip_switches.mode_ac);
+        assert!(!status.dip_switches.crc_disabled);
+        assert!(matches!(
+            frames.frames[1].decode_message(),
+            Ok(ADSBMessage::BeastStatusMessage(_))
+        ));
+
+        // a receiver ID whose frame is cut off stays with the leftover bytes
+        let frames = format_adsb_beast_frames_from_bytes(&raw_frames[..20]);
+        assert!(frames.is_empty());
+        assert_eq!(frames.left_over, raw_frames[..20]);
+    }
+
+    #[test]
+    fn test_beast_framer() {
+        "debug".enable_logging();
+
+        let short_frame = [
+            0x32_u8, 0x01, 0xB5, 0xBF, 0x53, 0x9F, 0x89, 0x6D, 0x5D, 0xA4, 0x4C, 0xFD, 0x32, 0x04,
+            0x1A,
+        ];
+        let long_frame = [
+            0x33_u8, 0x01, 0xB5, 0xBF, 0x52, 0x0C, 0x77, 0x1D, 0x8D, 0xAB, 0x97, 0x40, 0x59, 0x2F,
+            0x16, 0x98, 0xB5, 0x94, 0x6E, 0xDE, 0x10, 0x8E,
+        ];
+
+        // joining the stream half way through a frame, then an escaped 0x1a at the end of the short frame
+        let mut stream = vec![0x5D_u8, 0xA4, 0x1a];
+        stream.extend_from_slice(&long_frame);
+        stream.extend_from_slice(&[
+            0x1a, 0x32, 0x01, 0xB5, 0xBF, 0x53, 0x9F, 0x89, 0x6D, 0x5D, 0xA4, 0x4C, 0xFD, 0x32,
+            0x04, 0x1A, 0x1A, 0x1a, 0xe3, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x1a,
+        ]);
+        stream.extend_from_slice(&long_frame);
+
+        let mut framer = ADSBBeastFramer::new();
+        let mut output: Vec<Bytes> = Vec::new();
+        for chunk in stream.chunks(5) {
+            framer.push(chunk);
+            while let Some(frame) = framer.next_frame() {
+                output.push(frame);
+            }
+        }
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0], long_frame[..]);
+        assert_eq!(output[1], short_frame[..]);
+        assert_eq!(
+            output[2][..9],
+            [0xe3, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77]
+        );
+        assert_eq!(output[2][9..], long_frame);
+        assert_eq!(framer.bytes_dropped(), 2);
+        assert_eq!(framer.resyncs(), 1);
+
+        // a frame cut short by the next one is dropped
+        let mut buffer = BytesMut::from(&[0x1a_u8, 0x33, 0x01, 0x02, 0x1a][..]);
+        buffer.extend_from_slice(&short_frame[..14]);
+        buffer.extend_from_slice(&[0x1a, 0x1a]);
+        let mut framer = ADSBBeastFramer::new();
+        assert_eq!(
+            framer.decode(&mut buffer).unwrap().unwrap(),
+            short_frame[..]
+        );
+        assert!(buffer.is_empty());
+        assert_eq!(framer.bytes_dropped(), 4);
+    }
 }
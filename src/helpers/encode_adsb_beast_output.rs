@@ -0,0 +1,194 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// Beast output, the reverse of `encode_adsb_beast_input`. Every frame starts with a 0x1a, a 0x1a
+// anywhere after that is sent twice so it can't be mistaken for the start of the next frame.
+
+use crate::decoders::beast_types::messagetype::MessageType;
+use crate::error_handling::adsb_beast_error::ADSBBeastError;
+
+const ADSB_BEAST_START_CHARACTER: u8 = 0x1a;
+const ADSB_BEAST_LONG_FRAME_START_CHARACTER: u8 = 0x33;
+const ADSB_BEAST_SHORT_FRAME_START_CHARACTER: u8 = 0x32;
+const ADSB_BEAST_MODEAC_FRAME_START_CHARACTER: u8 = 0x31;
+const ADSB_BEAST_RECEIVER_ID_START_CHARACTER: u8 = 0xe3;
+const ADSB_BEAST_RECEIVER_ID_LENGTH: usize = 9;
+const ADSB_BEAST_MODEAC_FRAME_BYTES: usize = 2;
+const ADSB_BEAST_SHORT_FRAME_BYTES: usize = 7;
+const ADSB_BEAST_LONG_FRAME_BYTES: usize = 14;
+/// The MLAT timestamp is a 48 bit counter
+const ADSB_BEAST_MAX_MLAT_TIMESTAMP: u64 = (1 << 48) - 1;
+
+/// Encode a Mode A/C code or a Mode S frame as Beast wire bytes, starting with the `0x1a` and escaped.
+///
+/// `frame` is the 2 byte Mode A/C code or the 7 or 14 byte Mode S frame, and has to match `message_type`.
+/// # Errors
+/// If the frame length doesn't match the type or the timestamp doesn't fit in 48 bits, the error is returned.
+pub fn encode_beast_frame(
+    message_type: &MessageType,
+    mlat_timestamp: u64,
+    signal_level: u8,
+    frame: &[u8],
+) -> Result<Vec<u8>, ADSBBeastError> {
+    let (type_character, frame_length) = match message_type {
+        MessageType::ModeAC => (
+            ADSB_BEAST_MODEAC_FRAME_START_CHARACTER,
+            ADSB_BEAST_MODEAC_FRAME_BYTES,
+        ),
+        MessageType::ShortFrame => (
+            ADSB_BEAST_SHORT_FRAME_START_CHARACTER,
+            ADSB_BEAST_SHORT_FRAME_BYTES,
+        ),
+        MessageType::LongFrame => (
+            ADSB_BEAST_LONG_FRAME_START_CHARACTER,
+            ADSB_BEAST_LONG_FRAME_BYTES,
+        ),
+    };
+
+    if frame.len() != frame_length {
+        return Err(ADSBBeastError::FrameLengthMismatch {
+            message_type: message_type.to_string(),
+            message: frame.len(),
+        });
+    }
+
+    if mlat_timestamp > ADSB_BEAST_MAX_MLAT_TIMESTAMP {
+        return Err(ADSBBeastError::TimestampOutOfRange {
+            timestamp: mlat_timestamp,
+        });
+    }
+
+    let mut unescaped = Vec::with_capacity(8 + frame_length);
+    unescaped.push(type_character);
+    unescaped.extend_from_slice(&mlat_timestamp.to_be_bytes()[2..]);
+    unescaped.push(signal_level);
+    unescaped.extend_from_slice(frame);
+
+    Ok(escape_beast_frame(&unescaped))
+}
+
+/// Escape a frame in the layout the framers hand out: unescaped, without the leading `0x1a`, and with
+/// the 9 byte `0xe3` receiver ID in front if it has one. The receiver ID is sent as a frame of its own.
+#[must_use]
+pub fn escape_beast_frame(frame: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(frame.len() * 2 + 2);
+
+    let frame = match frame.split_at_checked(ADSB_BEAST_RECEIVER_ID_LENGTH) {
+        Some((receiver_id, frame))
+            if receiver_id[0] == ADSB_BEAST_RECEIVER_ID_START_CHARACTER && !frame.is_empty() =>
+        {
+            push_escaped(&mut escaped, receiver_id);
+            frame
+        }
+        _ => frame,
+    };
+    push_escaped(&mut escaped, frame);
+
+    escaped
+}
+
+fn push_escaped(escaped: &mut Vec<u8>, frame: &[u8]) {
+    escaped.push(ADSB_BEAST_START_CHARACTER);
+    for byte in frame {
+        if *byte == ADSB_BEAST_START_CHARACTER {
+            escaped.push(ADSB_BEAST_START_CHARACTER);
+        }
+        escaped.push(*byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::beast::AdsbBeastMessage;
+    use crate::decoders::modeac::ModeACMessage;
+    use crate::decoders::raw::AdsbRawMessage;
+    use crate::helpers::encode_adsb_beast_input::{
+        ADSBBeastFramer, format_adsb_beast_frames_from_bytes,
+    };
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn encode_beast_frames() {
+        "debug".enable_logging();
+
+        // 0x1a in the timestamp, the signal level, the frame and the Mode A/C code
+        let long_frame = hex::decode("8DAB9740592F1698B5946EDE108E").unwrap();
+        let short_frame = hex::decode("5DA44CFD32041A").unwrap();
+        let long = encode_beast_frame(&MessageType::LongFrame, 0x1A00_1A00_0001, 0x1a, &long_frame)
+            .unwrap();
+        let short = encode_beast_frame(
+            &MessageType::ShortFrame,
+            0x01B5_BF53_9F89,
+            0x6D,
+            &short_frame,
+        )
+        .unwrap();
+        let mode_ac = encode_beast_frame(&MessageType::ModeAC, 0, 0x40, &[0x1a, 0x1a]).unwrap();
+        assert_eq!(
+            long[..12],
+            [
+                0x1a, 0x33, 0x1a, 0x1a, 0x00, 0x1a, 0x1a, 0x00, 0x00, 0x01, 0x1a, 0x1a
+            ]
+        );
+        assert_eq!(short.len(), 17);
+
+        let encoded = [long.clone(), short.clone(), mode_ac].concat();
+        let frames = format_adsb_beast_frames_from_bytes(&encoded);
+        assert_eq!(frames.len(), 3);
+        assert!(frames.errors.is_empty());
+        assert!(frames.left_over.is_empty());
+
+        let mut streaming = ADSBBeastFramer::new();
+        streaming.push(&encoded);
+        for frame in &frames.frames {
+            assert_eq!(streaming.next_frame().unwrap().as_ref(), frame.as_slice());
+        }
+        assert_eq!(streaming.bytes_dropped(), 0);
+
+        // decoding and encoding again gives the same bytes
+        let message = AdsbBeastMessage::from_bytes(&frames.frames[0]).unwrap();
+        assert_eq!(message.to_beast_bytes().unwrap(), long);
+        let message = AdsbBeastMessage::from_bytes(&frames.frames[1]).unwrap();
+        assert_eq!(message.to_beast_bytes().unwrap(), short);
+        // a message that never was Beast encodes the same, if it is an extended squitter
+        let raw_message = AdsbRawMessage::from_bytes(&long_frame).unwrap();
+        let message = AdsbBeastMessage::new(raw_message, 0x1A00_1A00_0001, 0x1a);
+        assert_eq!(message.to_beast_bytes().unwrap(), long);
+        let raw_message = AdsbRawMessage::from_bytes(&short_frame).unwrap();
+        let message = AdsbBeastMessage::new(raw_message, 0x01B5_BF53_9F89, 0x6D);
+        assert!(message.to_beast_bytes().is_err());
+        // a corrupt frame goes back out with the parity it came in with
+        let mut corrupt_frame = long_frame.clone();
+        corrupt_frame[6] ^= 0x01;
+        let corrupt = encode_beast_frame(&MessageType::LongFrame, 0, 0x40, &corrupt_frame).unwrap();
+        let corrupt_frames = format_adsb_beast_frames_from_bytes(&corrupt);
+        let message = AdsbBeastMessage::from_bytes(&corrupt_frames.frames[0]).unwrap();
+        assert!(message.raw_message.crc_status().is_corrupt());
+        assert_eq!(message.to_beast_bytes().unwrap(), corrupt);
+        assert_eq!(
+            ModeACMessage::from_bytes(&frames.frames[2]).unwrap().code,
+            0x1a1a
+        );
+
+        // the receiver ID goes out as a frame of its own in front
+        let mut with_receiver_id = vec![0xe3, 0, 0, 0, 0, 0, 0, 0, 0x1a];
+        with_receiver_id.extend_from_slice(&frames.frames[1]);
+        let escaped = escape_beast_frame(&with_receiver_id);
+        assert_eq!(
+            escaped[..12],
+            [0x1a, 0xe3, 0, 0, 0, 0, 0, 0, 0, 0x1a, 0x1a, 0x1a]
+        );
+        let frames = format_adsb_beast_frames_from_bytes(&escaped);
+        assert_eq!(frames.frames, vec![with_receiver_id.clone()]);
+        let message = AdsbBeastMessage::from_bytes(&with_receiver_id).unwrap();
+        assert_eq!(message.receiver_id, Some(0x1a));
+        assert_eq!(message.to_beast_bytes().unwrap(), escaped);
+
+        assert!(encode_beast_frame(&MessageType::ShortFrame, 0, 0, &long_frame).is_err());
+        assert!(encode_beast_frame(&MessageType::LongFrame, 1 << 48, 0, &long_frame).is_err());
+    }
+}
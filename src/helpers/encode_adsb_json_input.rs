@@ -5,6 +5,13 @@
 // https://opensource.org/licenses/MIT.
 
 use crate::error_handling::adsb_json_error::ADSBJSONError;
+use bytes::BytesMut;
+
+use super::framer::{ADSBFramer, FrameFormat, FrameScan};
+
+const ADSB_JSON_FRAME_END: &[u8] = b"}\n";
+/// A frame that hasn't ended by now never will
+const ADSB_JSON_MAX_FRAME: usize = 64 * 1024;
 
 pub struct ADSBJSONFrames {
     pub frames: Vec<String>,
@@ -24,6 +31,48 @@ impl ADSBJSONFrames {
     }
 }
 
+/// readsb's JSON output for `ADSBFramer`, one object per line.
+///
+/// Frames are handed out as the bytes from `{` to `}`, ready for `DecodeMessage`.
+#[derive(Debug, Default)]
+pub struct ADSBJSONFraming;
+
+/// Streaming JSON framer, see `ADSBJSONFraming`
+pub type ADSBJSONFramer = ADSBFramer<ADSBJSONFraming>;
+
+impl FrameFormat for ADSBJSONFraming {
+    fn scan(buffer: &mut BytesMut, lost_sync: bool) -> FrameScan {
+        // the line ending of the previous frame
+        if !lost_sync {
+            let whitespace = buffer
+                .iter()
+                .take_while(|byte| byte.is_ascii_whitespace())
+                .count();
+            if whitespace > 0 {
+                return FrameScan::Skip(whitespace);
+            }
+        }
+
+        match buffer.iter().position(|byte| *byte == b'{') {
+            Some(0) => (),
+            Some(start) => return FrameScan::Drop(start),
+            None => return FrameScan::Drop(buffer.len()),
+        }
+
+        let Some(end) = buffer
+            .windows(ADSB_JSON_FRAME_END.len())
+            .position(|window| window == ADSB_JSON_FRAME_END)
+        else {
+            if buffer.len() < ADSB_JSON_MAX_FRAME {
+                return FrameScan::Incomplete;
+            }
+            return FrameScan::Drop(1);
+        };
+
+        FrameScan::Frame(buffer.split_to(end + 1).freeze())
+    }
+}
+
 /// Helper function to format ADSB JSON frames from a string.
 /// Expected input is a &String of the JSON frame(s), including the control characters to start and end the frame.
 /// Does not consume the input.
@@ -101,6 +150,9 @@ pub fn format_adsb_json_frames_from_bytes(bytes: &[u8]) -> ADSBJSONFrames {
 #[cfg(test)]
 mod test {
     use super::*;
+    use bytes::Bytes;
+    use tokio_util::codec::Decoder;
+
     #[test]
     fn test_adsb_json_parsing_input_single_frame() {
         let input = "{\"now\" : 1701103343.740,\"hex\":\"a40d4c\",\"type\":\"adsb_icao\",\"flight\":\"N360LF  \",\"r\":\"N360LF\",\"t\":\"GLF5\",\"dbFlags\":8,\"alt_baro\":45000,\"alt_geom\":45450,\"gs\":521.1,\"track\":68.85,\"baro_rate\":-64,\"squawk\":\"1416\",\"emergency\":\"none\",\"category\":\"A3\",\"nav_qnh\":1013.6,\"nav_altitude_mcp\":45024,\"nav_modes\":[\"autopilot\",\"althold\",\"tcas\"],\"lat\":37.491031,\"lon\":-107.526358,\"nic\":8,\"rc\":186,\"seen_pos\":0.000,\"r_dst\":145.808,\"r_dir\":341.8,\"version\":2,\"nic_baro\":1,\"nac_p\":10,\"nac_v\":2,\"sil\":3,\"sil_type\":\"perhour\",\"gva\":2,\"sda\":2,\"alert\":0,\"spi\":0,\"mlat\":[],\"tisb\":[],\"messages\":2657,\"seen\":0.0,\"rssi\":-19.0}\n";
@@ -385,4 +437,36 @@ mod test {
             "Expected incomplete frame"
         );
     }
+
+    #[test]
+    fn test_adsb_json_framer() {
+        let input = b"0.0}\n{\"hex\":\"a40d4c\",\"alt_baro\":45000}\n{\"hex\":\"ac9f65\",\"tisb\":[],\"mlat\":[]}\n{\"hex\"";
+
+        let mut framer = ADSBJSONFramer::new();
+        let mut output: Vec<Bytes> = Vec::new();
+        for chunk in input.chunks(7) {
+            framer.push(chunk);
+            while let Some(frame) = framer.next_frame() {
+                output.push(frame);
+            }
+        }
+
+        assert_eq!(
+            output,
+            [
+                &b"{\"hex\":\"a40d4c\",\"alt_baro\":45000}"[..],
+                &b"{\"hex\":\"ac9f65\",\"tisb\":[],\"mlat\":[]}"[..]
+            ]
+        );
+        assert_eq!(framer.bytes_dropped(), 5);
+        assert_eq!(framer.resyncs(), 1);
+
+        let mut buffer = BytesMut::from(&b"{\"hex\"}\n{\"hex\""[..]);
+        assert_eq!(
+            framer.decode(&mut buffer).unwrap().unwrap(),
+            &b"{\"hex\"}"[..]
+        );
+        assert_eq!(framer.decode_eof(&mut buffer).unwrap(), None);
+        assert!(buffer.is_empty());
+    }
 }
@@ -5,13 +5,29 @@
 // https://opensource.org/licenses/MIT.
 
 use crate::error_handling::adsb_raw_error::ADSBRawError;
+use bytes::{Buf, Bytes, BytesMut};
 use hex;
+
+use super::framer::{ADSBFramer, FrameFormat, FrameScan};
+
 const ADSB_RAW_START_CHARACTER: u8 = 0x2a; // The adsb raw end character sequence is is a '0x3b0a', start is '0x2a'
 const ADSB_RAW_END_SEQUENCE_FINISH_CHARACTER: u8 = 0x0a;
 const ADSB_RAW_END_SEQUENCE_INIT_CHARACTER: u8 = 0x3b;
 const ADSB_RAW_FRAME_SMALL: usize = 14;
 const ADSB_RAW_FRAME_LARGE: usize = 28;
 const ADSB_RAW_MODEAC_FRAME: usize = 4;
+/// `@<12 hex MLAT timestamp><frame>;`
+const ADSB_RAW_MLAT_START_CHARACTER: u8 = b'@';
+/// `%<12 hex MLAT timestamp><2 hex signal level><frame>;`
+const ADSB_RAW_SIGNAL_START_CHARACTER: u8 = b'%';
+const ADSB_RAW_TIMESTAMP_LENGTH: usize = 12;
+const ADSB_RAW_SIGNAL_LENGTH: usize = 2;
+/// `%`, the timestamp, the signal level, the longest frame and `;`
+const ADSB_RAW_MAX_LINE: usize =
+    ADSB_RAW_TIMESTAMP_LENGTH + ADSB_RAW_SIGNAL_LENGTH + ADSB_RAW_FRAME_LARGE + 2;
+const ADSB_BEAST_MODEAC_FRAME_START_CHARACTER: u8 = 0x31;
+const ADSB_BEAST_SHORT_FRAME_START_CHARACTER: u8 = 0x32;
+const ADSB_BEAST_LONG_FRAME_START_CHARACTER: u8 = 0x33;
 
 pub struct ADSBRawFrames {
     pub frames: Vec<Vec<u8>>,
@@ -31,6 +47,126 @@ impl ADSBRawFrames {
     }
 }
 
+/// AVR lines for `ADSBFramer`.
+///
+/// Frames are handed out hex decoded, like `format_adsb_raw_frames_from_bytes`. The decoding of `*` lines is done in
+/// place in the buffer.
+#[derive(Debug, Default)]
+pub struct ADSBRawFraming;
+
+/// Streaming AVR framer, see `ADSBRawFraming`
+pub type ADSBRawFramer = ADSBFramer<ADSBRawFraming>;
+
+impl FrameFormat for ADSBRawFraming {
+    fn scan(buffer: &mut BytesMut, lost_sync: bool) -> FrameScan {
+        // the line endings of the previous frame
+        if !lost_sync {
+            let line_endings = buffer
+                .iter()
+                .take_while(|byte| matches!(byte, b'\r' | b'\n'))
+                .count();
+            if line_endings > 0 {
+                return FrameScan::Skip(line_endings);
+            }
+        }
+
+        match buffer.iter().position(|byte| {
+            matches!(
+                *byte,
+                ADSB_RAW_START_CHARACTER
+                    | ADSB_RAW_MLAT_START_CHARACTER
+                    | ADSB_RAW_SIGNAL_START_CHARACTER
+            )
+        }) {
+            Some(0) => (),
+            Some(start) => return FrameScan::Drop(start),
+            None => return FrameScan::Drop(buffer.len()),
+        }
+
+        let Some(end) = buffer
+            .iter()
+            .take(ADSB_RAW_MAX_LINE)
+            .position(|byte| *byte == ADSB_RAW_END_SEQUENCE_INIT_CHARACTER)
+        else {
+            if buffer.len() < ADSB_RAW_MAX_LINE {
+                return FrameScan::Incomplete;
+            }
+            return FrameScan::Drop(1);
+        };
+
+        if buffer[0] != ADSB_RAW_START_CHARACTER {
+            let Some(frame) = timestamped_frame(buffer[0], &buffer[1..end]) else {
+                return FrameScan::Drop(end + 1);
+            };
+            buffer.advance(end + 1);
+            return FrameScan::Frame(Bytes::from(frame));
+        }
+
+        let frame_length = end - 1;
+        if !matches!(
+            frame_length,
+            ADSB_RAW_MODEAC_FRAME | ADSB_RAW_FRAME_SMALL | ADSB_RAW_FRAME_LARGE
+        ) || !decode_hex_in_place(buffer, frame_length)
+        {
+            return FrameScan::Drop(end + 1);
+        }
+
+        let frame = buffer.split_to(frame_length / 2).freeze();
+        buffer.advance(end + 1 - frame_length / 2);
+        FrameScan::Frame(frame)
+    }
+}
+
+/// Decode the hex after the `*` to the start of the buffer. Every byte is written behind the
+/// digits it was read from, so nothing is overwritten before it is read.
+fn decode_hex_in_place(buffer: &mut BytesMut, frame_length: usize) -> bool {
+    for index in 0..frame_length / 2 {
+        let high = (buffer[1 + 2 * index] as char).to_digit(16);
+        let low = (buffer[2 + 2 * index] as char).to_digit(16);
+        let (Some(high), Some(low)) = (high, low) else {
+            return false;
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let byte = ((high << 4) | low) as u8;
+        buffer[index] = byte;
+    }
+
+    true
+}
+
+/// Hex decode the rest of a `@` or `%` line to a frame with a Beast style header: frame type, 48 bit MLAT
+/// timestamp and signal level, which is 0 for `@` lines. `AdsbRawMessage::from_avr_bytes` reads it back.
+fn timestamped_frame(start_character: u8, hex_line: &[u8]) -> Option<Vec<u8>> {
+    let header_length = match start_character {
+        ADSB_RAW_MLAT_START_CHARACTER => ADSB_RAW_TIMESTAMP_LENGTH,
+        ADSB_RAW_SIGNAL_START_CHARACTER => ADSB_RAW_TIMESTAMP_LENGTH + ADSB_RAW_SIGNAL_LENGTH,
+        _ => return None,
+    };
+
+    let frame_type = match hex_line.len().checked_sub(header_length)? {
+        ADSB_RAW_MODEAC_FRAME => ADSB_BEAST_MODEAC_FRAME_START_CHARACTER,
+        ADSB_RAW_FRAME_SMALL => ADSB_BEAST_SHORT_FRAME_START_CHARACTER,
+        ADSB_RAW_FRAME_LARGE => ADSB_BEAST_LONG_FRAME_START_CHARACTER,
+        _ => return None,
+    };
+
+    let bytes = hex::decode(hex_line).ok()?;
+    let (timestamp, rest) = bytes.split_at(ADSB_RAW_TIMESTAMP_LENGTH / 2);
+    let (signal_level, frame) = if start_character == ADSB_RAW_SIGNAL_START_CHARACTER {
+        rest.split_first()?
+    } else {
+        (&0, rest)
+    };
+
+    let mut output = Vec::with_capacity(2 + bytes.len());
+    output.push(frame_type);
+    output.extend_from_slice(timestamp);
+    output.push(*signal_level);
+    output.extend_from_slice(frame);
+    Some(output)
+}
+
 /// Helper function to format ADSB Raw frames from bytes.
 /// Expected input is a &Vec<Vec<u8>>of the raw frame(s), including the control characters to start and end the frame.
 /// Does not consume the input.
@@ -43,6 +179,8 @@ pub fn format_adsb_raw_frames_from_bytes(bytes: &[u8]) -> ADSBRawFrames {
     let mut current_frame: Vec<u8> = Vec::new();
     let mut errors_found: Vec<ADSBRawError> = Vec::new();
 
+    let mut start_character = ADSB_RAW_START_CHARACTER;
+
     let mut byte_iter = bytes.iter().peekable();
 
     while let Some(byte) = byte_iter.next() {
@@ -51,15 +189,24 @@ pub fn format_adsb_raw_frames_from_bytes(bytes: &[u8]) -> ADSBRawFrames {
         if *byte == ADSB_RAW_END_SEQUENCE_INIT_CHARACTER
             && byte_iter.peek() == Some(&&ADSB_RAW_END_SEQUENCE_FINISH_CHARACTER)
         {
-            // verify we have a valid frame length
-            if current_frame.len() == ADSB_RAW_MODEAC_FRAME {
-                // we will ignore the modeac frame
+            // `@` and `%` lines carry a timestamp in front of the frame
+            if start_character != ADSB_RAW_START_CHARACTER {
+                match timestamped_frame(start_character, &current_frame) {
+                    Some(frame_bytes) => formatted_frames.push(frame_bytes),
+                    None => errors_found.push(ADSBRawError::ByteSequenceWrong {
+                        size: current_frame.len(),
+                    }),
+                }
+
+                start_character = ADSB_RAW_START_CHARACTER;
                 current_frame.clear();
                 _ = byte_iter.next();
                 continue;
             }
 
-            if current_frame.len() != ADSB_RAW_FRAME_SMALL
+            // verify we have a valid frame length
+            if current_frame.len() != ADSB_RAW_MODEAC_FRAME
+                && current_frame.len() != ADSB_RAW_FRAME_SMALL
                 && current_frame.len() != ADSB_RAW_FRAME_LARGE
             {
                 errors_found.push(ADSBRawError::ByteSequenceWrong {
@@ -85,7 +232,13 @@ pub fn format_adsb_raw_frames_from_bytes(bytes: &[u8]) -> ADSBRawFrames {
         }
 
         // If we've encountered the start character, we will just continue to the next loop iteration
-        if *byte == ADSB_RAW_START_CHARACTER {
+        if matches!(
+            *byte,
+            ADSB_RAW_START_CHARACTER
+                | ADSB_RAW_MLAT_START_CHARACTER
+                | ADSB_RAW_SIGNAL_START_CHARACTER
+        ) {
+            start_character = *byte;
             continue;
         }
 
@@ -108,6 +261,8 @@ pub fn format_adsb_raw_frames_from_bytes(bytes: &[u8]) -> ADSBRawFrames {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::decoders::{modeac::ModeACMessage, raw::AdsbRawMessage};
+    use tokio_util::codec::Decoder;
     #[test]
     fn test_adsb_raw_parsing_input() {
         let mut input = vec![
@@ -139,15 +294,98 @@ mod test {
         input.push(0x0a);
         assert_eq!(
             format_adsb_raw_frames_from_bytes(&input).len(),
-            2,
-            "There should be two frames in the input"
+            3,
+            "There should be three frames in the input"
         );
         assert_eq!(
             format_adsb_raw_frames_from_bytes(&input).frames,
             [
                 hex::decode("5DABE65A2FBFAF").unwrap(),
-                hex::decode("8DA1A3CC9909B814F004127F1107").unwrap()
+                hex::decode("8DA1A3CC9909B814F004127F1107").unwrap(),
+                hex::decode("5424").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_adsb_raw_framer() {
+        let input =
+            b"0F;\n*5DABE65A2FBFAF;\n*8DA1A3CC9909B814F004127F1107;\r\n*5424;\n*8DXX;\n*5DAB";
+
+        let mut framer = ADSBRawFramer::new();
+        let mut output: Vec<Bytes> = Vec::new();
+        for chunk in input.chunks(3) {
+            framer.push(chunk);
+            while let Some(frame) = framer.next_frame() {
+                output.push(frame);
+            }
+        }
+
+        assert_eq!(
+            output,
+            [
+                hex::decode("5DABE65A2FBFAF").unwrap(),
+                hex::decode("8DA1A3CC9909B814F004127F1107").unwrap(),
+                hex::decode("5424").unwrap()
             ]
         );
+        // the tail of a frame at the start and the broken frame, with their line endings
+        assert_eq!(framer.bytes_dropped(), 11);
+        assert_eq!(framer.resyncs(), 2);
+
+        let mut buffer = BytesMut::from(&b"*5DABE65A2FBFAF;\n*5DAB"[..]);
+        assert_eq!(
+            framer.decode(&mut buffer).unwrap().unwrap(),
+            hex::decode("5DABE65A2FBFAF").unwrap()
+        );
+        assert_eq!(framer.decode_eof(&mut buffer).unwrap(), None);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_adsb_raw_timestamped_lines() {
+        let input = b"@0001B5BF520C8D4840D6202CC371C32CE0576098;\n%0001B5BF520C9F5D4840D6F8740F;\n@0001B5BF520C5424;\n@0001B5BF8D48;\n";
+        let long = hex::decode("330001B5BF520C008D4840D6202CC371C32CE0576098").unwrap();
+        let short = hex::decode("320001B5BF520C9F5D4840D6F8740F").unwrap();
+        let mode_ac = hex::decode("310001B5BF520C005424").unwrap();
+
+        let formatted = format_adsb_raw_frames_from_bytes(input);
+        assert_eq!(
+            formatted.frames,
+            [long.clone(), short.clone(), mode_ac.clone()]
+        );
+        assert_eq!(formatted.errors.len(), 1);
+
+        let mut framer = ADSBRawFramer::new();
+        framer.push(input);
+        let mut output: Vec<Bytes> = Vec::new();
+        while let Some(frame) = framer.next_frame() {
+            output.push(frame);
+        }
+        assert_eq!(output, [long.clone(), short.clone(), mode_ac.clone()]);
+        // the line with a timestamp that is too short
+        assert_eq!(framer.bytes_dropped(), 15);
+
+        let message = AdsbRawMessage::from_avr_bytes(&long).unwrap();
+        assert_eq!(message.mlat_timestamp, Some(0x0001_B5BF_520C));
+        assert_eq!(message.signal_level, None);
+        assert_eq!(message.get_transponder_hex(), Some("4840D6".to_string()));
+
+        let message = AdsbRawMessage::from_avr_bytes(&short).unwrap();
+        assert_eq!(message.signal_level, Some(0x9F));
+
+        let message =
+            AdsbRawMessage::from_avr_bytes(&hex::decode("5D4840D6F8740F").unwrap()).unwrap();
+        assert_eq!(message.mlat_timestamp, None);
+
+        // Mode A/C lines keep their timestamp and signal level too
+        let message = ModeACMessage::from_bytes(&mode_ac).unwrap();
+        assert_eq!(message.mlat_timestamp, Some(0x0001_B5BF_520C));
+        assert_eq!(message.signal_level, None);
+
+        let formatted = format_adsb_raw_frames_from_bytes(b"%0001B5BF520C9F5424;\n");
+        let message = ModeACMessage::from_bytes(&formatted.frames[0]).unwrap();
+        assert_eq!(message.mlat_timestamp, Some(0x0001_B5BF_520C));
+        assert_eq!(message.signal_level, Some(0x9F));
     }
 }
@@ -0,0 +1,148 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// Build Mode S frames from decoded or hand made messages. Only extended squitters can be written,
+// the other formats overlay the address on the parity and carry fields this library doesn't keep.
+
+use deku::ctx::BitSize;
+use deku::no_std_io::Cursor;
+use deku::prelude::*;
+
+use crate::MessageResult;
+use crate::decoders::raw_types::{df::DF, helper_functions::modes_checksum};
+use crate::error_handling::adsb_raw_error::ADSBRawError;
+
+const DF_ADSB: u8 = 17;
+const DF_TISB: u8 = 18;
+const MODES_LONG_MSG_BYTES: usize = 14;
+const MODES_PARITY_BYTES: usize = 3;
+
+/// Encode a DF17 or DF18 extended squitter as a 14 byte frame, with the parity computed over the frame.
+///
+/// The PI field of `df` is not written, so a message can be built with any value there. The frame decodes
+/// back with `AdsbRawMessage::from_bytes` to the same `DF`, with the PI set to the parity.
+/// # Errors
+/// If `df` is not an extended squitter, or a field holds a value that can't be sent, the error is returned.
+pub fn encode_extended_squitter(df: &DF) -> MessageResult<Vec<u8>> {
+    let mut cursor = Cursor::new(Vec::with_capacity(MODES_LONG_MSG_BYTES));
+    let mut writer = Writer::new(&mut cursor);
+
+    match df {
+        DF::ADSB(adsb) => {
+            DF_ADSB.to_writer(&mut writer, BitSize(5))?;
+            adsb.capability.to_writer(&mut writer, ())?;
+            adsb.icao.to_writer(&mut writer, ())?;
+            adsb.me.to_writer(&mut writer, ())?;
+        }
+        DF::TisB { cf, .. } => {
+            DF_TISB.to_writer(&mut writer, BitSize(5))?;
+            cf.to_writer(&mut writer, ())?;
+        }
+        _ => {
+            return Err(ADSBRawError::UnsupportedDownlinkFormat {
+                df: df
+                    .deku_id()
+                    .map_or_else(|_| "24".to_string(), |id| id.to_string()),
+            }
+            .into());
+        }
+    }
+    [0_u8; MODES_PARITY_BYTES].to_writer(&mut writer, ())?;
+    writer.finalize()?;
+
+    let mut frame = cursor.into_inner();
+    if frame.len() != MODES_LONG_MSG_BYTES {
+        return Err(ADSBRawError::ByteSequenceWrong { size: frame.len() }.into());
+    }
+
+    // with the parity bytes zeroed the checksum is the parity itself
+    let parity = modes_checksum(&frame, MODES_LONG_MSG_BYTES * 8)?;
+    frame[MODES_LONG_MSG_BYTES - MODES_PARITY_BYTES..]
+        .copy_from_slice(&parity.to_be_bytes()[4 - MODES_PARITY_BYTES..]);
+
+    Ok(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::raw::{AdsbRawMessage, NewAdsbRawMessage};
+    use crate::decoders::raw_types::{
+        adsb::Adsb, capability::Capability, icao::ICAO, identification::Identification, me::ME,
+        typecoding::TypeCoding,
+    };
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn encode_extended_squitters() {
+        "debug".enable_logging();
+
+        // identification, airborne and surface position, velocity over ground and airspeed, no position,
+        // aircraft status, target state, operation status, and DF18 from ADS-R and TIS-B
+        for message in [
+            "8D4840D6202CC371C32CE0576098",
+            "8DA69B9C223B5CB5082820C97A87",
+            "8FA4955D597D8288F8C756559A37",
+            "8DAB9740592F1698B5946EDE108E",
+            "8D4840D6C1123456789ABCFC9F73",
+            "8DA1A3CC9909B814F004127F1107",
+            "8DC05BCF9909CF0DD00417286F1E",
+            "92A1A3CC9989B814F0041223387D",
+            "8DADC035002D8000000000B16E64",
+            "8DAB44A7E10289000000008922C1",
+            "8DA0CA2DEA57F866C15C088DEF6F",
+            "8DABBD47F8230006004AB87B5E9E",
+            "904840D6202CC371C32CE02A6C6D",
+            "96A1A3CC9909B814F004122CCEEB",
+        ] {
+            let decoded = message.to_adsb_raw().unwrap();
+            let frame = decoded.to_frame_bytes().unwrap();
+            assert_eq!(hex::encode_upper(&frame), message);
+            assert_eq!(AdsbRawMessage::from_bytes(&frame).unwrap(), decoded);
+        }
+
+        // built from field values, the parity is filled in
+        let adsb = DF::ADSB(Adsb {
+            capability: Capability::AG_AIRBORNE,
+            icao: ICAO([0x48, 0x40, 0xD6]),
+            me: ME::AircraftIdentification(
+                4,
+                Identification {
+                    tc: TypeCoding::from(4),
+                    ca: 0,
+                    cn: "KLM1023".to_string(),
+                },
+            ),
+            pi: ICAO([0, 0, 0]),
+        });
+        let frame = encode_extended_squitter(&adsb).unwrap();
+        assert_eq!(hex::encode_upper(&frame), "8D4840D6202CC371C32CE0576098");
+        let decoded = AdsbRawMessage::from_bytes(&frame).unwrap();
+        assert!(!decoded.crc_status().is_corrupt());
+
+        let DF::ADSB(mut invalid) = adsb else {
+            unreachable!()
+        };
+        invalid.me = ME::AircraftIdentification(
+            4,
+            Identification {
+                tc: TypeCoding::from(4),
+                ca: 0,
+                cn: "KLM-1023".to_string(),
+            },
+        );
+        assert!(encode_extended_squitter(&DF::ADSB(invalid)).is_err());
+
+        // DF11 can't be encoded
+        assert!(
+            "5D4840D6F8740F"
+                .to_adsb_raw()
+                .unwrap()
+                .to_frame_bytes()
+                .is_err()
+        );
+    }
+}
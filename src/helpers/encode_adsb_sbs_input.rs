@@ -0,0 +1,90 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use bytes::{Buf, BytesMut};
+
+use super::framer::{ADSBFramer, FrameFormat, FrameScan};
+
+const ADSB_SBS_END_CHARACTER: u8 = b'\n';
+/// SBS lines are around 100 characters, anything this long is not one
+const ADSB_SBS_MAX_LINE: usize = 512;
+
+/// SBS (`BaseStation`) output for `ADSBFramer`, one CSV message per line.
+///
+/// Frames are handed out as the line without the line ending.
+#[derive(Debug, Default)]
+pub struct ADSBSBSFraming;
+
+/// Streaming SBS framer, see `ADSBSBSFraming`
+pub type ADSBSBSFramer = ADSBFramer<ADSBSBSFraming>;
+
+impl FrameFormat for ADSBSBSFraming {
+    fn scan(buffer: &mut BytesMut, lost_sync: bool) -> FrameScan {
+        let Some(end) = buffer
+            .iter()
+            .position(|byte| *byte == ADSB_SBS_END_CHARACTER)
+        else {
+            if buffer.len() >= ADSB_SBS_MAX_LINE {
+                return FrameScan::Drop(buffer.len());
+            }
+            return FrameScan::Incomplete;
+        };
+
+        // the rest of a line that was too long
+        if lost_sync {
+            return FrameScan::Resync(end + 1);
+        }
+
+        if matches!(&buffer[..end], b"" | b"\r") {
+            return FrameScan::Skip(end + 1);
+        }
+
+        let mut line = buffer.split_to(end).freeze();
+        buffer.advance(1);
+        if line.ends_with(b"\r") {
+            line.truncate(line.len() - 1);
+        }
+
+        FrameScan::Frame(line)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bytes::Bytes;
+
+    #[test]
+    fn test_adsb_sbs_framer() {
+        let input = b"MSG,3,1,1,4840D6,1,2024/01/01,00:00:00.000,2024/01/01,00:00:00.000,,38000,,,52.25,3.91,,,0,0,0,0\r\nMSG,5,1,1,4840D6,1,,,,,,38000,,,,,,,0,,0,\nMSG,";
+
+        let mut framer = ADSBSBSFramer::new();
+        let mut output: Vec<Bytes> = Vec::new();
+        for chunk in input.chunks(11) {
+            framer.push(chunk);
+            while let Some(frame) = framer.next_frame() {
+                output.push(frame);
+            }
+        }
+
+        assert_eq!(output.len(), 2);
+        assert!(output[0].starts_with(b"MSG,3,"));
+        assert!(output[0].ends_with(b",0"));
+        assert_eq!(&output[1][..], b"MSG,5,1,1,4840D6,1,,,,,,38000,,,,,,,0,,0,");
+        assert_eq!(framer.bytes_dropped(), 0);
+
+        // a line that never ends is dropped, including its tail once the end shows up
+        framer.push(&[b'0'; ADSB_SBS_MAX_LINE]);
+        assert_eq!(framer.next_frame(), None);
+        framer.push(b"0000\nMSG,8,1,1,4840D6,1,,,,,,,,,,,,,,,,0\n");
+        assert_eq!(
+            framer.next_frame().unwrap(),
+            &b"MSG,8,1,1,4840D6,1,,,,,,,,,,,,,,,,0"[..]
+        );
+        assert_eq!(framer.resyncs(), 1);
+        assert_eq!(framer.bytes_dropped(), (ADSB_SBS_MAX_LINE + 4 + 5) as u64);
+    }
+}
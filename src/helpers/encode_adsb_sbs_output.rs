@@ -0,0 +1,421 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// SBS-1 / BaseStation output, the port 30003 format read by Virtual Radar Server and PlanePlotter.
+// See `decoders::sbs` for the fields. The frame that was received picks the transmission type, the
+// values come from what is known about the aircraft once the frame has been applied, the same way
+// dump1090 writes them.
+
+use std::fmt::{self, Formatter};
+
+use crate::decoders::{
+    common_types::{
+        barorate::BaroRate, heading::Heading, speed::Speed, surveillancestatus::SurveillanceStatus,
+    },
+    helpers::time::get_time_as_f64,
+    json::JSONMessage,
+    json_types::{altitude::Altitude, emergency::Emergency},
+    raw::AdsbRawMessage,
+    raw_types::{capability::Capability, df::DF, flightstatus::FlightStatus, me::ME},
+};
+
+const SBS_LINE_ENDING: &str = "\r\n";
+const SBS_FLAG_SET: &str = "-1";
+const SBS_FLAG_NOT_SET: &str = "0";
+const SBS_DATE_FORMAT: &str = "%Y/%m/%d";
+const SBS_TIME_FORMAT: &str = "%H:%M:%S%.3f";
+const EMERGENCY_SQUAWKS: [&str; 3] = ["7500", "7600", "7700"];
+
+/// The second field of a `MSG` line, which says what kind of frame the line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SBSTransmissionType {
+    /// DF17/18 identification and category
+    Identification = 1,
+    /// DF17/18 surface position
+    SurfacePosition = 2,
+    /// DF17/18 airborne position
+    AirbornePosition = 3,
+    /// DF17/18 airborne velocity
+    AirborneVelocity = 4,
+    /// DF4 and DF20 altitude replies
+    SurveillanceAltitude = 5,
+    /// DF5 and DF21 identity replies
+    SurveillanceIdentity = 6,
+    /// DF0 and DF16 air to air surveillance
+    AirToAir = 7,
+    /// DF11 all-call reply
+    AllCallReply = 8,
+}
+
+impl fmt::Display for SBSTransmissionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as u8)
+    }
+}
+
+/// One `MSG` line. Fields that are `None` are left empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SBSLine {
+    pub transmission_type: SBSTransmissionType,
+    pub transponder_hex: String,
+    pub callsign: Option<String>,
+    /// Feet
+    pub altitude: Option<i32>,
+    /// Knots
+    pub ground_speed: Option<f64>,
+    pub track: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Feet per minute
+    pub vertical_rate: Option<i32>,
+    pub squawk: Option<String>,
+    /// Alert, the squawk has changed
+    pub alert: Option<bool>,
+    pub emergency: Option<bool>,
+    pub spi: Option<bool>,
+    pub on_ground: Option<bool>,
+    /// Seconds since the epoch, written as both the generated and the logged time
+    pub timestamp: f64,
+}
+
+impl fmt::Display for SBSLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        #[allow(clippy::cast_possible_truncation)]
+        let (date, time) =
+            match chrono::DateTime::from_timestamp_millis((self.timestamp * 1000.0) as i64) {
+                Some(timestamp) => (
+                    timestamp.format(SBS_DATE_FORMAT).to_string(),
+                    timestamp.format(SBS_TIME_FORMAT).to_string(),
+                ),
+                None => (String::new(), String::new()),
+            };
+
+        write!(
+            f,
+            "MSG,{},1,1,{},1,{date},{time},{date},{time},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.transmission_type,
+            self.transponder_hex,
+            self.callsign.as_deref().unwrap_or_default(),
+            optional_field(self.altitude),
+            optional_field(self.ground_speed.map(|speed| format!("{speed:.0}"))),
+            optional_field(self.track.map(|track| format!("{track:.0}"))),
+            optional_field(self.latitude.map(|latitude| format!("{latitude:.5}"))),
+            optional_field(self.longitude.map(|longitude| format!("{longitude:.5}"))),
+            optional_field(self.vertical_rate),
+            self.squawk.as_deref().unwrap_or_default(),
+            flag_field(self.alert),
+            flag_field(self.emergency),
+            flag_field(self.spi),
+            flag_field(self.on_ground),
+        )
+    }
+}
+
+impl SBSLine {
+    /// The line for a frame, with the values the state of the aircraft holds after the frame was applied to it.
+    ///
+    /// Returns `None` for frames that have no transmission type, such as DF19, DF24 or extended squitters other
+    /// than identification, position and velocity.
+    #[must_use]
+    pub fn from_raw(message: &AdsbRawMessage, aircraft: &JSONMessage) -> Option<SBSLine> {
+        let (transmission_type, flight_status, on_ground) = match &message.df {
+            DF::ADSB(adsb) => extended_squitter_type(&adsb.me)?,
+            DF::TisB { cf, .. } => extended_squitter_type(&cf.me)?,
+            DF::SurveillanceAltitudeReply { fs, .. } => {
+                (SBSTransmissionType::SurveillanceAltitude, Some(*fs), None)
+            }
+            DF::CommBAltitudeReply { flight_status, .. } => (
+                SBSTransmissionType::SurveillanceAltitude,
+                Some(*flight_status),
+                None,
+            ),
+            DF::SurveillanceIdentityReply { fs, .. } | DF::CommBIdentityReply { fs, .. } => {
+                (SBSTransmissionType::SurveillanceIdentity, Some(*fs), None)
+            }
+            DF::ShortAirAirSurveillance { vs, .. } | DF::LongAirAir { vs, .. } => {
+                (SBSTransmissionType::AirToAir, None, Some(*vs == 1))
+            }
+            DF::AllCallReply { capability, .. } => (
+                SBSTransmissionType::AllCallReply,
+                None,
+                match capability {
+                    Capability::AG_GROUND => Some(true),
+                    Capability::AG_AIRBORNE => Some(false),
+                    _ => None,
+                },
+            ),
+            _ => return None,
+        };
+
+        let mut line = Self::new(transmission_type, aircraft, get_time_as_f64());
+        line.on_ground = on_ground
+            .or_else(|| flight_status.and_then(flight_status_on_ground))
+            .or(line.on_ground);
+        if let Some(fs) = flight_status {
+            line.alert = Some(matches!(
+                fs,
+                FlightStatus::AlertNoSPIAirborne
+                    | FlightStatus::AlertNoSPIOnGround
+                    | FlightStatus::AlertSPIAirborneGround
+            ));
+            line.spi = Some(matches!(
+                fs,
+                FlightStatus::AlertSPIAirborneGround | FlightStatus::NoAlertSPIAirborneGround
+            ));
+        }
+
+        Some(line.only_transmitted_fields())
+    }
+
+    /// A snapshot of an aircraft as the lines a feeder would have sent for it: identification, position,
+    /// velocity and identity, each only if the aircraft has the values for it.
+    #[must_use]
+    pub fn from_aircraft(aircraft: &JSONMessage) -> Vec<SBSLine> {
+        let timestamp = match aircraft.timestamp.get_time() {
+            timestamp if timestamp > 0.0 => timestamp,
+            _ => get_time_as_f64(),
+        };
+
+        [
+            (
+                SBSTransmissionType::Identification,
+                aircraft.calculated_best_flight_id.is_some(),
+            ),
+            (
+                SBSTransmissionType::AirbornePosition,
+                aircraft.latitude.is_some() && aircraft.longitude.is_some(),
+            ),
+            (
+                SBSTransmissionType::AirborneVelocity,
+                aircraft.ground_speed.is_some() || aircraft.true_track_over_ground.is_some(),
+            ),
+            (
+                SBSTransmissionType::SurveillanceIdentity,
+                aircraft.transponder_squawk_code.is_some(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, known)| *known)
+        .map(|(transmission_type, _)| {
+            Self::new(transmission_type, aircraft, timestamp).only_transmitted_fields()
+        })
+        .collect()
+    }
+
+    /// The line with the line ending that feeders send
+    #[must_use]
+    pub fn to_string_newline(&self) -> String {
+        format!("{self}{SBS_LINE_ENDING}")
+    }
+
+    fn new(transmission_type: SBSTransmissionType, aircraft: &JSONMessage, timestamp: f64) -> Self {
+        let (altitude, on_ground) = match &aircraft.barometric_altitude {
+            Some(Altitude::U16(altitude)) => (Some(i32::from(*altitude)), Some(false)),
+            Some(Altitude::U32(altitude)) => (i32::try_from(*altitude).ok(), Some(false)),
+            Some(Altitude::String(altitude)) if altitude == "ground" => (None, Some(true)),
+            _ => (None, None),
+        };
+        let squawk = aircraft
+            .transponder_squawk_code
+            .as_ref()
+            .map(ToString::to_string);
+        let emergency = match (&aircraft.emergency, &squawk) {
+            (_, Some(squawk)) if EMERGENCY_SQUAWKS.contains(&squawk.as_str()) => Some(true),
+            (Some(emergency), _) => Some(*emergency != Emergency::None),
+            (None, Some(_)) => Some(false),
+            (None, None) => None,
+        };
+
+        SBSLine {
+            transmission_type,
+            transponder_hex: aircraft.transponder_hex.to_string(),
+            callsign: aircraft
+                .calculated_best_flight_id
+                .as_ref()
+                .map(|callsign| callsign.to_string().trim().to_string()),
+            altitude,
+            ground_speed: aircraft.ground_speed.as_ref().map(Speed::get_speed),
+            track: aircraft
+                .true_track_over_ground
+                .as_ref()
+                .and_then(Heading::get_heading),
+            latitude: aircraft.latitude.as_ref().map(|latitude| latitude.latitude),
+            longitude: aircraft
+                .longitude
+                .as_ref()
+                .map(|longitude| longitude.longitude),
+            vertical_rate: aircraft
+                .barometric_altitude_rate
+                .as_ref()
+                .map(BaroRate::get_baro_rate),
+            squawk,
+            alert: aircraft.flight_status.map(|status| {
+                matches!(
+                    status,
+                    SurveillanceStatus::PermanentAlert | SurveillanceStatus::TemporaryAlert
+                )
+            }),
+            emergency,
+            spi: aircraft
+                .flight_status_special_position_id_bit
+                .map(|spi| spi != 0),
+            on_ground,
+            timestamp,
+        }
+    }
+
+    /// Clear the fields the transmission type doesn't carry
+    fn only_transmitted_fields(mut self) -> Self {
+        let (callsign, altitude, velocity, position, squawk, flags) = match self.transmission_type {
+            SBSTransmissionType::Identification => (true, false, false, false, false, false),
+            SBSTransmissionType::SurfacePosition => (false, true, true, true, false, false),
+            SBSTransmissionType::AirbornePosition => (false, true, false, true, false, true),
+            SBSTransmissionType::AirborneVelocity => (false, false, true, false, false, false),
+            SBSTransmissionType::SurveillanceAltitude => (false, true, false, false, false, true),
+            SBSTransmissionType::SurveillanceIdentity => (false, true, false, false, true, true),
+            SBSTransmissionType::AirToAir => (false, true, false, false, false, false),
+            SBSTransmissionType::AllCallReply => (false, false, false, false, false, false),
+        };
+
+        if !callsign {
+            self.callsign = None;
+        }
+        if !altitude {
+            self.altitude = None;
+        }
+        if !velocity {
+            self.ground_speed = None;
+            self.track = None;
+        }
+        if !position {
+            self.latitude = None;
+            self.longitude = None;
+        }
+        // vertical rate only comes with velocity
+        if self.transmission_type != SBSTransmissionType::AirborneVelocity {
+            self.vertical_rate = None;
+        }
+        if !squawk {
+            self.squawk = None;
+        }
+        if !flags {
+            self.alert = None;
+            self.spi = None;
+        }
+        // emergency is only reported with a squawk or a position
+        if !squawk && self.transmission_type != SBSTransmissionType::AirbornePosition {
+            self.emergency = None;
+        }
+        // identification and velocity don't say whether the aircraft is on the ground
+        if matches!(
+            self.transmission_type,
+            SBSTransmissionType::Identification | SBSTransmissionType::AirborneVelocity
+        ) {
+            self.on_ground = None;
+        }
+
+        self
+    }
+}
+
+/// Transmission type, flight status and on ground flag of an extended squitter
+fn extended_squitter_type(
+    me: &ME,
+) -> Option<(SBSTransmissionType, Option<FlightStatus>, Option<bool>)> {
+    match me {
+        ME::AircraftIdentification(..) => Some((SBSTransmissionType::Identification, None, None)),
+        ME::SurfacePosition(..) => Some((SBSTransmissionType::SurfacePosition, None, Some(true))),
+        ME::AirbornePositionBaroAltitude(..) | ME::AirbornePositionGNSSAltitude(..) => {
+            Some((SBSTransmissionType::AirbornePosition, None, Some(false)))
+        }
+        ME::AirborneVelocity(..) => Some((SBSTransmissionType::AirborneVelocity, None, None)),
+        _ => None,
+    }
+}
+
+/// The SPI codes don't say whether the aircraft is airborne
+const fn flight_status_on_ground(fs: FlightStatus) -> Option<bool> {
+    match fs {
+        FlightStatus::NoAlertNoSPIAirborne | FlightStatus::AlertNoSPIAirborne => Some(false),
+        FlightStatus::NoAlertNoSPIOnGround | FlightStatus::AlertNoSPIOnGround => Some(true),
+        _ => None,
+    }
+}
+
+fn optional_field<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn flag_field(flag: Option<bool>) -> &'static str {
+    match flag {
+        Some(true) => SBS_FLAG_SET,
+        Some(false) => SBS_FLAG_NOT_SET,
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::{raw::NewAdsbRawMessage, sbs::NewSBSMessage};
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn encode_sbs_lines() {
+        "debug".enable_logging();
+
+        let mut aircraft = JSONMessage::new("4840D6".to_string());
+        aircraft.calculated_best_flight_id = Some("KLM1023 ".to_string().into());
+        aircraft.barometric_altitude = Some(38000_u32.into());
+        aircraft.transponder_squawk_code = Some("7700".into());
+
+        let identification = "8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap();
+        let line = SBSLine::from_raw(&identification, &aircraft).unwrap();
+        assert_eq!(line.transmission_type, SBSTransmissionType::Identification);
+        assert_eq!(line.callsign.as_deref(), Some("KLM1023"));
+        assert_eq!(line.altitude, None);
+        let fields: Vec<String> = line
+            .to_string_newline()
+            .split(',')
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(fields.len(), 22);
+        assert_eq!(fields[1], "1");
+        assert_eq!(fields[4], "4840D6");
+        assert_eq!(fields[10], "KLM1023");
+        assert_eq!(fields[21], "\r\n");
+
+        // DF4 with no alert, no SPI and airborne
+        let altitude_reply = "2000183859C38D".to_adsb_raw().unwrap();
+        let line = SBSLine::from_raw(&altitude_reply, &aircraft).unwrap();
+        assert_eq!(
+            line.transmission_type,
+            SBSTransmissionType::SurveillanceAltitude
+        );
+        assert_eq!(line.altitude, Some(38000));
+        assert_eq!(line.alert, Some(false));
+        assert_eq!(line.spi, Some(false));
+        assert_eq!(line.on_ground, Some(false));
+        assert_eq!(line.squawk, None);
+
+        let lines = SBSLine::from_aircraft(&aircraft);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1].transmission_type,
+            SBSTransmissionType::SurveillanceIdentity
+        );
+        assert_eq!(lines[1].emergency, Some(true));
+
+        // what we write reads back the same
+        let decoded = lines[1].to_string().to_sbs().unwrap();
+        assert_eq!(
+            decoded.transponder_squawk_code,
+            aircraft.transponder_squawk_code
+        );
+        assert_eq!(decoded.barometric_altitude, aircraft.barometric_altitude);
+        assert_eq!(decoded.emergency, Some(Emergency::General));
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// Work out the format of a connection from its first bytes. Every format gets a score for how much
+// of the sample it explains with complete frames, so a stream that starts mid-frame or carries a
+// bit of noise still ranks correctly, and a 7 or 14 byte frame that parses as both Beast and raw
+// doesn't have to be guessed at one frame at a time.
+
+use super::encode_adsb_beast_input::complete_beast_frame_end;
+use super::message_stream::InputFormat;
+
+const BEAST_START_CHARACTER: u8 = 0x1a;
+const AVR_START_CHARACTER: u8 = b'*';
+const AVR_MLAT_START_CHARACTER: u8 = b'@';
+const AVR_SIGNAL_START_CHARACTER: u8 = b'%';
+const AVR_END_CHARACTER: u8 = b';';
+/// 48 bit MLAT timestamp of an `@` or `%` line
+const AVR_MLAT_TIMESTAMP_CHARACTERS: usize = 12;
+/// Signal level after the timestamp of a `%` line
+const AVR_SIGNAL_CHARACTERS: usize = 2;
+/// Mode A/C, short and long frames
+const AVR_FRAME_CHARACTERS: [usize; 3] = [4, 14, 28];
+const SBS_MESSAGE_TYPES: [&[u8]; 6] = [b"MSG,", b"SEL,", b"ID,", b"AIR,", b"STA,", b"CLK,"];
+/// The shortest SBS messages still have 10 fields
+const SBS_MIN_FIELDS: usize = 10;
+/// Below this share of the sample a format is not worth considering
+const MIN_CONFIDENCE: f64 = 0.5;
+
+/// How well a sample of a stream fits one format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatMatch {
+    pub format: InputFormat,
+    /// Complete frames of this format in the sample
+    pub frames: usize,
+    /// Share of the sample covered by those frames, from 0.0 to 1.0
+    pub confidence: f64,
+}
+
+/// Score the sample against every format. The best match comes first, formats without a single frame are left out.
+#[must_use]
+pub fn rank_input_formats(sample: &[u8]) -> Vec<FormatMatch> {
+    if sample.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranked: Vec<FormatMatch> = [
+        (InputFormat::Beast, scan_beast(sample)),
+        (InputFormat::Avr, scan_lines(sample, is_avr_line)),
+        (InputFormat::Sbs, scan_lines(sample, is_sbs_line)),
+        (InputFormat::JSONLines, scan_lines(sample, is_json_line)),
+    ]
+    .into_iter()
+    .filter(|(_, (frames, _))| *frames > 0)
+    .map(|(format, (frames, covered))| FormatMatch {
+        format,
+        frames,
+        #[allow(clippy::cast_precision_loss)]
+        confidence: covered as f64 / sample.len() as f64,
+    })
+    .collect();
+
+    ranked.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    ranked
+}
+
+/// The format of the sample, if the best match has at least `min_frames` frames and explains most of it.
+#[must_use]
+pub fn detect_input_format(sample: &[u8], min_frames: usize) -> Option<InputFormat> {
+    rank_input_formats(sample)
+        .first()
+        .filter(|best| best.frames >= min_frames && best.confidence >= MIN_CONFIDENCE)
+        .map(|best| best.format)
+}
+
+/// Complete frames and the bytes they cover. A frame only counts if the next one starts right after it.
+fn scan_beast(sample: &[u8]) -> (usize, usize) {
+    let mut frames = 0;
+    let mut covered = 0;
+    let mut position = 0;
+
+    while position < sample.len() {
+        if sample[position] == BEAST_START_CHARACTER
+            && let Some(end) = complete_beast_frame_end(sample, position)
+            && sample
+                .get(end)
+                .is_none_or(|next| *next == BEAST_START_CHARACTER)
+        {
+            frames += 1;
+            covered += end - position;
+            position = end;
+        } else {
+            position += 1;
+        }
+    }
+
+    (frames, covered)
+}
+
+/// Complete lines that pass `is_frame` and the bytes they cover, line ending included.
+fn scan_lines(sample: &[u8], is_frame: fn(&[u8]) -> bool) -> (usize, usize) {
+    let mut frames = 0;
+    let mut covered = 0;
+
+    // the part after the last newline is incomplete
+    for line in sample.split_inclusive(|byte| *byte == b'\n') {
+        if !line.ends_with(b"\n") {
+            break;
+        }
+
+        if is_frame(line.trim_ascii_end()) {
+            frames += 1;
+            covered += line.len();
+        }
+    }
+
+    (frames, covered)
+}
+
+/// `*<frame>;`, `@<timestamp><frame>;` or `%<timestamp><signal><frame>;`
+fn is_avr_line(line: &[u8]) -> bool {
+    let hex = match line {
+        [AVR_START_CHARACTER, hex @ .., AVR_END_CHARACTER] => hex,
+        [AVR_MLAT_START_CHARACTER, hex @ .., AVR_END_CHARACTER]
+            if hex.len() > AVR_MLAT_TIMESTAMP_CHARACTERS =>
+        {
+            &hex[AVR_MLAT_TIMESTAMP_CHARACTERS..]
+        }
+        [AVR_SIGNAL_START_CHARACTER, hex @ .., AVR_END_CHARACTER]
+            if hex.len() > AVR_MLAT_TIMESTAMP_CHARACTERS + AVR_SIGNAL_CHARACTERS =>
+        {
+            &hex[AVR_MLAT_TIMESTAMP_CHARACTERS + AVR_SIGNAL_CHARACTERS..]
+        }
+        _ => return false,
+    };
+
+    AVR_FRAME_CHARACTERS.contains(&hex.len())
+        && line[1..line.len() - 1].iter().all(u8::is_ascii_hexdigit)
+}
+
+fn is_sbs_line(line: &[u8]) -> bool {
+    SBS_MESSAGE_TYPES
+        .iter()
+        .any(|message_type| line.starts_with(message_type))
+        && line.split(|byte| *byte == b',').count() >= SBS_MIN_FIELDS
+}
+
+fn is_json_line(line: &[u8]) -> bool {
+    line.first() == Some(&b'{')
+        && serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(line).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn detect_formats() {
+        "debug".enable_logging();
+
+        // starts in the middle of a frame
+        let beast = [
+            0xB5, 0x94, 0x6E, 0xDE, 0x10, 0x8E, 0x1a, 0x32, 0x01, 0xB5, 0xBF, 0x53, 0x9F, 0x89,
+            0x6D, 0x5D, 0xA4, 0x4C, 0xFD, 0x32, 0x04, 0x1A, 0x1A, 0x1a, 0x31, 0, 0, 0, 0, 0, 0,
+            0x40, 0x12, 0x34,
+        ];
+        let ranked = rank_input_formats(&beast);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].format, InputFormat::Beast);
+        assert_eq!(ranked[0].frames, 2);
+        assert_eq!(detect_input_format(&beast, 2), Some(InputFormat::Beast));
+        assert_eq!(detect_input_format(&beast, 3), None);
+
+        let avr =
+            b"*8D4840D6202CC371C32CE0576098;\r\n@0001B5BF520C5D4840D6F8740F;\n%0001B5BF520C9F5424;\n*8D4840D6";
+        let ranked = rank_input_formats(avr);
+        assert_eq!(ranked[0].format, InputFormat::Avr);
+        assert_eq!(ranked[0].frames, 3);
+
+        let sbs =
+            b"MSG,8,1,1,4840D6,1,2024/01/01,00:00:00.000,2024/01/01,00:00:00.000,,,,,,,,,,,,0\n";
+        assert_eq!(detect_input_format(sbs, 1), Some(InputFormat::Sbs));
+
+        let json = b"{\"hex\":\"a40d4c\",\"alt_baro\":45000}\n{\"hex\":\"ac9f65\"}\n";
+        assert_eq!(detect_input_format(json, 2), Some(InputFormat::JSONLines));
+
+        // a lone frame in a lot of noise
+        let noise = b"*5424;\nnot a frame at all, just some text that happens to be on the port\n";
+        assert_eq!(rank_input_formats(noise)[0].frames, 1);
+        assert_eq!(detect_input_format(noise, 1), None);
+
+        assert!(rank_input_formats(&[0u8; 64]).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// The streaming framers of every input format share their buffer, the bookkeeping of dropped
+// bytes and the `Decoder` implementation. A format only says how frames are split off the buffer.
+
+use std::marker::PhantomData;
+
+use bytes::{Buf, Bytes, BytesMut};
+use tokio_util::codec::Decoder;
+
+/// What a `FrameFormat` found at the start of the buffer
+#[derive(Debug, PartialEq)]
+pub enum FrameScan {
+    /// More bytes are needed
+    Incomplete,
+    /// The bytes can't be part of a frame and are dropped
+    Drop(usize),
+    /// The bytes are dropped, and a frame starts right after them
+    Resync(usize),
+    /// The bytes sit between frames, like a line ending, and are skipped without counting them
+    Skip(usize),
+    /// A complete frame, already split off the buffer
+    Frame(Bytes),
+}
+
+/// How the frames of one input format are split off a stream
+pub trait FrameFormat {
+    /// Look at the start of a buffer that isn't empty. `lost_sync` is set while the bytes in front
+    /// were dropped and no frame has been found since. Only `Frame` takes bytes off the buffer, the
+    /// framer does that for the others, and their count is never 0.
+    fn scan(buffer: &mut BytesMut, lost_sync: bool) -> FrameScan;
+}
+
+/// Streaming framer. It owns its buffer, so chunks can be pushed as they come off the socket.
+///
+/// The framer also implements `Decoder`, to be used with a `FramedRead`.
+#[derive(Debug)]
+pub struct ADSBFramer<F: FrameFormat> {
+    buffer: BytesMut,
+    bytes_dropped: u64,
+    resyncs: u64,
+    lost_sync: bool,
+    format: PhantomData<F>,
+}
+
+impl<F: FrameFormat> Default for ADSBFramer<F> {
+    fn default() -> Self {
+        ADSBFramer {
+            buffer: BytesMut::new(),
+            bytes_dropped: 0,
+            resyncs: 0,
+            lost_sync: false,
+            format: PhantomData,
+        }
+    }
+}
+
+impl<F: FrameFormat> ADSBFramer<F> {
+    #[must_use]
+    pub fn new() -> ADSBFramer<F> {
+        ADSBFramer::default()
+    }
+
+    /// Append a chunk of the stream. It doesn't have to end on a frame boundary.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// The next complete frame, or `None` if more bytes are needed.
+    pub fn next_frame(&mut self) -> Option<Bytes> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let frame = self.frame_from(&mut buffer);
+        self.buffer = buffer;
+        frame
+    }
+
+    /// Bytes thrown away because they were not part of a complete frame
+    #[must_use]
+    pub const fn bytes_dropped(&self) -> u64 {
+        self.bytes_dropped
+    }
+
+    /// How often the framer lost track of the frame boundaries and had to look for a new frame
+    #[must_use]
+    pub const fn resyncs(&self) -> u64 {
+        self.resyncs
+    }
+
+    fn frame_from(&mut self, buffer: &mut BytesMut) -> Option<Bytes> {
+        while !buffer.is_empty() {
+            match F::scan(buffer, self.lost_sync) {
+                FrameScan::Incomplete => return None,
+                FrameScan::Drop(count) => self.drop_bytes(buffer, count),
+                FrameScan::Resync(count) => {
+                    self.drop_bytes(buffer, count);
+                    self.lost_sync = false;
+                }
+                FrameScan::Skip(count) => buffer.advance(count),
+                FrameScan::Frame(frame) => {
+                    self.lost_sync = false;
+                    return Some(frame);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn drop_bytes(&mut self, buffer: &mut BytesMut, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        if !self.lost_sync {
+            self.resyncs += 1;
+            self.lost_sync = true;
+        }
+        self.bytes_dropped += count as u64;
+        buffer.advance(count);
+    }
+}
+
+impl<F: FrameFormat> Decoder for ADSBFramer<F> {
+    type Item = Bytes;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self.frame_from(src))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let frame = self.frame_from(src);
+        if frame.is_none() {
+            // the stream ended in the middle of a frame
+            self.drop_bytes(src, src.len());
+        }
+        Ok(frame)
+    }
+}
@@ -0,0 +1,448 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::fmt::{self, Formatter};
+
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use tokio::io::AsyncRead;
+use tokio_util::codec::{Decoder, FramedRead};
+
+use crate::decoders::{
+    beast::{AdsbBeastMessage, split_receiver_id},
+    beaststatus::BeastStatusMessage,
+    helpers::error_correction::ErrorCorrection,
+    modeac::ModeACMessage,
+    raw::AdsbRawMessage,
+    sbs::NewSBSMessage,
+};
+use crate::error_handling::adsb_stream_error::ADSBStreamError;
+use crate::{ADSBMessage, MessageResult, correct_frame};
+
+use super::{
+    encode_adsb_beast_input::ADSBBeastFramer, encode_adsb_json_input::ADSBJSONFramer,
+    encode_adsb_raw_input::ADSBRawFramer, encode_adsb_sbs_input::ADSBSBSFramer,
+    format_detection::detect_input_format,
+};
+
+const BEAST_MODEAC_FRAME_START_CHARACTER: u8 = 0x31;
+const BEAST_STATUS_FRAME_START_CHARACTER: u8 = 0x34;
+const AVR_MODEAC_FRAME_BYTES: usize = 2;
+/// A Mode A/C frame from a `@` or `%` line, with its Beast style header
+const AVR_TIMESTAMPED_MODEAC_FRAME_BYTES: usize = 10;
+/// Give up on finding out what the input is after this many bytes. Only complete lines and frames
+/// count, so this has to hold a few of the longest lines, readsb's JSON lines can be well over 1 KiB.
+const AUTO_DETECT_MAX_BYTES: usize = 4096;
+/// Settle on a format before `AUTO_DETECT_MAX_BYTES` once it has this many frames
+const AUTO_DETECT_FRAMES: usize = 3;
+
+/// The wire format of a stream
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum InputFormat {
+    /// Work it out from the first bytes of the stream, then stick with it
+    #[default]
+    AutoDetect,
+    /// Beast binary, readsb port 30005
+    Beast,
+    /// AVR hex lines, readsb port 30002
+    Avr,
+    /// SBS CSV lines, readsb port 30003
+    Sbs,
+    /// One JSON object per line, readsb port 30047
+    JSONLines,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InputFormat::AutoDetect => write!(f, "auto-detect"),
+            InputFormat::Beast => write!(f, "Beast"),
+            InputFormat::Avr => write!(f, "AVR"),
+            InputFormat::Sbs => write!(f, "SBS"),
+            InputFormat::JSONLines => write!(f, "JSON lines"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Framer {
+    Beast(ADSBBeastFramer),
+    Avr(ADSBRawFramer),
+    Sbs(ADSBSBSFramer),
+    JSONLines(ADSBJSONFramer),
+}
+
+impl Framer {
+    fn new(format: InputFormat) -> Option<Framer> {
+        match format {
+            InputFormat::AutoDetect => None,
+            InputFormat::Beast => Some(Framer::Beast(ADSBBeastFramer::new())),
+            InputFormat::Avr => Some(Framer::Avr(ADSBRawFramer::new())),
+            InputFormat::Sbs => Some(Framer::Sbs(ADSBSBSFramer::new())),
+            InputFormat::JSONLines => Some(Framer::JSONLines(ADSBJSONFramer::new())),
+        }
+    }
+
+    fn decode(&mut self, src: &mut BytesMut, eof: bool) -> std::io::Result<Option<Bytes>> {
+        match (self, eof) {
+            (Framer::Beast(framer), false) => framer.decode(src),
+            (Framer::Beast(framer), true) => framer.decode_eof(src),
+            (Framer::Avr(framer), false) => framer.decode(src),
+            (Framer::Avr(framer), true) => framer.decode_eof(src),
+            (Framer::Sbs(framer), false) => framer.decode(src),
+            (Framer::Sbs(framer), true) => framer.decode_eof(src),
+            (Framer::JSONLines(framer), false) => framer.decode(src),
+            (Framer::JSONLines(framer), true) => framer.decode_eof(src),
+        }
+    }
+
+    const fn bytes_dropped(&self) -> u64 {
+        match self {
+            Framer::Beast(framer) => framer.bytes_dropped(),
+            Framer::Avr(framer) => framer.bytes_dropped(),
+            Framer::Sbs(framer) => framer.bytes_dropped(),
+            Framer::JSONLines(framer) => framer.bytes_dropped(),
+        }
+    }
+}
+
+/// Codec that frames a stream and decodes every frame to an `ADSBMessage`.
+///
+/// Bytes the framer had to drop are reported as an `ADSBStreamError::FramingError` item, as are frames that don't
+/// decode, so the stream keeps going after bad input.
+#[derive(Debug)]
+pub struct ADSBMessageCodec {
+    format: InputFormat,
+    framer: Option<Framer>,
+    reject_corrupt_frames: bool,
+    error_correction: ErrorCorrection,
+    /// a frame found in the same call as dropped bytes, handed out after the error
+    pending: Option<Bytes>,
+}
+
+impl ADSBMessageCodec {
+    #[must_use]
+    pub fn new(format: InputFormat) -> ADSBMessageCodec {
+        ADSBMessageCodec {
+            format,
+            framer: Framer::new(format),
+            reject_corrupt_frames: true,
+            error_correction: ErrorCorrection::Disabled,
+            pending: None,
+        }
+    }
+
+    /// Hand out frames that fail the CRC check instead of an error. See `AdsbRawMessage::crc_status`.
+    #[must_use]
+    pub const fn with_reject_corrupt_frames(mut self, reject_corrupt_frames: bool) -> Self {
+        self.reject_corrupt_frames = reject_corrupt_frames;
+        self
+    }
+
+    /// Repair up to `error_correction` flipped bits in DF17/DF18 frames that fail the CRC check before they are
+    /// checked. Off by default.
+    #[must_use]
+    pub const fn with_error_correction(mut self, error_correction: ErrorCorrection) -> Self {
+        self.error_correction = error_correction;
+        self
+    }
+
+    /// The format of the stream. With `AutoDetect` this changes once the format is known.
+    #[must_use]
+    pub const fn format(&self) -> InputFormat {
+        self.format
+    }
+
+    fn decode_frame(&self, frame: &[u8]) -> MessageResult<ADSBMessage> {
+        match self.format {
+            InputFormat::Beast => {
+                let (_, body) = split_receiver_id(frame);
+                match body.first() {
+                    Some(&BEAST_MODEAC_FRAME_START_CHARACTER) => {
+                        Ok(ADSBMessage::ModeACMessage(ModeACMessage::from_bytes(body)?))
+                    }
+                    Some(&BEAST_STATUS_FRAME_START_CHARACTER) => Ok(
+                        ADSBMessage::BeastStatusMessage(BeastStatusMessage::from_bytes(frame)?),
+                    ),
+                    _ => correct_frame(
+                        ADSBMessage::AdsbBeastMessage(AdsbBeastMessage::from_bytes(frame)?),
+                        frame,
+                        self.error_correction,
+                        self.reject_corrupt_frames,
+                    ),
+                }
+            }
+            InputFormat::Avr
+                if matches!(
+                    frame.len(),
+                    AVR_MODEAC_FRAME_BYTES | AVR_TIMESTAMPED_MODEAC_FRAME_BYTES
+                ) =>
+            {
+                Ok(ADSBMessage::ModeACMessage(ModeACMessage::from_bytes(
+                    frame,
+                )?))
+            }
+            InputFormat::Avr => correct_frame(
+                ADSBMessage::AdsbRawMessage(AdsbRawMessage::from_avr_bytes(frame)?),
+                frame,
+                self.error_correction,
+                self.reject_corrupt_frames,
+            ),
+            InputFormat::JSONLines => Ok(serde_json::from_slice(frame)?),
+            InputFormat::Sbs => Ok(ADSBMessage::SBSMessage(frame.to_sbs()?)),
+            InputFormat::AutoDetect => Err(ADSBStreamError::UnsupportedFormat {
+                format: self.format.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    fn next_item(
+        &mut self,
+        src: &mut BytesMut,
+        eof: bool,
+    ) -> std::io::Result<Option<MessageResult<ADSBMessage>>> {
+        if let Some(frame) = self.pending.take() {
+            return Ok(Some(self.decode_frame(&frame)));
+        }
+
+        if self.framer.is_none() {
+            // a short stream that ends is as good as a full sample
+            let settled = eof || src.len() >= AUTO_DETECT_MAX_BYTES;
+            let min_frames = if settled { 1 } else { AUTO_DETECT_FRAMES };
+            match detect_input_format(src, min_frames) {
+                Some(format) => {
+                    self.format = format;
+                    self.framer = Framer::new(format);
+                }
+                None if settled && !src.is_empty() => {
+                    let bytes = src.len();
+                    src.clear();
+                    return Ok(Some(
+                        Err(ADSBStreamError::UndetectedFormat { bytes }.into()),
+                    ));
+                }
+                None => return Ok(None),
+            }
+        }
+
+        let Some(framer) = self.framer.as_mut() else {
+            return Ok(None);
+        };
+
+        let dropped_before = framer.bytes_dropped();
+        let frame = framer.decode(src, eof)?;
+        let bytes_dropped = framer.bytes_dropped() - dropped_before;
+
+        if bytes_dropped > 0 {
+            self.pending = frame;
+            return Ok(Some(Err(ADSBStreamError::FramingError {
+                format: self.format.to_string(),
+                bytes_dropped,
+            }
+            .into())));
+        }
+
+        Ok(frame.map(|frame| self.decode_frame(&frame)))
+    }
+}
+
+impl Decoder for ADSBMessageCodec {
+    type Item = MessageResult<ADSBMessage>;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.next_item(src, false)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.next_item(src, true)
+    }
+}
+
+/// Turn any `AsyncRead`, like a `TcpStream`, into a stream of decoded messages.
+///
+/// Read errors, framing errors and frames that don't decode all come out as `Err` items, the stream only ends with the input.
+/// ```rust, no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use futures::StreamExt;
+/// use sdre_rust_adsb_parser::helpers::message_stream::{InputFormat, message_stream};
+///
+/// let socket = tokio::net::TcpStream::connect("127.0.0.1:30005").await?;
+/// let mut messages = Box::pin(message_stream(socket, InputFormat::AutoDetect));
+/// while let Some(message) = messages.next().await {
+///     match message {
+///         Ok(message) => println!("{message}"),
+///         Err(e) => eprintln!("{e}"),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn message_stream<R: AsyncRead>(
+    reader: R,
+    format: InputFormat,
+) -> impl Stream<Item = MessageResult<ADSBMessage>> {
+    message_stream_with_codec(reader, ADSBMessageCodec::new(format))
+}
+
+/// Same as `message_stream`, with a codec set up by the caller.
+pub fn message_stream_with_codec<R: AsyncRead>(
+    reader: R,
+    codec: ADSBMessageCodec,
+) -> impl Stream<Item = MessageResult<ADSBMessage>> {
+    FramedRead::new(reader, codec).map(|item| item.unwrap_or_else(|e| Err(e.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdre_rust_logging::SetupLogging;
+
+    async fn collect(input: &[u8], format: InputFormat) -> Vec<MessageResult<ADSBMessage>> {
+        message_stream(input, format).collect().await
+    }
+
+    #[tokio::test]
+    async fn stream_avr() {
+        "debug".enable_logging();
+
+        let input = b"*8D4840D6202CC371C32CE0576098;\n*5424;\n*8D4840D6202CC371C32CE1576098;\nFF*5D4840D6F8740F;\n";
+        let messages = collect(input, InputFormat::Avr).await;
+
+        assert_eq!(messages.len(), 5);
+        assert!(matches!(messages[0], Ok(ADSBMessage::AdsbRawMessage(_))));
+        assert!(matches!(messages[1], Ok(ADSBMessage::ModeACMessage(_))));
+        // the corrupt frame and the two stray bytes
+        assert!(messages[2].is_err());
+        assert!(matches!(
+            messages[3],
+            Err(
+                crate::error_handling::deserialization_error::DeserializationError::ADSBStreamError(
+                    ADSBStreamError::FramingError {
+                        bytes_dropped: 2,
+                        ..
+                    }
+                )
+            )
+        ));
+        assert!(matches!(messages[4], Ok(ADSBMessage::AdsbRawMessage(_))));
+    }
+
+    #[tokio::test]
+    async fn stream_error_correction() {
+        "debug".enable_logging();
+
+        // one bit flipped in the ME field of both frames
+        let avr = b"*8D4840D6202CC371C32CE1576098;\n";
+        let beast = [
+            0x1a_u8, 0x33, 0x01, 0xB5, 0xBF, 0x52, 0x0C, 0x77, 0x1D, 0x8D, 0xAB, 0x97, 0x40, 0x59,
+            0x2F, 0x16, 0x99, 0xB5, 0x94, 0x6E, 0xDE, 0x10, 0x8E,
+        ];
+        assert!(collect(avr, InputFormat::Avr).await[0].is_err());
+
+        let codec = ADSBMessageCodec::new(InputFormat::Avr)
+            .with_error_correction(ErrorCorrection::SingleBit);
+        let messages: Vec<_> = message_stream_with_codec(&avr[..], codec).collect().await;
+        assert!(
+            matches!(&messages[0], Ok(ADSBMessage::AdsbRawMessage(message)) if !message.crc_status().is_corrupt())
+        );
+
+        let codec = ADSBMessageCodec::new(InputFormat::Beast)
+            .with_error_correction(ErrorCorrection::SingleBit);
+        let messages: Vec<_> = message_stream_with_codec(&beast[..], codec).collect().await;
+        assert!(
+            matches!(&messages[0], Ok(ADSBMessage::AdsbBeastMessage(message)) if message.get_signal_level() == 0x1D && !message.raw_message.crc_status().is_corrupt())
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_sbs() {
+        "debug".enable_logging();
+
+        let input = b"MSG,1,1,1,4840D6,1,,,,,KLM1023,,,,,,,,,,,0\r\nAIR,,1,1,4840D6,1,,,,,,,,,,,,,,,,\nMSG,3,1,1,4840D6,1,,,,,,38000,,,52.25720,3.91937,,,0,0,0,0\n";
+        let messages = collect(input, InputFormat::Sbs).await;
+
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[0], Ok(ADSBMessage::SBSMessage(_))));
+        assert!(messages[1].is_err());
+        assert!(
+            matches!(&messages[2], Ok(ADSBMessage::SBSMessage(message)) if message.latitude.is_some())
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_auto_detect() {
+        "debug".enable_logging();
+
+        let beast = [
+            0x1a_u8, 0x33, 0x01, 0xB5, 0xBF, 0x52, 0x0C, 0x77, 0x1D, 0x8D, 0xAB, 0x97, 0x40, 0x59,
+            0x2F, 0x16, 0x98, 0xB5, 0x94, 0x6E, 0xDE, 0x10, 0x8E, 0x1a, 0x31, 0, 0, 0, 0, 0, 0,
+            0x40, 0x12, 0x34,
+        ];
+        let messages = collect(&beast, InputFormat::AutoDetect).await;
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[0], Ok(ADSBMessage::AdsbBeastMessage(_))));
+        assert!(matches!(messages[1], Ok(ADSBMessage::ModeACMessage(_))));
+
+        let aircraft = include_str!("../../test_data/json_1.json")
+            .lines()
+            .next()
+            .unwrap()
+            .trim_end_matches(',');
+        let json = format!("{aircraft}\n{aircraft}\n");
+        let messages = collect(json.as_bytes(), InputFormat::AutoDetect).await;
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[1], Ok(ADSBMessage::JSONMessage(_))));
+
+        let mut codec = ADSBMessageCodec::new(InputFormat::AutoDetect);
+        let mut buffer = BytesMut::from(&b"*8D4840D6202CC371C32CE0576098;\n*5424;\n"[..]);
+        // not enough frames to be sure yet
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        assert_eq!(codec.format(), InputFormat::AutoDetect);
+        buffer.extend_from_slice(b"*5D4840D6F8740F;\n");
+        assert!(codec.decode(&mut buffer).unwrap().unwrap().is_ok());
+        assert_eq!(codec.format(), InputFormat::Avr);
+
+        let messages = collect(&[0u8; AUTO_DETECT_MAX_BYTES], InputFormat::AutoDetect).await;
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].is_err());
+    }
+
+    #[test]
+    fn auto_detect_long_lines() {
+        "debug".enable_logging();
+
+        let aircraft = include_str!("../../test_data/json_1.json")
+            .lines()
+            .next()
+            .unwrap()
+            .trim_end_matches(',');
+        // whitespace inside the object brings it up to the length of a busy readsb line
+        let line = format!("{{{:700}{}\n", "", &aircraft[1..]);
+
+        // every line comes in over several reads, none of them holding a whole line
+        let mut codec = ADSBMessageCodec::new(InputFormat::AutoDetect);
+        let mut buffer = BytesMut::new();
+        let mut messages = Vec::new();
+        for _ in 0..AUTO_DETECT_FRAMES {
+            for chunk in line.as_bytes().chunks(500) {
+                buffer.extend_from_slice(chunk);
+                while let Some(message) = codec.decode(&mut buffer).unwrap() {
+                    messages.push(message);
+                }
+            }
+        }
+
+        assert_eq!(codec.format(), InputFormat::JSONLines);
+        assert_eq!(messages.len(), AUTO_DETECT_FRAMES);
+        assert!(
+            messages
+                .iter()
+                .all(|message| matches!(message, Ok(ADSBMessage::JSONMessage(_))))
+        );
+    }
+}
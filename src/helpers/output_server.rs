@@ -0,0 +1,134 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::io;
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::broadcast::{self, Receiver, Sender, error::RecvError};
+use tokio::task::JoinHandle;
+
+use super::encode_adsb_sbs_output::SBSLine;
+
+/// Lines held for a client that is not keeping up before it starts missing them
+const OUTPUT_SERVER_BACKLOG: usize = 1024;
+
+/// Sends everything it is given to every connected TCP client, like the SBS output port of a feeder.
+///
+/// A client that falls too far behind misses lines instead of holding up the others, a client that
+/// disconnects is dropped. Dropping the server closes all connections.
+pub struct OutputServer {
+    sender: Sender<Bytes>,
+    local_addr: SocketAddr,
+    listener_task: JoinHandle<()>,
+}
+
+impl OutputServer {
+    /// Listen on `addr` and start accepting clients.
+    /// # Errors
+    /// If the address can't be bound, the error is returned.
+    pub async fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let (sender, _) = broadcast::channel(OUTPUT_SERVER_BACKLOG);
+
+        let accept_sender = sender.clone();
+        let listener_task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        debug!("Output client connected from {peer}");
+                        tokio::spawn(serve_client(stream, peer, accept_sender.subscribe()));
+                    }
+                    Err(e) => error!("Failed to accept an output client: {e}"),
+                }
+            }
+        });
+
+        Ok(OutputServer {
+            sender,
+            local_addr,
+            listener_task,
+        })
+    }
+
+    /// The address the server listens on, useful when it was bound to port 0
+    #[must_use]
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Clients being served. A client that went away is only noticed on the next write to it.
+    #[must_use]
+    pub fn client_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Queue `data` for every connected client. Returns the number of clients it was queued for.
+    #[must_use]
+    pub fn send<B: Into<Bytes>>(&self, data: B) -> usize {
+        self.sender.send(data.into()).unwrap_or_default()
+    }
+
+    /// Queue an SBS line, with its line ending, for every connected client. Returns the number of clients it was queued for.
+    #[must_use]
+    pub fn send_sbs_line(&self, line: &SBSLine) -> usize {
+        self.send(line.to_string_newline())
+    }
+}
+
+impl Drop for OutputServer {
+    fn drop(&mut self) {
+        self.listener_task.abort();
+    }
+}
+
+async fn serve_client(mut stream: TcpStream, peer: SocketAddr, mut receiver: Receiver<Bytes>) {
+    loop {
+        match receiver.recv().await {
+            Ok(data) => {
+                if let Err(e) = stream.write_all(&data).await {
+                    debug!("Output client {peer} disconnected: {e}");
+                    return;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Output client {peer} is too slow, skipped {skipped} messages");
+            }
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdre_rust_logging::SetupLogging;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::time::{Duration, sleep};
+
+    #[tokio::test]
+    async fn fan_out_to_clients() {
+        "debug".enable_logging();
+
+        let server = OutputServer::bind("127.0.0.1:0").await.unwrap();
+        let first = TcpStream::connect(server.local_addr()).await.unwrap();
+        let second = TcpStream::connect(server.local_addr()).await.unwrap();
+        while server.client_count() < 2 {
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(server.send("MSG,8,1,1,4840D6,1,,,,,,,,,,,,,,,,0\r\n"), 2);
+
+        for client in [first, second] {
+            let mut line = String::new();
+            BufReader::new(client).read_line(&mut line).await.unwrap();
+            assert!(line.starts_with("MSG,8,1,1,4840D6"));
+        }
+    }
+}
@@ -0,0 +1,904 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// Scripted ADS-B traffic, for testing whatever reads Beast or AVR. Every aircraft flies its route at a
+// constant ground speed and sends what a version 2 transponder would, at the usual rates:
+//
+// airborne position     every 0.5 s, alternating even and odd CPR
+// airborne velocity     every 0.5 s
+// target state          every 1.25 s
+// operational status    every 2.5 s
+// identification        every 5 s
+// aircraft status       every 5 s with the squawk, every second for 24 s after it changes or while
+//                       squawking an emergency code
+//
+// The frames are built from this crate's message types and encoded with `encode_extended_squitter`, so
+// they decode back to the same values.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::Duration;
+
+use tokio::time::{Instant, sleep_until};
+
+use crate::MessageResult;
+use crate::decoders::beast_types::messagetype::MessageType;
+use crate::decoders::common_types::{
+    sda::SystemDesignAssurance, surveillancestatus::SurveillanceStatus,
+};
+use crate::decoders::helpers::cpr_calculators::{
+    Position, get_bearing_from_positions, get_cpr_position_airborne,
+    get_position_from_bearing_and_distance, haversine_distance_position,
+};
+use crate::decoders::raw_types::{
+    adsb::Adsb,
+    adsbversion::ADSBVersion,
+    airbornevelocity::AirborneVelocity,
+    airbornevelocitysubtype::AirborneVelocitySubType,
+    aircraftstatus::AircraftStatus,
+    altitude::Altitude,
+    autopilot_modes::{AltitudeHold, ApproachMode, AutopilotEngaged, LNAV, TCAS, VNAVEngaged},
+    capability::Capability,
+    capabilityclassairborne::CapabilityClassAirborne,
+    cprheaders::CPRFormat,
+    df::DF,
+    emergencyprioritystatus::EmergencyPriorityStatus,
+    emergencystate::EmergencyState,
+    fms::IsFMS,
+    groundspeeddecoding::GroundSpeedDecoding,
+    heading::SelectedHeadingStatus,
+    icao::ICAO,
+    identification::Identification,
+    me::ME,
+    modevalidity::IsValidMode,
+    operationalmode::OperationalMode,
+    operationstatus::OperationStatus,
+    operationstatusairborne::OperationStatusAirborne,
+    sign::Sign,
+    targetstateandstatusinformation::TargetStateAndStatusInformation,
+    typecoding::TypeCoding,
+    verticleratesource::VerticalRateSource,
+};
+
+use super::encode_adsb_beast_output::encode_beast_frame;
+use super::encode_adsb_raw_output::encode_extended_squitter;
+use super::output_server::OutputServer;
+
+const POSITION_INTERVAL: Duration = Duration::from_millis(500);
+const VELOCITY_INTERVAL: Duration = Duration::from_millis(500);
+const TARGET_STATE_INTERVAL: Duration = Duration::from_millis(1250);
+const OPERATIONAL_STATUS_INTERVAL: Duration = Duration::from_millis(2500);
+const IDENTIFICATION_INTERVAL: Duration = Duration::from_secs(5);
+const AIRCRAFT_STATUS_INTERVAL: Duration = Duration::from_secs(5);
+const AIRCRAFT_STATUS_CHANGED_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the aircraft status is sent more often after a squawk change
+const SQUAWK_CHANGED_BROADCAST: Duration = Duration::from_secs(24);
+/// How long the surveillance status shows a temporary alert after a squawk change
+const SQUAWK_CHANGED_ALERT: Duration = Duration::from_secs(18);
+/// Spread the aircraft out so they don't all transmit at the same moment
+const AIRCRAFT_PHASE_STEP_MS: u64 = 137;
+/// The Beast MLAT timestamp is a 12 MHz counter
+const MLAT_TICKS_PER_SECOND: u128 = 12_000_000;
+const MLAT_TIMESTAMP_MASK: u128 = (1 << 48) - 1;
+const KM_PER_NAUTICAL_MILE: f64 = 1.852;
+/// Highest altitude in 25 ft steps
+const MAX_ALTITUDE: f64 = 50_175.0;
+/// Selected heading resolution in degrees
+const SELECTED_HEADING_STEP: f64 = 180.0 / 256.0;
+/// Airborne position with barometric altitude, NIC 8
+const POSITION_TYPE_CODE: u8 = 11;
+/// Aircraft identification, emitter category set A
+const IDENTIFICATION_TYPE_CODE: u8 = 4;
+const SQUAWK_HIJACK: u32 = 0x7500;
+const SQUAWK_RADIO_FAILURE: u32 = 0x7600;
+const SQUAWK_EMERGENCY: u32 = 0x7700;
+
+/// A point on a route. The aircraft climbs or descends to `altitude`, in feet, on the leg to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Waypoint {
+    pub position: Position,
+    pub altitude: u16,
+}
+
+/// A new squawk, set `after` the start of the scenario
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SquawkChange {
+    pub after: Duration,
+    /// The four octal digits written as hex, `0x7700`, the same as `EmergencyPriorityStatus::squawk`
+    pub squawk: u32,
+}
+
+/// An aircraft in a scenario
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedAircraft {
+    pub icao: ICAO,
+    /// Up to 8 characters of A-Z, 0-9 and space
+    pub callsign: String,
+    /// The aircraft appears at the first waypoint and stops transmitting at the last, so it needs at least two
+    pub route: Vec<Waypoint>,
+    /// Knots
+    pub ground_speed: f64,
+    /// Climb and descent rate in feet per minute
+    pub vertical_rate: u16,
+    /// The four octal digits written as hex, `0x7700`, the same as `EmergencyPriorityStatus::squawk`
+    pub squawk: u32,
+    pub squawk_changes: Vec<SquawkChange>,
+    /// When the aircraft appears, from the start of the scenario
+    pub start: Duration,
+}
+
+impl SimulatedAircraft {
+    /// An aircraft flying `route` at 250 knots, climbing and descending at 1500 feet per minute and squawking 2000
+    #[must_use]
+    pub fn new(icao: ICAO, callsign: &str, route: Vec<Waypoint>) -> SimulatedAircraft {
+        SimulatedAircraft {
+            icao,
+            callsign: callsign.to_string(),
+            route,
+            ground_speed: 250.0,
+            vertical_rate: 1500,
+            squawk: 0x2000,
+            squawk_changes: Vec::new(),
+            start: Duration::ZERO,
+        }
+    }
+}
+
+/// The aircraft to simulate
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scenario {
+    pub aircraft: Vec<SimulatedAircraft>,
+}
+
+/// How the frames go out
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum SimulatorOutputFormat {
+    /// Beast binary, readsb port 30005
+    #[default]
+    Beast,
+    /// `*<frame>;` lines, readsb port 30002
+    Avr,
+    /// `@<MLAT timestamp><frame>;` lines
+    AvrMlat,
+}
+
+/// A frame sent by one of the aircraft
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SimulatedFrame {
+    /// From the start of the scenario
+    pub time: Duration,
+    pub icao: ICAO,
+    pub signal_level: u8,
+    /// The 14 byte extended squitter
+    pub frame: Vec<u8>,
+}
+
+impl SimulatedFrame {
+    /// `time` as a 12 MHz MLAT timestamp
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn get_mlat_timestamp(&self) -> u64 {
+        // masked to 48 bits, so it fits
+        ((self.time.as_nanos() * MLAT_TICKS_PER_SECOND / 1_000_000_000) & MLAT_TIMESTAMP_MASK)
+            as u64
+    }
+
+    /// The frame as Beast wire bytes
+    /// # Errors
+    /// If the frame is not 14 bytes, the error is returned.
+    pub fn to_beast_bytes(&self) -> MessageResult<Vec<u8>> {
+        Ok(encode_beast_frame(
+            &MessageType::LongFrame,
+            self.get_mlat_timestamp(),
+            self.signal_level,
+            &self.frame,
+        )?)
+    }
+
+    /// The frame as an AVR line, with the line ending
+    #[must_use]
+    pub fn to_avr_line(&self) -> String {
+        format!("*{};\n", hex::encode_upper(&self.frame))
+    }
+
+    /// The frame as an AVR line with the MLAT timestamp, with the line ending
+    #[must_use]
+    pub fn to_avr_mlat_line(&self) -> String {
+        format!(
+            "@{:012X}{};\n",
+            self.get_mlat_timestamp(),
+            hex::encode_upper(&self.frame)
+        )
+    }
+
+    /// The frame in `format`
+    /// # Errors
+    /// If the frame can't be encoded as Beast, the error is returned.
+    pub fn to_bytes(&self, format: SimulatorOutputFormat) -> MessageResult<Vec<u8>> {
+        match format {
+            SimulatorOutputFormat::Beast => self.to_beast_bytes(),
+            SimulatorOutputFormat::Avr => Ok(self.to_avr_line().into_bytes()),
+            SimulatorOutputFormat::AvrMlat => Ok(self.to_avr_mlat_line().into_bytes()),
+        }
+    }
+}
+
+/// The message types each aircraft sends
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum Transmission {
+    Position,
+    Velocity,
+    TargetState,
+    OperationalStatus,
+    Identification,
+    AircraftStatus,
+}
+
+impl Transmission {
+    const ALL: [Transmission; 6] = [
+        Transmission::Position,
+        Transmission::Velocity,
+        Transmission::TargetState,
+        Transmission::OperationalStatus,
+        Transmission::Identification,
+        Transmission::AircraftStatus,
+    ];
+
+    /// When it is first sent after the aircraft appears, so the types don't all go out together
+    const fn first_offset(self) -> Duration {
+        match self {
+            Transmission::Position => Duration::ZERO,
+            Transmission::Velocity => Duration::from_millis(250),
+            Transmission::TargetState => Duration::from_millis(350),
+            Transmission::OperationalStatus => Duration::from_millis(450),
+            Transmission::Identification => Duration::from_millis(100),
+            Transmission::AircraftStatus => Duration::from_millis(600),
+        }
+    }
+}
+
+/// Where an aircraft is and what it is doing
+#[derive(Debug)]
+struct AircraftState {
+    aircraft: SimulatedAircraft,
+    /// Distance along the route to each waypoint in kilometers
+    waypoint_distances: Vec<f64>,
+    signal_level: u8,
+    updated: Duration,
+    position: Position,
+    track: f64,
+    /// Index of the waypoint the aircraft is flying to
+    next_waypoint: usize,
+    altitude: f64,
+    vertical_rate: i32,
+    cpr_format: CPRFormat,
+    squawk: u32,
+    squawk_changed: Option<Duration>,
+    next_squawk_change: usize,
+}
+
+impl AircraftState {
+    fn new(mut aircraft: SimulatedAircraft, signal_level: u8) -> AircraftState {
+        aircraft.squawk_changes.sort_by_key(|change| change.after);
+
+        let mut waypoint_distances = Vec::with_capacity(aircraft.route.len());
+        let mut distance = 0.0;
+        for (index, waypoint) in aircraft.route.iter().enumerate() {
+            if let Some(previous) = index.checked_sub(1).and_then(|i| aircraft.route.get(i)) {
+                distance += haversine_distance_position(&previous.position, &waypoint.position);
+            }
+            waypoint_distances.push(distance);
+        }
+
+        let (position, altitude) = aircraft
+            .route
+            .first()
+            .map_or((Position::default(), 0.0), |waypoint| {
+                (waypoint.position, f64::from(waypoint.altitude))
+            });
+
+        AircraftState {
+            signal_level,
+            updated: aircraft.start,
+            position,
+            track: 0.0,
+            next_waypoint: 1,
+            altitude,
+            vertical_rate: 0,
+            cpr_format: CPRFormat::Even,
+            squawk: aircraft.squawk,
+            squawk_changed: None,
+            next_squawk_change: 0,
+            waypoint_distances,
+            aircraft,
+        }
+    }
+
+    /// Move the aircraft on to `time`. Returns false once it has flown past the last waypoint.
+    fn advance(&mut self, time: Duration) -> bool {
+        let flown = self.aircraft.ground_speed
+            * KM_PER_NAUTICAL_MILE
+            * time.saturating_sub(self.aircraft.start).as_secs_f64()
+            / 3600.0;
+
+        let Some(next_waypoint) = self
+            .waypoint_distances
+            .iter()
+            .position(|distance| *distance > flown)
+        else {
+            return false;
+        };
+        let (Some(from), Some(to)) = (
+            next_waypoint
+                .checked_sub(1)
+                .and_then(|i| self.aircraft.route.get(i)),
+            self.aircraft.route.get(next_waypoint),
+        ) else {
+            return false;
+        };
+
+        let bearing = get_bearing_from_positions(&from.position, &to.position);
+        self.position = get_position_from_bearing_and_distance(
+            &from.position,
+            bearing,
+            flown - self.waypoint_distances[next_waypoint - 1],
+        );
+        self.track = get_bearing_from_positions(&self.position, &to.position);
+        self.next_waypoint = next_waypoint;
+
+        let climbed = f64::from(self.aircraft.vertical_rate)
+            * time.saturating_sub(self.updated).as_secs_f64()
+            / 60.0;
+        let target = f64::from(to.altitude);
+        if (target - self.altitude).abs() <= climbed {
+            self.altitude = target;
+            self.vertical_rate = 0;
+        } else if target > self.altitude {
+            self.altitude += climbed;
+            self.vertical_rate = i32::from(self.aircraft.vertical_rate);
+        } else {
+            self.altitude -= climbed;
+            self.vertical_rate = -i32::from(self.aircraft.vertical_rate);
+        }
+
+        while let Some(change) = self.aircraft.squawk_changes.get(self.next_squawk_change)
+            && change.after <= time
+        {
+            if change.squawk != self.squawk {
+                self.squawk = change.squawk;
+                self.squawk_changed = Some(change.after);
+            }
+            self.next_squawk_change += 1;
+        }
+
+        self.updated = time;
+        true
+    }
+
+    const fn is_emergency_squawk(&self) -> bool {
+        matches!(
+            self.squawk,
+            SQUAWK_HIJACK | SQUAWK_RADIO_FAILURE | SQUAWK_EMERGENCY
+        )
+    }
+
+    fn squawk_changed_within(&self, within: Duration) -> bool {
+        self.squawk_changed
+            .is_some_and(|changed| self.updated.saturating_sub(changed) < within)
+    }
+
+    fn interval(&self, transmission: Transmission) -> Duration {
+        match transmission {
+            Transmission::Position => POSITION_INTERVAL,
+            Transmission::Velocity => VELOCITY_INTERVAL,
+            Transmission::TargetState => TARGET_STATE_INTERVAL,
+            Transmission::OperationalStatus => OPERATIONAL_STATUS_INTERVAL,
+            Transmission::Identification => IDENTIFICATION_INTERVAL,
+            Transmission::AircraftStatus
+                if self.is_emergency_squawk()
+                    || self.squawk_changed_within(SQUAWK_CHANGED_BROADCAST) =>
+            {
+                AIRCRAFT_STATUS_CHANGED_INTERVAL
+            }
+            Transmission::AircraftStatus => AIRCRAFT_STATUS_INTERVAL,
+        }
+    }
+
+    fn get_me(&mut self, transmission: Transmission) -> ME {
+        match transmission {
+            Transmission::Position => {
+                let cpr_format = self.cpr_format;
+                self.cpr_format = match cpr_format {
+                    CPRFormat::Even => CPRFormat::Odd,
+                    CPRFormat::Odd => CPRFormat::Even,
+                };
+                ME::AirbornePositionBaroAltitude(
+                    POSITION_TYPE_CODE,
+                    self.get_airborne_position(cpr_format),
+                )
+            }
+            Transmission::Velocity => ME::AirborneVelocity(self.get_airborne_velocity()),
+            Transmission::TargetState => {
+                ME::TargetStateAndStatusInformation(self.get_target_state())
+            }
+            Transmission::OperationalStatus => {
+                ME::AircraftOperationStatus(OperationStatus::Airborne(get_operational_status()))
+            }
+            Transmission::Identification => ME::AircraftIdentification(
+                IDENTIFICATION_TYPE_CODE,
+                Identification {
+                    tc: TypeCoding::from(IDENTIFICATION_TYPE_CODE),
+                    ca: 0,
+                    cn: self.aircraft.callsign.clone(),
+                },
+            ),
+            Transmission::AircraftStatus => ME::AircraftStatus(
+                AircraftStatus::EmergencyPriorityStatus(EmergencyPriorityStatus {
+                    emergency_state: match self.squawk {
+                        SQUAWK_HIJACK => EmergencyState::UnlawfulInterference,
+                        SQUAWK_RADIO_FAILURE => EmergencyState::NoCommunication,
+                        SQUAWK_EMERGENCY => EmergencyState::General,
+                        _ => EmergencyState::None,
+                    },
+                    squawk: self.squawk,
+                    reserved: 0,
+                }),
+            ),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn get_airborne_position(&self, cpr_format: CPRFormat) -> Altitude {
+        let cpr_position = get_cpr_position_airborne(&self.position, cpr_format);
+        // rounded to the 25 ft steps that are sent, 0 can't be sent
+        let altitude = ((self.altitude / 25.0).round() * 25.0).clamp(25.0, MAX_ALTITUDE) as u16;
+
+        Altitude {
+            tc: POSITION_TYPE_CODE,
+            ss: if self.is_emergency_squawk() {
+                SurveillanceStatus::PermanentAlert
+            } else if self.squawk_changed_within(SQUAWK_CHANGED_ALERT) {
+                SurveillanceStatus::TemporaryAlert
+            } else {
+                SurveillanceStatus::NoCondition
+            },
+            saf_or_imf: 0,
+            alt: Some(altitude),
+            t: false,
+            odd_flag: cpr_format,
+            lat_cpr: cpr_position.lat_cpr,
+            lon_cpr: cpr_position.lon_cpr,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn get_airborne_velocity(&self) -> AirborneVelocity {
+        let track = self.track.to_radians();
+        let east = self.aircraft.ground_speed * libm::sin(track);
+        let north = self.aircraft.ground_speed * libm::cos(track);
+        let sign = |value: f64| {
+            if value < 0.0 {
+                Sign::Negative
+            } else {
+                Sign::Positive
+            }
+        };
+        // a value of 0 means no information, the rest are one more than the speed or rate
+        let velocity = |value: f64| (value.abs().round() + 1.0).min(1023.0) as u16;
+        let vertical_rate = f64::from(self.vertical_rate);
+
+        AirborneVelocity {
+            st: 1,
+            intent_change: 0,
+            reserved1: 0,
+            nac_v: 1,
+            sub_type: AirborneVelocitySubType::GroundSpeedDecoding(GroundSpeedDecoding {
+                ew_sign: sign(east),
+                ew_vel: velocity(east),
+                ns_sign: sign(north),
+                ns_vel: velocity(north),
+            }),
+            vrate_src: VerticalRateSource::BarometricPressureAltitude,
+            vrate_sign: sign(vertical_rate),
+            vrate_value: ((vertical_rate / 64.0).abs().round() + 1.0).min(511.0) as u16,
+            reserved2: 0,
+            gnss_sign: Sign::Positive,
+            gnss_baro_diff: 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn get_target_state(&self) -> TargetStateAndStatusInformation {
+        let selected_altitude = self
+            .aircraft
+            .route
+            .get(self.next_waypoint)
+            .map_or(0.0, |waypoint| f64::from(waypoint.altitude));
+        TargetStateAndStatusInformation {
+            subtype: 1,
+            is_fms: IsFMS::FMS,
+            // the selected altitude is sent in 32 ft steps
+            altitude: ((selected_altitude / 32.0).round() * 32.0) as u32,
+            qnh: 1013.6,
+            is_heading: SelectedHeadingStatus::Valid,
+            heading: calc_selected_heading(self.track),
+            nacp: 9,
+            nicbaro: 1,
+            sil: 3,
+            mode_validity: IsValidMode::ValidMode,
+            autopilot: AutopilotEngaged::Engaged,
+            vnac: VNAVEngaged::Engaged,
+            alt_hold: if self.vertical_rate == 0 {
+                AltitudeHold::Engaged
+            } else {
+                AltitudeHold::Disengaged
+            },
+            reserved0: 0,
+            approach: ApproachMode::Disengaged,
+            tcas: TCAS::Engaged,
+            lnav: LNAV::Engaged,
+            reserved1: 0,
+        }
+    }
+}
+
+/// The track in the selected heading's steps, 360 wraps to 0
+fn calc_selected_heading(track: f64) -> f64 {
+    (track / SELECTED_HEADING_STEP).round() * SELECTED_HEADING_STEP % 360.0
+}
+
+const fn get_operational_status() -> OperationStatusAirborne {
+    OperationStatusAirborne {
+        capability_class: CapabilityClassAirborne {
+            reserved0: 0,
+            acas: 1,
+            cdti: 1,
+            reserved1: 0,
+            arv: 0,
+            ts: 1,
+            tc: 0,
+        },
+        operational_mode: OperationalMode {
+            reserved: 0,
+            tcas_ra_active: false,
+            ident_switch_active: false,
+            reserved_recv_atc_service: 0,
+            single_antenna_flag: false,
+            system_design_assurance: SystemDesignAssurance::Hazardous,
+        },
+        reserved1: 0,
+        version_number: ADSBVersion::ADSBVersion2,
+        nic_supplement_a: 0,
+        navigational_accuracy_category: 9,
+        geometric_vertical_accuracy: 2,
+        source_integrity_level: 3,
+        barometric_altitude_integrity: 1,
+        horizontal_reference_direction: 0,
+        sil_supplement: 0,
+        reserved: 0,
+    }
+}
+
+/// Generates the frames of a `Scenario` in time order, either as fast as they are asked for or in real time.
+///
+/// As an iterator it runs until every aircraft has flown its route.
+/// ```
+/// use std::time::Duration;
+/// use sdre_rust_adsb_parser::decoders::helpers::cpr_calculators::Position;
+/// use sdre_rust_adsb_parser::decoders::raw_types::icao::ICAO;
+/// use sdre_rust_adsb_parser::helpers::traffic_simulator::{
+///     Scenario, SimulatedAircraft, SimulatorOutputFormat, TrafficSimulator, Waypoint,
+/// };
+///
+/// let route = vec![
+///     Waypoint { position: Position { latitude: 52.3, longitude: 4.76 }, altitude: 3000 },
+///     Waypoint { position: Position { latitude: 52.5, longitude: 5.2 }, altitude: 10000 },
+/// ];
+/// let scenario = Scenario {
+///     aircraft: vec![SimulatedAircraft::new(ICAO([0x48, 0x40, 0xD6]), "KLM1023", route)],
+/// };
+///
+/// let mut simulator = TrafficSimulator::new(scenario);
+/// for frame in simulator.run_until(Duration::from_secs(10)).unwrap() {
+///     print!("{}", frame.to_avr_line());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TrafficSimulator {
+    aircraft: Vec<AircraftState>,
+    schedule: BinaryHeap<Reverse<(Duration, usize, Transmission)>>,
+}
+
+impl TrafficSimulator {
+    #[must_use]
+    pub fn new(scenario: Scenario) -> TrafficSimulator {
+        let mut aircraft = Vec::with_capacity(scenario.aircraft.len());
+        let mut schedule = BinaryHeap::new();
+
+        for (index, (simulated, phase)) in scenario
+            .aircraft
+            .into_iter()
+            .zip((0_u64..).map(|n| n * AIRCRAFT_PHASE_STEP_MS % 1000))
+            .enumerate()
+        {
+            let start = simulated.start + Duration::from_millis(phase);
+            for transmission in Transmission::ALL {
+                schedule.push(Reverse((
+                    start + transmission.first_offset(),
+                    index,
+                    transmission,
+                )));
+            }
+            let signal_level = u8::try_from(0x40 + phase % 0x80).unwrap_or(u8::MAX);
+            aircraft.push(AircraftState::new(simulated, signal_level));
+        }
+
+        TrafficSimulator { aircraft, schedule }
+    }
+
+    /// The next frame if it is sent before `end`, otherwise it stays queued.
+    /// # Errors
+    /// If a message can't be encoded, for instance from a callsign with characters that can't be sent, the
+    /// error is returned and that message type is not sent by that aircraft again.
+    pub fn next_frame_before(&mut self, end: Duration) -> MessageResult<Option<SimulatedFrame>> {
+        loop {
+            let Some(Reverse((time, index, transmission))) = self.schedule.peek().copied() else {
+                return Ok(None);
+            };
+            if time >= end {
+                return Ok(None);
+            }
+            self.schedule.pop();
+
+            let Some(aircraft) = self.aircraft.get_mut(index) else {
+                continue;
+            };
+            if !aircraft.advance(time) {
+                // done with the route
+                continue;
+            }
+
+            let df = DF::ADSB(Adsb {
+                capability: Capability::AG_AIRBORNE,
+                icao: aircraft.aircraft.icao,
+                me: aircraft.get_me(transmission),
+                pi: ICAO([0, 0, 0]),
+            });
+            let frame = encode_extended_squitter(&df)?;
+            self.schedule.push(Reverse((
+                time + aircraft.interval(transmission),
+                index,
+                transmission,
+            )));
+
+            return Ok(Some(SimulatedFrame {
+                time,
+                icao: aircraft.aircraft.icao,
+                signal_level: aircraft.signal_level,
+                frame,
+            }));
+        }
+    }
+
+    /// Every frame sent before `end`, in simulated time. The frames after it stay queued for the next call.
+    /// # Errors
+    /// If a message can't be encoded, the error is returned.
+    pub fn run_until(&mut self, end: Duration) -> MessageResult<Vec<SimulatedFrame>> {
+        let mut frames = Vec::new();
+        while let Some(frame) = self.next_frame_before(end)? {
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+
+    /// Send the frames to the clients of `server` in real time, as `format`, until `duration` has passed
+    /// or every aircraft has flown its route. The scenario starts when this is called.
+    /// # Errors
+    /// If a message can't be encoded, the error is returned.
+    pub async fn run_real_time(
+        &mut self,
+        server: &OutputServer,
+        format: SimulatorOutputFormat,
+        duration: Duration,
+    ) -> MessageResult<()> {
+        let start = Instant::now();
+        while let Some(frame) = self.next_frame_before(duration)? {
+            sleep_until(start + frame.time).await;
+            // nobody listening yet is fine, the simulation keeps its own clock
+            let _ = server.send(frame.to_bytes(format)?);
+        }
+        if !self.schedule.is_empty()
+            && let Some(end) = start.checked_add(duration)
+        {
+            sleep_until(end).await;
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for TrafficSimulator {
+    type Item = MessageResult<SimulatedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame_before(Duration::MAX).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ADSBMessage;
+    use crate::helpers::message_stream::{InputFormat, message_stream};
+    use crate::state_machine::state::Machine;
+    use futures::StreamExt;
+    use sdre_rust_logging::SetupLogging;
+    use tokio::io::AsyncReadExt;
+
+    fn waypoint(latitude: f64, longitude: f64, altitude: u16) -> Waypoint {
+        Waypoint {
+            position: Position {
+                latitude,
+                longitude,
+            },
+            altitude,
+        }
+    }
+
+    fn get_scenario() -> Scenario {
+        let klm = SimulatedAircraft::new(
+            ICAO([0x48, 0x40, 0xD6]),
+            "KLM1023",
+            vec![waypoint(52.3, 4.76, 3000), waypoint(52.5, 5.2, 10000)],
+        );
+        // across the antimeridian, declaring an emergency 10 seconds in
+        let mut fiji = SimulatedAircraft::new(
+            ICAO([0xC8, 0x81, 0x23]),
+            "FJI811",
+            vec![
+                waypoint(-17.0, 179.97, 35000),
+                waypoint(-17.2, -179.0, 35000),
+            ],
+        );
+        fiji.ground_speed = 480.0;
+        fiji.squawk_changes.push(SquawkChange {
+            after: Duration::from_secs(10),
+            squawk: 0x7700,
+        });
+        Scenario {
+            aircraft: vec![klm, fiji],
+        }
+    }
+
+    #[tokio::test]
+    async fn simulate_traffic() {
+        "debug".enable_logging();
+
+        let scenario = get_scenario();
+        let mut simulator = TrafficSimulator::new(scenario.clone());
+        let frames = simulator.run_until(Duration::from_secs(30)).unwrap();
+        assert!(frames.windows(2).all(|pair| pair[0].time <= pair[1].time));
+        let klm_frames: Vec<&SimulatedFrame> = frames
+            .iter()
+            .filter(|frame| frame.icao == ICAO([0x48, 0x40, 0xD6]))
+            .collect();
+        // 2 Hz position and velocity, TSS, operational status, identification and aircraft status
+        assert_eq!(klm_frames.len(), 60 + 60 + 24 + 12 + 6 + 6);
+
+        let beast: Vec<u8> = frames
+            .iter()
+            .flat_map(|frame| frame.to_beast_bytes().unwrap())
+            .collect();
+        let messages: Vec<_> = message_stream(beast.as_slice(), InputFormat::Beast)
+            .collect()
+            .await;
+        assert_eq!(messages.len(), frames.len());
+
+        let mut machine = Machine::new();
+        machine.position = Position {
+            latitude: 52.3,
+            longitude: 4.76,
+        };
+        for message in messages {
+            let ADSBMessage::AdsbBeastMessage(message) = message.unwrap() else {
+                panic!("Not a Beast message");
+            };
+            assert!(!message.raw_message.crc_status().is_corrupt());
+            machine.process_aircraft_beast(message).await.unwrap();
+        }
+
+        for (hex, state) in [
+            ("4840D6", &simulator.aircraft[0]),
+            ("C88123", &simulator.aircraft[1]),
+        ] {
+            let airplane = machine.get_airplane_by_hex(hex).await.unwrap();
+            let position = Position {
+                latitude: airplane.latitude.unwrap().latitude,
+                longitude: airplane.longitude.unwrap().longitude,
+            };
+            // the last position sent is at most half a second behind
+            assert!(
+                haversine_distance_position(&position, &state.position) < 0.2,
+                "{hex} at {position:?}, simulated at {:?}",
+                state.position
+            );
+            assert_eq!(
+                airplane
+                    .calculated_best_flight_id
+                    .unwrap()
+                    .to_string()
+                    .trim(),
+                state.aircraft.callsign
+            );
+        }
+        let fiji = machine.get_airplane_by_hex("C88123").await.unwrap();
+        assert_eq!(fiji.transponder_squawk_code.unwrap().to_string(), "7700");
+        // and it made it across
+        assert!(simulator.aircraft[1].position.longitude < 0.0);
+
+        // AVR carries the same frames, and the rest of the scenario picks up where it stopped
+        let avr: String = frames.iter().map(SimulatedFrame::to_avr_line).collect();
+        let messages: Vec<_> = message_stream(avr.as_bytes(), InputFormat::Avr)
+            .collect()
+            .await;
+        assert_eq!(messages.len(), frames.len());
+        assert!(messages.iter().all(Result::is_ok));
+        let next = simulator.next().unwrap().unwrap();
+        assert!(next.time >= Duration::from_secs(30));
+
+        // the route ends
+        let everything: Vec<_> = TrafficSimulator::new(scenario.clone()).collect();
+        assert!(everything.iter().all(Result::is_ok));
+        assert!(everything.len() > frames.len());
+
+        let mut invalid = SimulatedAircraft::new(
+            ICAO([0x48, 0x40, 0xD6]),
+            "KLM-1023",
+            vec![waypoint(52.3, 4.76, 3000), waypoint(52.5, 5.2, 10000)],
+        );
+        invalid.start = Duration::from_secs(1);
+        let mut simulator = TrafficSimulator::new(Scenario {
+            aircraft: vec![invalid],
+        });
+        assert!(simulator.run_until(Duration::from_secs(2)).is_err());
+    }
+
+    #[tokio::test]
+    async fn simulate_traffic_in_real_time() {
+        "debug".enable_logging();
+
+        let scenario = get_scenario();
+        let server = OutputServer::bind("127.0.0.1:0").await.unwrap();
+        let mut client = tokio::net::TcpStream::connect(server.local_addr())
+            .await
+            .unwrap();
+        while server.client_count() < 1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let expected = TrafficSimulator::new(scenario.clone())
+            .run_until(Duration::from_secs(1))
+            .unwrap();
+        let mut simulator = TrafficSimulator::new(scenario);
+        let started = Instant::now();
+        simulator
+            .run_real_time(
+                &server,
+                SimulatorOutputFormat::AvrMlat,
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        drop(server);
+        let mut received = String::new();
+        client.read_to_string(&mut received).await.unwrap();
+        let expected: String = expected
+            .iter()
+            .map(SimulatedFrame::to_avr_mlat_line)
+            .collect();
+        assert_eq!(received, expected);
+        assert!(received.starts_with("@0000000000008D4840D6"));
+    }
+}
@@ -60,14 +60,25 @@ extern crate log;
 use core::fmt;
 
 use decoders::beast::AdsbBeastMessage;
+#[cfg(feature = "beast")]
+use decoders::beaststatus::BeastStatusMessage;
+use error_handling::adsb_raw_error::ADSBRawError;
 use error_handling::deserialization_error::{DeserializationError, WrongType};
 
 #[cfg(feature = "json")]
 use decoders::aircraftjson::AircraftJSON;
+#[cfg(feature = "raw")]
+use decoders::helpers::error_correction::{ErrorCorrection, MODES_LONG_MSG_BYTES, correct_errors};
 #[cfg(feature = "json")]
 use decoders::json::JSONMessage;
 #[cfg(feature = "raw")]
+use decoders::modeac::ModeACMessage;
+#[cfg(feature = "raw")]
 use decoders::raw::AdsbRawMessage;
+#[cfg(feature = "raw")]
+use decoders::raw_types::df::DF;
+#[cfg(feature = "json")]
+use decoders::sbs::NewSBSMessage;
 use deku::{no_std_io, prelude::*};
 use serde::{Deserialize, Serialize};
 pub mod decoders {
@@ -75,54 +86,69 @@ pub mod decoders {
     #[cfg(feature = "raw")]
     pub mod raw_types {
         pub mod ac13field;
+        pub mod acasresolutionadvisory;
         pub mod adsb;
         pub mod adsbversion;
         pub mod airbornevelocity;
         pub mod airbornevelocitysubfields;
         pub mod airbornevelocitysubtype;
         pub mod airbornevelocitytype;
+        pub mod aircraftoperationalcoordination;
         pub mod aircraftstatus;
         pub mod aircraftstatustype;
         pub mod airspeeddecoding;
         pub mod altitude;
         pub mod autopilot_modes;
         pub mod bds;
+        pub mod bdsinference;
         pub mod capability;
         pub mod capabilityclassairborne;
         pub mod capabilityclasssurface;
         pub mod controlfield;
         pub mod controlfieldtype;
         pub mod cprheaders;
+        pub mod crcstatus;
         pub mod datalinkcapability;
         pub mod df;
         pub mod direction_nsew;
         pub mod downlinkrequest;
+        pub mod emergencyprioritystatus;
         pub mod emergencystate;
         pub mod flightstatus;
         pub mod fms;
         pub mod groundspeed;
         pub mod groundspeeddecoding;
+        pub mod hazardlevel;
         pub mod heading;
+        pub mod headingandspeedreport;
         pub mod helper_functions;
         pub mod icao;
         pub mod identification;
         pub mod identitycode;
         pub mod ke;
         pub mod me;
+        pub mod meteorologicalhazardreport;
+        pub mod meteorologicalroutineairreport;
         pub mod modevalidity;
+        pub mod mvfield;
         pub mod noposition;
         pub mod operationalmode;
         pub mod operationcodesurface;
         pub mod operationstatus;
         pub mod operationstatusairborne;
         pub mod operationstatussurface;
+        pub mod resolutionadvisorycomplement;
+        pub mod selectedverticalintention;
         pub mod sign;
         pub mod signbitgnssbaroaltitudesdiff;
         pub mod signbitverticalrate;
         pub mod sourcebitverticalrate;
         pub mod statusforgroundtrack;
         pub mod surfaceposition;
+        pub mod surfacesystemstatus;
         pub mod targetstateandstatusinformation;
+        pub mod threatidentity;
+        pub mod trackandturnreport;
         pub mod typecoding;
         pub mod utilitymessage;
         pub mod utilitymessagetype;
@@ -132,14 +158,18 @@ pub mod decoders {
     pub mod beast;
     #[cfg(feature = "beast")]
     pub mod beast_types {
+        pub mod dipswitches;
         pub mod messagetype;
     }
     #[cfg(feature = "json")]
     pub mod aircraftjson;
+    #[cfg(feature = "beast")]
+    pub mod beaststatus;
     #[cfg(feature = "json")]
     pub mod json;
     #[cfg(feature = "json")]
     pub mod json_types {
+        pub mod acasresolutionadvisory;
         pub mod adsbversion;
         pub mod altimeter;
         pub mod altitude;
@@ -175,9 +205,15 @@ pub mod decoders {
         pub mod surveillancestatus;
     }
     #[cfg(feature = "raw")]
+    pub mod modeac;
+    #[cfg(feature = "raw")]
     pub mod raw;
+    #[cfg(feature = "json")]
+    pub mod sbs;
     pub mod helpers {
         pub mod cpr_calculators;
+        #[cfg(feature = "raw")]
+        pub mod error_correction;
         pub mod prettyprint;
         pub mod time;
     }
@@ -191,13 +227,30 @@ pub mod error_handling {
     pub mod adsb_beast_error;
     pub mod adsb_json_error;
     pub mod adsb_raw_error;
+    pub mod adsb_sbs_error;
+    pub mod adsb_stream_error;
     pub mod deserialization_error;
 }
 
 pub mod helpers {
     pub mod encode_adsb_beast_input;
+    pub mod encode_adsb_beast_output;
     pub mod encode_adsb_json_input;
     pub mod encode_adsb_raw_input;
+    pub mod encode_adsb_raw_output;
+    pub mod encode_adsb_sbs_input;
+    pub mod encode_adsb_sbs_output;
+    pub mod format_detection;
+    pub mod framer;
+    pub mod message_stream;
+    pub mod output_server;
+    pub mod traffic_simulator;
+}
+
+#[cfg(feature = "beast")]
+pub mod demodulator {
+    pub mod iq_samples;
+    pub mod mode_s;
 }
 
 pub mod data_structures {
@@ -205,6 +258,7 @@ pub mod data_structures {
 }
 
 pub mod state_machine {
+    pub mod icao_filter;
     pub mod state;
 }
 
@@ -218,9 +272,35 @@ pub type MessageResult<T> = Result<T, DeserializationError>;
 /// The originating data must be in JSON, Beast or Raw format. Vectors of bytes are also supported.
 pub trait DecodeMessage {
     /// Decodes the message and returns it as an `ADSBMessage` struct.
+    ///
+    /// Extended squitters and all-call replies that fail the CRC check are rejected.
     /// # Errors
     /// This function will return an error if the message is not in JSON, Beast, or Raw format.
     fn decode_message(&self) -> MessageResult<ADSBMessage>;
+    /// Decodes the message and returns it as an `ADSBMessage` struct.
+    ///
+    /// With `reject_corrupt` unset, frames that fail the CRC check are returned as well. See `AdsbRawMessage::crc_status`.
+    /// The default only checks the result of `decode_message`, so it can't return corrupt frames; the
+    /// implementations in this library override it.
+    /// # Errors
+    /// This function will return an error if the message is not in JSON, Beast, or Raw format.
+    fn decode_message_with_crc_check(&self, reject_corrupt: bool) -> MessageResult<ADSBMessage> {
+        check_crc(self.decode_message()?, reject_corrupt)
+    }
+    /// Decodes the message and returns it as an `ADSBMessage` struct, first repairing up to `error_correction`
+    /// flipped bits in a DF17/DF18 that fails the CRC check. See `correct_errors`.
+    ///
+    /// `reject_corrupt` then works as in `decode_message_with_crc_check`. The default can't repair anything and only
+    /// checks the result of `decode_message`; the implementations in this library override it.
+    /// # Errors
+    /// This function will return an error if the message is not in JSON, Beast, or Raw format.
+    fn decode_message_with_error_correction(
+        &self,
+        _error_correction: ErrorCorrection,
+        reject_corrupt: bool,
+    ) -> MessageResult<ADSBMessage> {
+        self.decode_message_with_crc_check(reject_corrupt)
+    }
     /// Decodes the message and returns it as an `AircraftJSON` struct.
     /// # Errors
     /// This function will return an error if the message is not an aircraft.
@@ -237,16 +317,144 @@ pub trait DecodeMessage {
     }
 }
 
+/// Turn a frame that fails the CRC check into an error, unless the caller asked for corrupt frames.
+pub(crate) fn check_crc(message: ADSBMessage, reject_corrupt: bool) -> MessageResult<ADSBMessage> {
+    let raw_message = match &message {
+        ADSBMessage::AdsbRawMessage(raw_message) => raw_message,
+        ADSBMessage::AdsbBeastMessage(beast_message) => &beast_message.raw_message,
+        ADSBMessage::JSONMessage(_)
+        | ADSBMessage::AircraftJSON(_)
+        | ADSBMessage::ModeACMessage(_)
+        | ADSBMessage::BeastStatusMessage(_)
+        | ADSBMessage::SBSMessage(_) => return Ok(message),
+    };
+
+    if reject_corrupt && raw_message.crc_status().is_corrupt() {
+        return Err(ADSBRawError::CorruptFrame {
+            syndrome: format!("{:06X}", raw_message.crc),
+        }
+        .into());
+    }
+
+    Ok(message)
+}
+
+/// Repair a DF17/DF18 that fails the CRC check, then check the CRC like `check_crc`.
+///
+/// `bytes` are the ones the message was decoded from. A long frame is always the last 14 of them, behind any
+/// Beast or AVR header.
+pub(crate) fn correct_frame(
+    message: ADSBMessage,
+    bytes: &[u8],
+    error_correction: ErrorCorrection,
+    reject_corrupt: bool,
+) -> MessageResult<ADSBMessage> {
+    let raw_message = match &message {
+        ADSBMessage::AdsbRawMessage(raw_message) => raw_message,
+        ADSBMessage::AdsbBeastMessage(beast_message) => &beast_message.raw_message,
+        _ => return check_crc(message, reject_corrupt),
+    };
+    let Some(frame_start) = bytes.len().checked_sub(MODES_LONG_MSG_BYTES) else {
+        return check_crc(message, reject_corrupt);
+    };
+    if !raw_message.crc_status().is_corrupt()
+        || !matches!(raw_message.df, DF::ADSB(_) | DF::TisB { .. })
+    {
+        return check_crc(message, reject_corrupt);
+    }
+
+    let mut repaired = bytes.to_vec();
+    if correct_errors(&mut repaired[frame_start..], error_correction).is_none() {
+        return check_crc(message, reject_corrupt);
+    }
+
+    let message = match message {
+        ADSBMessage::AdsbRawMessage(raw_message) => {
+            let mut repaired_message = AdsbRawMessage::from_bytes(&repaired[frame_start..])?;
+            repaired_message.mlat_timestamp = raw_message.mlat_timestamp;
+            repaired_message.signal_level = raw_message.signal_level;
+            ADSBMessage::AdsbRawMessage(repaired_message)
+        }
+        ADSBMessage::AdsbBeastMessage(_) => {
+            ADSBMessage::AdsbBeastMessage(AdsbBeastMessage::from_bytes(&repaired)?)
+        }
+        message => message,
+    };
+
+    check_crc(message, reject_corrupt)
+}
+
+/// The frame of a single AVR line, `*…;`, `@…;` or `%…;`, hex decoded by `format_adsb_raw_frames_from_bytes`.
+/// A timestamped line keeps its Beast style header in front of the frame.
+fn get_avr_frame(line: &str) -> Option<Vec<u8>> {
+    if !line.starts_with(['*', '@', '%']) {
+        return None;
+    }
+
+    let formatted = helpers::encode_adsb_raw_input::format_adsb_raw_frames_from_bytes(
+        format!("{}\n", line.trim_end()).as_bytes(),
+    );
+    match <[Vec<u8>; 1]>::try_from(formatted.frames) {
+        Ok([frame]) if formatted.errors.is_empty() => Some(frame),
+        _ => None,
+    }
+}
+
+/// Decode a frame from `get_avr_frame` the way the AVR stream codec does.
+fn decode_avr_frame(frame: &[u8], reject_corrupt: bool) -> MessageResult<ADSBMessage> {
+    // a bare Mode A/C code, or one with its header
+    if matches!(frame.len(), 2 | 10) {
+        return Ok(ADSBMessage::ModeACMessage(ModeACMessage::from_bytes(
+            frame,
+        )?));
+    }
+
+    check_crc(
+        ADSBMessage::AdsbRawMessage(AdsbRawMessage::from_avr_bytes(frame)?),
+        reject_corrupt,
+    )
+}
+
 /// Provides functionality for decoding a `String` to `ADSBMessage`.
 ///
 /// This does not consume the `String`.
 impl DecodeMessage for String {
     fn decode_message(&self) -> MessageResult<ADSBMessage> {
+        self.decode_message_with_crc_check(true)
+    }
+
+    fn decode_message_with_error_correction(
+        &self,
+        error_correction: ErrorCorrection,
+        reject_corrupt: bool,
+    ) -> MessageResult<ADSBMessage> {
+        // the frame of an AVR line, without the framing around it
+        let bytes = get_avr_frame(self)
+            .or_else(|| hex::decode(self).ok())
+            .unwrap_or_default();
+        correct_frame(
+            self.decode_message_with_crc_check(false)?,
+            &bytes,
+            error_correction,
+            reject_corrupt,
+        )
+    }
+
+    fn decode_message_with_crc_check(&self, reject_corrupt: bool) -> MessageResult<ADSBMessage> {
         let error_serde: DeserializationError = match serde_json::from_str(self) {
             Ok(v) => return Ok(v),
             Err(e) => e.into(),
         };
 
+        let error_sbs: DeserializationError = match self.to_sbs() {
+            Ok(body) => return Ok(ADSBMessage::SBSMessage(body)),
+            Err(e) => e,
+        };
+
+        if let Some(frame) = get_avr_frame(self) {
+            return decode_avr_frame(&frame, reject_corrupt);
+        }
+
         let bytes: Vec<u8> = match hex::decode(self) {
             Ok(v) => v,
             Err(e) => {
@@ -254,23 +462,43 @@ impl DecodeMessage for String {
                 // we can't attempt to use the other decoders here, because we didn't get sane bytes
                 return Err(DeserializationError::CombinedError(vec![
                     error_serde,
+                    error_sbs,
                     e.into(),
                 ]));
             }
         };
         // try to decode it as a raw frame
         let error_raw: DeserializationError = match AdsbRawMessage::from_bytes(&bytes) {
-            Ok(body) => return Ok(ADSBMessage::AdsbRawMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbRawMessage(body), reject_corrupt),
             Err(e) => e.into(),
         };
 
         let error_beast: DeserializationError = match AdsbBeastMessage::from_bytes(&bytes) {
-            Ok(body) => return Ok(ADSBMessage::AdsbBeastMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbBeastMessage(body), reject_corrupt),
+            Err(e) => e.into(),
+        };
+
+        let error_beast_status: DeserializationError = match BeastStatusMessage::from_bytes(&bytes)
+        {
+            Ok(body) => return Ok(ADSBMessage::BeastStatusMessage(body)),
+            Err(e) => e.into(),
+        };
+
+        // Mode A/C frames are too short to be anything else, so they are tried last
+        let error_mode_ac: DeserializationError = match ModeACMessage::from_bytes(&bytes) {
+            Ok(body) => return Ok(ADSBMessage::ModeACMessage(body)),
             Err(e) => e.into(),
         };
 
         // create a combined error
-        let errors: Vec<DeserializationError> = vec![error_serde, error_raw, error_beast];
+        let errors: Vec<DeserializationError> = vec![
+            error_serde,
+            error_sbs,
+            error_raw,
+            error_beast,
+            error_beast_status,
+            error_mode_ac,
+        ];
         Err(DeserializationError::CombinedError(errors))
     }
 
@@ -292,11 +520,41 @@ impl DecodeMessage for String {
 /// This does not consume the `str`.
 impl DecodeMessage for str {
     fn decode_message(&self) -> MessageResult<ADSBMessage> {
+        self.decode_message_with_crc_check(true)
+    }
+
+    fn decode_message_with_error_correction(
+        &self,
+        error_correction: ErrorCorrection,
+        reject_corrupt: bool,
+    ) -> MessageResult<ADSBMessage> {
+        // the frame of an AVR line, without the framing around it
+        let bytes = get_avr_frame(self)
+            .or_else(|| hex::decode(self).ok())
+            .unwrap_or_default();
+        correct_frame(
+            self.decode_message_with_crc_check(false)?,
+            &bytes,
+            error_correction,
+            reject_corrupt,
+        )
+    }
+
+    fn decode_message_with_crc_check(&self, reject_corrupt: bool) -> MessageResult<ADSBMessage> {
         let error_serde: DeserializationError = match serde_json::from_str(self) {
             Ok(v) => return Ok(v),
             Err(e) => e.into(),
         };
 
+        let error_sbs: DeserializationError = match self.to_sbs() {
+            Ok(body) => return Ok(ADSBMessage::SBSMessage(body)),
+            Err(e) => e,
+        };
+
+        if let Some(frame) = get_avr_frame(self) {
+            return decode_avr_frame(&frame, reject_corrupt);
+        }
+
         let bytes: Vec<u8> = match hex::decode(self) {
             Ok(v) => v,
             Err(e) => {
@@ -304,23 +562,43 @@ impl DecodeMessage for str {
                 // we can't attempt to use the other decoders here, because we didn't get sane bytes
                 return Err(DeserializationError::CombinedError(vec![
                     error_serde,
+                    error_sbs,
                     e.into(),
                 ]));
             }
         };
         // try to decode it as a raw frame
         let error_raw: DeserializationError = match AdsbRawMessage::from_bytes(&bytes) {
-            Ok(body) => return Ok(ADSBMessage::AdsbRawMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbRawMessage(body), reject_corrupt),
             Err(e) => e.into(),
         };
 
         let error_beast: DeserializationError = match AdsbBeastMessage::from_bytes(&bytes) {
-            Ok(body) => return Ok(ADSBMessage::AdsbBeastMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbBeastMessage(body), reject_corrupt),
+            Err(e) => e.into(),
+        };
+
+        let error_beast_status: DeserializationError = match BeastStatusMessage::from_bytes(&bytes)
+        {
+            Ok(body) => return Ok(ADSBMessage::BeastStatusMessage(body)),
+            Err(e) => e.into(),
+        };
+
+        // Mode A/C frames are too short to be anything else, so they are tried last
+        let error_mode_ac: DeserializationError = match ModeACMessage::from_bytes(&bytes) {
+            Ok(body) => return Ok(ADSBMessage::ModeACMessage(body)),
             Err(e) => e.into(),
         };
 
         // create a combined error
-        let errors: Vec<DeserializationError> = vec![error_serde, error_raw, error_beast];
+        let errors: Vec<DeserializationError> = vec![
+            error_serde,
+            error_sbs,
+            error_raw,
+            error_beast,
+            error_beast_status,
+            error_mode_ac,
+        ];
         Err(DeserializationError::CombinedError(errors))
     }
 
@@ -342,14 +620,36 @@ impl DecodeMessage for str {
 /// This does not consume the `&[u8]`.
 impl DecodeMessage for &[u8] {
     fn decode_message(&self) -> MessageResult<ADSBMessage> {
+        self.decode_message_with_crc_check(true)
+    }
+
+    fn decode_message_with_error_correction(
+        &self,
+        error_correction: ErrorCorrection,
+        reject_corrupt: bool,
+    ) -> MessageResult<ADSBMessage> {
+        correct_frame(
+            self.decode_message_with_crc_check(false)?,
+            self,
+            error_correction,
+            reject_corrupt,
+        )
+    }
+
+    fn decode_message_with_crc_check(&self, reject_corrupt: bool) -> MessageResult<ADSBMessage> {
         let error_beast: DeserializationError = match AdsbBeastMessage::from_bytes(self) {
-            Ok(body) => return Ok(ADSBMessage::AdsbBeastMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbBeastMessage(body), reject_corrupt),
+            Err(e) => e.into(),
+        };
+
+        let error_beast_status: DeserializationError = match BeastStatusMessage::from_bytes(self) {
+            Ok(body) => return Ok(ADSBMessage::BeastStatusMessage(body)),
             Err(e) => e.into(),
         };
 
         // try to decode it as a raw frame
         let error_raw: DeserializationError = match AdsbRawMessage::from_bytes(self) {
-            Ok(body) => return Ok(ADSBMessage::AdsbRawMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbRawMessage(body), reject_corrupt),
             Err(e) => e.into(),
         };
 
@@ -358,8 +658,26 @@ impl DecodeMessage for &[u8] {
             Err(e) => e.into(),
         };
 
+        // Mode A/C frames are too short to be anything else, so they are tried last
+        let error_mode_ac: DeserializationError = match ModeACMessage::from_bytes(self) {
+            Ok(body) => return Ok(ADSBMessage::ModeACMessage(body)),
+            Err(e) => e.into(),
+        };
+
+        let error_sbs: DeserializationError = match self.to_sbs() {
+            Ok(body) => return Ok(ADSBMessage::SBSMessage(body)),
+            Err(e) => e,
+        };
+
         // create a combined error
-        let errors: Vec<DeserializationError> = vec![error_serde, error_raw, error_beast];
+        let errors: Vec<DeserializationError> = vec![
+            error_serde,
+            error_sbs,
+            error_raw,
+            error_beast,
+            error_beast_status,
+            error_mode_ac,
+        ];
         Err(DeserializationError::CombinedError(errors))
     }
 
@@ -378,14 +696,36 @@ impl DecodeMessage for &[u8] {
 
 impl DecodeMessage for Vec<u8> {
     fn decode_message(&self) -> MessageResult<ADSBMessage> {
+        self.decode_message_with_crc_check(true)
+    }
+
+    fn decode_message_with_error_correction(
+        &self,
+        error_correction: ErrorCorrection,
+        reject_corrupt: bool,
+    ) -> MessageResult<ADSBMessage> {
+        correct_frame(
+            self.decode_message_with_crc_check(false)?,
+            self,
+            error_correction,
+            reject_corrupt,
+        )
+    }
+
+    fn decode_message_with_crc_check(&self, reject_corrupt: bool) -> MessageResult<ADSBMessage> {
         let error_beast: DeserializationError = match AdsbBeastMessage::from_bytes(self) {
-            Ok(body) => return Ok(ADSBMessage::AdsbBeastMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbBeastMessage(body), reject_corrupt),
+            Err(e) => e.into(),
+        };
+
+        let error_beast_status: DeserializationError = match BeastStatusMessage::from_bytes(self) {
+            Ok(body) => return Ok(ADSBMessage::BeastStatusMessage(body)),
             Err(e) => e.into(),
         };
 
         // try to decode it as a raw frame
         let error_raw: DeserializationError = match AdsbRawMessage::from_bytes(self) {
-            Ok(body) => return Ok(ADSBMessage::AdsbRawMessage(body)),
+            Ok(body) => return check_crc(ADSBMessage::AdsbRawMessage(body), reject_corrupt),
             Err(e) => e.into(),
         };
 
@@ -394,8 +734,26 @@ impl DecodeMessage for Vec<u8> {
             Err(e) => e.into(),
         };
 
+        // Mode A/C frames are too short to be anything else, so they are tried last
+        let error_mode_ac: DeserializationError = match ModeACMessage::from_bytes(self) {
+            Ok(body) => return Ok(ADSBMessage::ModeACMessage(body)),
+            Err(e) => e.into(),
+        };
+
+        let error_sbs: DeserializationError = match self.as_slice().to_sbs() {
+            Ok(body) => return Ok(ADSBMessage::SBSMessage(body)),
+            Err(e) => e,
+        };
+
         // create a combined error
-        let errors: Vec<DeserializationError> = vec![error_serde, error_raw, error_beast];
+        let errors: Vec<DeserializationError> = vec![
+            error_serde,
+            error_sbs,
+            error_raw,
+            error_beast,
+            error_beast_status,
+            error_mode_ac,
+        ];
         Err(DeserializationError::CombinedError(errors))
     }
 
@@ -421,6 +779,11 @@ impl fmt::Display for ADSBMessage {
             ADSBMessage::AdsbBeastMessage(adsb_beast_message) => {
                 write!(f, "{adsb_beast_message}")
             }
+            ADSBMessage::ModeACMessage(mode_ac_message) => write!(f, "{mode_ac_message}"),
+            ADSBMessage::BeastStatusMessage(beast_status_message) => {
+                write!(f, "{beast_status_message}")
+            }
+            ADSBMessage::SBSMessage(sbs_message) => write!(f, "{sbs_message}"),
         }
     }
 }
@@ -482,6 +845,11 @@ impl ADSBMessage {
             ADSBMessage::AircraftJSON(aircraft_json) => aircraft_json.pretty_print(),
             ADSBMessage::AdsbRawMessage(adsb_raw_message) => adsb_raw_message.pretty_print(),
             ADSBMessage::AdsbBeastMessage(adsb_beast_message) => adsb_beast_message.pretty_print(),
+            ADSBMessage::ModeACMessage(mode_ac_message) => mode_ac_message.pretty_print(),
+            ADSBMessage::BeastStatusMessage(beast_status_message) => {
+                beast_status_message.pretty_print()
+            }
+            ADSBMessage::SBSMessage(sbs_message) => sbs_message.pretty_print(),
         }
     }
 
@@ -522,6 +890,14 @@ pub enum ADSBMessage {
     AdsbRawMessage(AdsbRawMessage),
     #[cfg(feature = "beast")]
     AdsbBeastMessage(AdsbBeastMessage),
+    #[cfg(feature = "raw")]
+    ModeACMessage(ModeACMessage),
+    #[cfg(feature = "beast")]
+    BeastStatusMessage(BeastStatusMessage),
+    /// A single SBS line. Only the fields the line carries are set, see `decoders::sbs::update_from_sbs`.
+    #[cfg(feature = "json")]
+    #[serde(skip_deserializing)]
+    SBSMessage(JSONMessage),
 }
 
 impl Default for ADSBMessage {
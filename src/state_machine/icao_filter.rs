@@ -0,0 +1,173 @@
+// Copyright (c) 2023-2024 Frederick Clausen II
+
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+// The addresses the state machine has seen in CRC-clean frames, for checking replies that only
+// carry their address in the parity.
+
+use std::collections::HashMap;
+
+use crate::decoders::helpers::time::get_time_as_f64;
+use crate::decoders::raw::AdsbRawMessage;
+use crate::decoders::raw_types::{crcstatus::CRCStatus, df::DF};
+
+/// How long a confirmed address is trusted, the same as readsb
+pub(crate) const ICAO_FILTER_EXPIRY_IN_SECONDS: u32 = 60;
+
+/// Addresses recently confirmed by a CRC-clean DF11, DF17 or DF18, like readsb's icao filter.
+///
+/// DF0/4/5/16/20/21 only carry their address XOR-ed into the parity, so any damaged frame turns
+/// into a plausible looking address. Those frames are only trusted if the address is in here.
+#[derive(Debug, Clone)]
+pub struct ICAOFilter {
+    /// address and the time it was last confirmed
+    addresses: HashMap<String, f64>,
+    expiry_in_seconds: f64,
+    last_expired: f64,
+}
+
+impl Default for ICAOFilter {
+    fn default() -> Self {
+        Self::new(ICAO_FILTER_EXPIRY_IN_SECONDS)
+    }
+}
+
+impl ICAOFilter {
+    #[must_use]
+    pub fn new(expiry_in_seconds: u32) -> ICAOFilter {
+        ICAOFilter::new_at(expiry_in_seconds, get_time_as_f64())
+    }
+
+    /// A filter on a clock of its own, like the time in to a capture, starting at `now`.
+    /// Use it with the `_at` methods only.
+    #[must_use]
+    pub(crate) fn new_at(expiry_in_seconds: u32, now: f64) -> ICAOFilter {
+        ICAOFilter {
+            addresses: HashMap::new(),
+            expiry_in_seconds: f64::from(expiry_in_seconds),
+            last_expired: now,
+        }
+    }
+
+    /// Remember the address of a CRC-clean DF11, DF17 or DF18 with an ICAO address. Other frames are ignored.
+    pub fn update(&mut self, message: &AdsbRawMessage) {
+        self.update_at(message, get_time_as_f64());
+    }
+
+    pub(crate) fn update_at(&mut self, message: &AdsbRawMessage, now: f64) {
+        if message.crc_status() != CRCStatus::Clean
+            || !matches!(
+                message.df,
+                DF::ADSB(_) | DF::TisB { .. } | DF::AllCallReply { .. }
+            )
+        {
+            return;
+        }
+
+        // non-ICAO DF18 addresses are prefixed and never match a reply's parity
+        if let Some(address) = message.get_transponder_hex()
+            && !address.starts_with('~')
+        {
+            self.addresses.insert(address, now);
+        }
+
+        if now - self.last_expired > self.expiry_in_seconds {
+            self.expire_at(now);
+        }
+    }
+
+    /// The address was confirmed within the expiry time.
+    #[must_use]
+    pub fn contains(&self, address: &str) -> bool {
+        self.contains_at(address, get_time_as_f64())
+    }
+
+    pub(crate) fn contains_at(&self, address: &str, now: f64) -> bool {
+        self.addresses
+            .get(address)
+            .is_some_and(|last_seen| now - last_seen <= self.expiry_in_seconds)
+    }
+
+    /// The message can be trusted: its parity checks out, or the address it recovers from the parity is known.
+    #[must_use]
+    pub fn accepts(&self, message: &AdsbRawMessage) -> bool {
+        match message.crc_status() {
+            CRCStatus::Clean => true,
+            CRCStatus::AddressRecovered => message
+                .get_transponder_hex()
+                .is_some_and(|address| self.contains(&address)),
+            CRCStatus::Corrupt => false,
+        }
+    }
+
+    /// Drop the addresses that were not confirmed within the expiry time.
+    pub fn expire(&mut self) {
+        self.expire_at(get_time_as_f64());
+    }
+
+    fn expire_at(&mut self, now: f64) {
+        let expiry_in_seconds = self.expiry_in_seconds;
+        self.addresses
+            .retain(|_, last_seen| now - *last_seen <= expiry_in_seconds);
+        self.last_expired = now;
+    }
+
+    /// The addresses currently confirmed, sorted.
+    #[must_use]
+    pub fn get_addresses(&self) -> Vec<String> {
+        let now = get_time_as_f64();
+        let mut addresses: Vec<String> = self
+            .addresses
+            .iter()
+            .filter(|(_, last_seen)| now - **last_seen <= self.expiry_in_seconds)
+            .map(|(address, _)| address.clone())
+            .collect();
+        addresses.sort();
+        addresses
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.get_addresses().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use sdre_rust_logging::SetupLogging;
+
+    #[test]
+    fn confirm_and_expire() {
+        "debug".enable_logging();
+
+        let mut filter = ICAOFilter::new(60);
+        let altitude_reply = "2000183859C38D".to_adsb_raw().unwrap();
+        assert!(!filter.accepts(&altitude_reply));
+
+        // a reply does not confirm its own address
+        filter.update_at(&altitude_reply, 1000.0);
+        assert!(!filter.contains_at("4840D6", 1000.0));
+
+        let all_call = "5D4840D6F8740F".to_adsb_raw().unwrap();
+        filter.update_at(&all_call, 1000.0);
+        assert!(filter.contains_at("4840D6", 1030.0));
+        assert!(!filter.contains_at("4840D6", 1061.0));
+
+        filter.expire_at(1061.0);
+        assert!(filter.addresses.is_empty());
+
+        let squitter = "8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap();
+        filter.update(&squitter);
+        assert_eq!(filter.get_addresses(), vec!["4840D6".to_string()]);
+        assert!(filter.accepts(&altitude_reply));
+    }
+}
@@ -87,13 +87,17 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc::{Receiver, Sender};
 
+use super::icao_filter::ICAOFilter;
 use crate::DecodeMessage;
 use crate::decoders::errors::conversion::ConversionError;
 use crate::decoders::helpers::cpr_calculators::Position;
+use crate::decoders::helpers::error_correction::ErrorCorrection;
 use crate::decoders::helpers::time::get_time_as_f64;
 use crate::decoders::json_types::lastknownposition::LastKnownPosition;
 use crate::decoders::json_types::timestamp::TimeStamp;
 use crate::decoders::raw_types::df::DF;
+use crate::decoders::sbs::update_from_sbs;
+use crate::helpers::encode_adsb_sbs_output::SBSLine;
 use crate::{
     ADSBMessage,
     data_structures::airplane::Airplane,
@@ -108,6 +112,8 @@ pub enum ProcessMessageType {
     Raw(AdsbRawMessage),
     Beast(AdsbBeastMessage),
     JSON(JSONMessage),
+    /// A message decoded from an SBS line, merged in to the aircraft instead of replacing it
+    SBS(JSONMessage),
     AircraftJSON(AircraftJSON),
     ADSBMessage(ADSBMessage),
     AsVecU8(Vec<u8>),
@@ -144,7 +150,9 @@ impl fmt::Display for ProcessMessageType {
         match self {
             ProcessMessageType::Raw(raw_message) => write!(f, "{raw_message}"),
             ProcessMessageType::Beast(beast_message) => write!(f, "{beast_message}"),
-            ProcessMessageType::JSON(json_message) => write!(f, "{json_message}"),
+            ProcessMessageType::JSON(json_message) | ProcessMessageType::SBS(json_message) => {
+                write!(f, "{json_message}")
+            }
             ProcessMessageType::AircraftJSON(aircraft_json) => write!(f, "{aircraft_json}"),
             ProcessMessageType::ADSBMessage(adsb_message) => write!(f, "{adsb_message}"),
             ProcessMessageType::AsVecU8(vec_u8) => {
@@ -176,6 +184,16 @@ pub struct Machine {
     pub position: Position,
     #[builder(default = "true")]
     pub use_strict_mode: bool,
+    /// Drop extended squitters and all-call replies that fail the CRC check
+    #[builder(default = "true")]
+    pub reject_corrupt_frames: bool,
+    /// Repair DF17/DF18 frames that fail the CRC check before they are checked. Only frames handed in as bytes or
+    /// strings can be repaired.
+    #[builder(default = "ErrorCorrection::Disabled")]
+    pub error_correction: ErrorCorrection,
+    /// Addresses confirmed by CRC-clean frames, for trusting replies that only carry the address in their parity
+    #[builder(default = "Arc::new(Mutex::new(ICAOFilter::default()))")]
+    pub icao_filter: Arc<Mutex<ICAOFilter>>,
 }
 
 impl MachineBuilder {
@@ -209,6 +227,9 @@ impl Machine {
                 longitude: 0.0,
             },
             use_strict_mode: true,
+            reject_corrupt_frames: true,
+            error_correction: ErrorCorrection::Disabled,
+            icao_filter: Arc::new(Mutex::new(ICAOFilter::default())),
         }
     }
 
@@ -234,6 +255,11 @@ impl Machine {
         self.messages_processed.clone()
     }
 
+    #[must_use]
+    pub fn get_icao_filter_mutex(&self) -> Arc<Mutex<ICAOFilter>> {
+        self.icao_filter.clone()
+    }
+
     #[must_use]
     pub async fn get_airplane_by_hex(&self, transponder_hex: &str) -> Option<Airplane> {
         let airplanes = self.airplanes.lock().await;
@@ -268,6 +294,25 @@ impl Machine {
         airplanes_vec
     }
 
+    /// The SBS line for a frame, from the state of its airplane. Call it after the frame has been processed.
+    /// Returns `None` if the airplane isn't known or the frame has no SBS transmission type.
+    pub async fn get_sbs_line(&self, message: &AdsbRawMessage) -> Option<SBSLine> {
+        let transponder_hex = message.get_transponder_hex()?;
+        let airplanes = self.airplanes.lock().await;
+
+        SBSLine::from_raw(message, airplanes.get(&transponder_hex)?)
+    }
+
+    /// SBS lines for every airplane, for a client that just connected
+    pub async fn get_sbs_lines(&self) -> Vec<SBSLine> {
+        let airplanes = self.airplanes.lock().await;
+
+        airplanes
+            .values()
+            .flat_map(SBSLine::from_aircraft)
+            .collect()
+    }
+
     pub async fn process_adsb_message(&mut self) {
         if self.verify_position_is_not_default().is_err() {
             warn!(
@@ -288,57 +333,29 @@ impl Machine {
                 ProcessMessageType::JSON(json_message) => {
                     self.process_json_message(json_message).await;
                 }
+                ProcessMessageType::SBS(sbs_message) => {
+                    self.process_sbs_message(sbs_message).await;
+                }
                 ProcessMessageType::AircraftJSON(aircraft_json) => {
                     self.process_aircraft_json(aircraft_json).await;
                 }
-                ProcessMessageType::ADSBMessage(adsb_message) => match adsb_message {
-                    ADSBMessage::AdsbRawMessage(raw_message) => {
-                        result = self.process_aircraft_raw(raw_message).await;
-                    }
-                    ADSBMessage::AdsbBeastMessage(beast_message) => {
-                        result = self.process_aircraft_beast(beast_message).await;
-                    }
-                    ADSBMessage::AircraftJSON(json_message) => {
-                        self.process_aircraft_json(json_message).await;
-                    }
-                    ADSBMessage::JSONMessage(json_message) => {
-                        self.process_json_message(json_message).await;
-                    }
-                },
+                ProcessMessageType::ADSBMessage(adsb_message) => {
+                    result = self.process_decoded_message(adsb_message).await;
+                }
                 ProcessMessageType::AsVecU8(vec_u8) => {
-                    if let Ok(message) = vec_u8.decode_message() {
-                        match message {
-                            ADSBMessage::AdsbRawMessage(raw_message) => {
-                                result = self.process_aircraft_raw(raw_message).await;
-                            }
-                            ADSBMessage::AdsbBeastMessage(beast_message) => {
-                                result = self.process_aircraft_beast(beast_message).await;
-                            }
-                            ADSBMessage::AircraftJSON(json_message) => {
-                                self.process_aircraft_json(json_message).await;
-                            }
-                            ADSBMessage::JSONMessage(json_message) => {
-                                self.process_json_message(json_message).await;
-                            }
-                        }
+                    if let Ok(message) = vec_u8.decode_message_with_error_correction(
+                        self.error_correction,
+                        self.reject_corrupt_frames,
+                    ) {
+                        result = self.process_decoded_message(message).await;
                     }
                 }
                 ProcessMessageType::AsString(string) => {
-                    if let Ok(message) = string.decode_message() {
-                        match message {
-                            ADSBMessage::AdsbRawMessage(raw_message) => {
-                                result = self.process_aircraft_raw(raw_message).await;
-                            }
-                            ADSBMessage::AdsbBeastMessage(beast_message) => {
-                                result = self.process_aircraft_beast(beast_message).await;
-                            }
-                            ADSBMessage::AircraftJSON(json_message) => {
-                                self.process_aircraft_json(json_message).await;
-                            }
-                            ADSBMessage::JSONMessage(json_message) => {
-                                self.process_json_message(json_message).await;
-                            }
-                        }
+                    if let Ok(message) = string.decode_message_with_error_correction(
+                        self.error_correction,
+                        self.reject_corrupt_frames,
+                    ) {
+                        result = self.process_decoded_message(message).await;
                     }
                 }
             }
@@ -353,6 +370,33 @@ impl Machine {
         }
     }
 
+    async fn process_decoded_message(
+        &mut self,
+        message: ADSBMessage,
+    ) -> Result<(), ConversionError> {
+        match message {
+            ADSBMessage::AdsbRawMessage(raw_message) => {
+                return self.process_aircraft_raw(raw_message).await;
+            }
+            ADSBMessage::AdsbBeastMessage(beast_message) => {
+                return self.process_aircraft_beast(beast_message).await;
+            }
+            ADSBMessage::AircraftJSON(json_message) => {
+                self.process_aircraft_json(json_message).await;
+            }
+            ADSBMessage::JSONMessage(json_message) => {
+                self.process_json_message(json_message).await;
+            }
+            ADSBMessage::SBSMessage(sbs_message) => {
+                self.process_sbs_message(sbs_message).await;
+            }
+            // Mode A/C replies carry no address, status frames describe the receiver
+            ADSBMessage::ModeACMessage(_) | ADSBMessage::BeastStatusMessage(_) => {}
+        }
+
+        Ok(())
+    }
+
     pub async fn process_json_message(&mut self, message: JSONMessage) {
         // lock the mutex and get a mutable reference to the hashmap
         let mut airplanes = self.airplanes.lock().await;
@@ -378,47 +422,91 @@ impl Machine {
         }
     }
 
+    /// Process a message decoded from an SBS line. Only the fields the line carried are updated, so SBS feeds
+    /// can be merged with the other inputs. If the airplane does not exist, it is created.
+    pub async fn process_sbs_message(&mut self, message: JSONMessage) {
+        let mut airplanes = self.airplanes.lock().await;
+
+        match airplanes.entry(message.transponder_hex.get_transponder_hex_as_string()) {
+            Entry::Occupied(mut airplane) => {
+                debug!(
+                    "Updating airplane {} from SBS",
+                    airplane.get().transponder_hex
+                );
+                update_from_sbs(airplane.get_mut(), &message);
+            }
+            Entry::Vacant(airplane) => {
+                debug!("Creating airplane {} from SBS", message.transponder_hex);
+                airplane.insert(message);
+            }
+        }
+    }
+
     pub async fn process_aircraft_json(&mut self, message: AircraftJSON) {
         for aircraft in message.aircraft {
             self.process_json_message(aircraft).await;
         }
     }
 
-    /// Process a raw ADS-B message. The message is decoded and the state of the airplane is updated.
-    /// If the airplane does not exist, it is created.
+    /// Process a raw ADS-B or Mode S message. The message is decoded and the state of the airplane is updated.
+    /// If the airplane does not exist, it is created from a DF11, DF17 or DF18. Other replies only carry the address
+    /// XOR-ed into their parity, where a corrupted frame yields a random address, so they only update known airplanes
+    /// or ones whose address is in the `icao_filter`.
     /// If the airplane exists, it is updated.
     /// # Errors
-    /// If the message cannot be decoded, an error is returned.
+    /// If the message cannot be decoded, or fails the CRC check while `reject_corrupt_frames` is set, an error is returned.
     pub async fn process_aircraft_raw(
         &mut self,
         message: AdsbRawMessage,
     ) -> Result<(), ConversionError> {
-        if let DF::ADSB(adsb) = &message.df {
-            let mut airplanes = self.airplanes.lock().await;
-
-            let transponderhex = adsb.icao.to_string();
-
-            match airplanes.entry(transponderhex.clone()) {
-                Entry::Occupied(mut airplane) => {
-                    return airplane.get_mut().update_from_df(
-                        &message.df,
-                        &self.position,
-                        &self.use_strict_mode,
-                    );
+        if self.reject_corrupt_frames && message.crc_status().is_corrupt() {
+            return Err(ConversionError::CorruptFrame {
+                syndrome: format!("{:06X}", message.crc),
+            });
+        }
+
+        let Some(transponderhex) = message.get_transponder_hex() else {
+            return Ok(());
+        };
+
+        let address_is_confirmed = {
+            let mut icao_filter = self.icao_filter.lock().await;
+            icao_filter.update(&message);
+            icao_filter.contains(&transponderhex)
+        };
+
+        let mut airplanes = self.airplanes.lock().await;
+
+        match airplanes.entry(transponderhex.clone()) {
+            Entry::Occupied(mut airplane) => {
+                return airplane.get_mut().update_from_df(
+                    &message.df,
+                    &self.position,
+                    &self.use_strict_mode,
+                );
+            }
+            Entry::Vacant(airplane) => {
+                if !address_is_confirmed
+                    && !matches!(
+                        message.df,
+                        DF::ADSB(_) | DF::TisB { .. } | DF::AllCallReply { .. }
+                    )
+                {
+                    return Ok(());
                 }
-                Entry::Vacant(airplane) => {
-                    let mut new_airplane = Airplane::new(transponderhex);
-                    match new_airplane.update_from_df(
-                        &message.df,
-                        &self.position,
-                        &self.use_strict_mode,
-                    ) {
-                        Ok(()) => {
-                            airplane.insert(new_airplane);
-                        }
-                        Err(e) => {
-                            return Err(e);
-                        }
+
+                let mut new_airplane = Airplane::new(transponderhex);
+
+                match new_airplane.update_from_df(
+                    &message.df,
+                    &self.position,
+                    &self.use_strict_mode,
+                ) {
+                    Ok(()) => {
+                        airplane.insert(new_airplane);
+                    }
+                    Err(e) => {
+                        return Err(e);
                     }
                 }
             }
@@ -537,3 +625,144 @@ pub async fn expire_planes<S: ::std::hash::BuildHasher>(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::json_types::messagetype::MessageType;
+    use crate::decoders::raw::NewAdsbRawMessage;
+    use crate::decoders::raw_types::helper_functions::modes_checksum;
+    use sdre_rust_logging::SetupLogging;
+
+    /// A reply with `address` XOR-ed in to the parity of `fields`
+    fn get_reply(fields: &[u8], address: u32) -> AdsbRawMessage {
+        let mut frame = fields.to_vec();
+        frame.extend_from_slice(&[0, 0, 0]);
+        let parity = modes_checksum(&frame, frame.len() * 8).unwrap() ^ address;
+        let parity_start = frame.len() - 3;
+        frame[parity_start..].copy_from_slice(&parity.to_be_bytes()[1..]);
+        AdsbRawMessage::from_bytes(&frame).unwrap()
+    }
+
+    #[tokio::test]
+    async fn replies_need_a_confirmed_address() {
+        "debug".enable_logging();
+
+        // DF4 altitude reply, and DF20 with the BDS 2,0 identification of KLM1023
+        let altitude_reply = [0x20, 0x00, 0x18, 0x38];
+        let identification_reply = [
+            0xA0, 0x00, 0x18, 0x38, 0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x2C, 0xE0,
+        ];
+
+        let mut machine = Machine::new();
+        for address in [0x00AB_CDEF, 0x0048_40D6] {
+            machine
+                .process_aircraft_raw(get_reply(&altitude_reply, address))
+                .await
+                .unwrap();
+            machine
+                .process_aircraft_raw(get_reply(&identification_reply, address))
+                .await
+                .unwrap();
+        }
+        assert!(machine.get_airplane_by_hex("ABCDEF").await.is_none());
+        assert!(machine.get_airplane_by_hex("4840D6").await.is_none());
+
+        // a clean all-call reply confirms the address, then the replies are taken
+        machine
+            .process_aircraft_raw("5D4840D6F8740F".to_adsb_raw().unwrap())
+            .await
+            .unwrap();
+        machine
+            .process_aircraft_raw(get_reply(&identification_reply, 0x0048_40D6))
+            .await
+            .unwrap();
+        let airplane = machine.get_airplane_by_hex("4840D6").await.unwrap();
+        assert_eq!(
+            airplane.calculated_best_flight_id.unwrap().to_string(),
+            "KLM1023"
+        );
+
+        // the same for an extended squitter, on a machine that has not seen the all-call reply
+        let mut machine = Machine::new();
+        machine
+            .process_aircraft_raw("8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap())
+            .await
+            .unwrap();
+        machine
+            .process_aircraft_raw(get_reply(&altitude_reply, 0x0048_40D6))
+            .await
+            .unwrap();
+        let airplane = machine.get_airplane_by_hex("4840D6").await.unwrap();
+        assert!(airplane.barometric_altitude.is_some());
+        assert!(machine.get_airplane_by_hex("ABCDEF").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn control_field_addresses_and_message_types() {
+        "debug".enable_logging();
+
+        let mut machine = Machine::new();
+        machine
+            .process_aircraft_raw("8D4840D6202CC371C32CE0576098".to_adsb_raw().unwrap())
+            .await
+            .unwrap();
+
+        // DF18 identifications of KLM1023, a reply with address 0 has clean parity
+        for (control_field, address) in [
+            (1, [0x48, 0x40, 0xD6]),
+            (2, [0xA1, 0xB2, 0xC3]),
+            (5, [0xA1, 0xB2, 0xC4]),
+            (6, [0xA1, 0xB2, 0xC5]),
+        ] {
+            let mut fields = vec![0x90 | control_field];
+            fields.extend_from_slice(&address);
+            fields.extend_from_slice(&[0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x2C, 0xE0]);
+            machine
+                .process_aircraft_raw(get_reply(&fields, 0))
+                .await
+                .unwrap();
+        }
+
+        // the non-ICAO address is kept apart from the aircraft with the same 24 bits
+        let airplane = machine.get_airplane_by_hex("4840D6").await.unwrap();
+        assert_eq!(airplane.message_type, MessageType::ADSBICAO);
+        let airplane = machine.get_airplane_by_hex("~4840D6").await.unwrap();
+        assert_eq!(airplane.message_type, MessageType::ADSBOTHER);
+
+        for (transponder_hex, message_type) in [
+            ("A1B2C3", MessageType::ADSBICAOSECONDARYSURVEILLANCE),
+            ("~A1B2C4", MessageType::ADSBOTHERSECONDARYSURVEILLANCE),
+            ("A1B2C5", MessageType::ADSBICAOREBROADCAST),
+        ] {
+            let airplane = machine.get_airplane_by_hex(transponder_hex).await.unwrap();
+            assert_eq!(airplane.message_type, message_type);
+        }
+        assert_eq!(machine.get_airplanes().await.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn corrupt_squitters_are_rejected() {
+        "debug".enable_logging();
+
+        // one bit flipped in the ME field
+        let corrupt = "8D4840D6202CC371C32CE1576098".to_adsb_raw().unwrap();
+
+        let mut machine = Machine::new();
+        assert!(machine.process_aircraft_raw(corrupt.clone()).await.is_err());
+        assert!(machine.get_airplane_by_hex("4840D6").await.is_none());
+        // and it doesn't confirm the address either
+        machine
+            .process_aircraft_raw(get_reply(&[0x20, 0x00, 0x18, 0x38], 0x0048_40D6))
+            .await
+            .unwrap();
+        assert!(machine.get_airplane_by_hex("4840D6").await.is_none());
+
+        let mut machine = MachineBuilder::default()
+            .reject_corrupt_frames(false)
+            .build()
+            .unwrap();
+        machine.process_aircraft_raw(corrupt).await.unwrap();
+        assert!(machine.get_airplane_by_hex("4840D6").await.is_some());
+    }
+}